@@ -0,0 +1,560 @@
+//! `harbor daemon`: the downloads watcher as a headless background service,
+//! for machines without a tray.
+
+use anyhow::{bail, Context, Result};
+use harbor_core::service::{ServiceEvent, WatcherService};
+use serde_json::json;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long a stop waits for the pass in progress, so a file being moved is
+/// never cut off. Stays below systemd's default `TimeoutStopSec` of 90 s.
+const STOP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// File name of the generated systemd user unit
+pub const UNIT_NAME: &str = "harbor.service";
+
+#[derive(clap::Args, Clone)]
+pub struct DaemonArgs {
+    #[arg(long, default_value = "harbor.downloads.yaml")]
+    pub config: String,
+    /// Seconds between passes
+    #[arg(long = "interval", default_value_t = 5)]
+    pub interval_secs: u64,
+    /// Activity log to append moves to (defaults to the shared Harbor log)
+    #[arg(long)]
+    pub log: Option<String>,
+    /// Also write events as JSON Lines to this file; reopened on SIGHUP
+    #[arg(long)]
+    pub log_file: Option<PathBuf>,
+    /// Holds the daemon's pid and keeps a second daemon from starting
+    /// (defaults to $XDG_RUNTIME_DIR/harbor/daemon.pid)
+    #[arg(long)]
+    pub pid_file: Option<PathBuf>,
+}
+
+impl DaemonArgs {
+    fn pid_path(&self) -> PathBuf {
+        self.pid_file.clone().unwrap_or_else(default_pid_file)
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/harbor/daemon.pid`, or the Harbor data folder when
+/// there is no runtime directory.
+pub fn default_pid_file() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|v| !v.is_empty())
+        .map(|dir| PathBuf::from(dir).join("harbor"))
+        .unwrap_or_else(harbor_core::paths::harbor_data_dir)
+        .join("daemon.pid")
+}
+
+/// A locked file holding our pid. Only one daemon can hold the lock on a
+/// given path; the file is removed when this is dropped.
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+    _file: File,
+}
+
+impl PidFile {
+    pub fn acquire(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        loop {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(path)
+                .with_context(|| format!("failed to open pid file {}", path.display()))?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(std::fs::TryLockError::WouldBlock) => {
+                    let mut pid = String::new();
+                    let _ = file.read_to_string(&mut pid);
+                    bail!(
+                        "harbor daemon is already running (pid {}, {})",
+                        pid.trim(),
+                        path.display()
+                    );
+                }
+                Err(std::fs::TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| format!("failed to lock {}", path.display()))
+                }
+            }
+            // The previous daemon may have removed the file between our open
+            // and lock; then the lock is on an orphan and we start over.
+            if !is_same_file(&file, path) {
+                continue;
+            }
+            file.set_len(0)?;
+            file.rewind()?;
+            writeln!(file, "{}", std::process::id())?;
+            file.sync_all()?;
+            return Ok(Self {
+                path: path.to_path_buf(),
+                _file: file,
+            });
+        }
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), std::fs::metadata(path)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, _path: &Path) -> bool {
+    true
+}
+
+/// Flags set by the signal handlers and polled by [`run`].
+#[derive(Debug, Clone, Default)]
+pub struct Signals {
+    /// SIGTERM or SIGINT: finish the pass in progress, then exit
+    pub stop: Arc<AtomicBool>,
+    /// SIGHUP: reload the config and reopen the log file
+    pub reload: Arc<AtomicBool>,
+}
+
+impl Signals {
+    #[cfg(unix)]
+    pub fn install() -> Result<Self> {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+        use signal_hook::flag;
+        let signals = Self::default();
+        for signal in [SIGTERM, SIGINT] {
+            // A second signal while the last move finishes exits right away
+            flag::register_conditional_shutdown(signal, 1, Arc::clone(&signals.stop))?;
+            flag::register(signal, Arc::clone(&signals.stop))?;
+        }
+        flag::register(SIGHUP, Arc::clone(&signals.reload))?;
+        Ok(signals)
+    }
+
+    #[cfg(not(unix))]
+    pub fn install() -> Result<Self> {
+        bail!("unix only");
+    }
+}
+
+/// The optional JSON Lines log behind `--log-file`.
+struct EventLog {
+    path: Option<PathBuf>,
+    file: Option<File>,
+}
+
+impl EventLog {
+    fn open(path: Option<PathBuf>) -> Result<Self> {
+        let mut log = Self { path, file: None };
+        log.reopen()?;
+        Ok(log)
+    }
+
+    /// Opens the file again, e.g. after logrotate moved it away.
+    fn reopen(&mut self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open log file {}", path.display()))?;
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn write(&mut self, mut record: serde_json::Value) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        record["ts"] = json!(chrono::Utc::now().to_rfc3339());
+        if let Err(e) = writeln!(file, "{}", record) {
+            eprintln!("failed to write log file: {}", e);
+        }
+    }
+}
+
+/// Log records for `event`, one per moved or failed file.
+fn event_records(event: &ServiceEvent) -> Vec<serde_json::Value> {
+    match event {
+        ServiceEvent::Organized(actions) => actions
+            .iter()
+            .map(|(from, to, rule, _, _)| {
+                json!({
+                    "level": "info",
+                    "event": "moved",
+                    "source": from,
+                    "dest": to,
+                    "rule": rule,
+                })
+            })
+            .collect(),
+        ServiceEvent::Failed(failures) => failures
+            .iter()
+            .map(|f| {
+                json!({
+                    "level": if f.retry_in.is_some() { "warn" } else { "error" },
+                    "event": "move_failed",
+                    "path": f.path,
+                    "rule": f.rule,
+                    "message": f.message,
+                    "attempts": f.attempts,
+                    "retry_in_secs": f.retry_in.map(|d| d.as_secs()),
+                })
+            })
+            .collect(),
+        ServiceEvent::ConfigReloaded => vec![json!({"level": "info", "event": "config_reloaded"})],
+        ServiceEvent::ConfigError(message) => {
+            vec![json!({"level": "error", "event": "config_error", "message": message})]
+        }
+        ServiceEvent::RecordError(message) => {
+            vec![json!({"level": "error", "event": "record_error", "message": message})]
+        }
+        ServiceEvent::StateChanged(state) => {
+            vec![json!({"level": "info", "event": "state_changed", "state": state})]
+        }
+        // Like the console output, only the first failure of a streak
+        ServiceEvent::Scanned(health) if health.consecutive_failures == 1 => vec![json!({
+            "level": "error",
+            "event": "scan_failed",
+            "message": health.last_error,
+        })],
+        ServiceEvent::Scanned(_) => Vec::new(),
+    }
+}
+
+/// Runs the watcher until `signals.stop` is set. The pid file is held for
+/// the whole run; a stop waits up to [`STOP_TIMEOUT`] for the pass in
+/// progress to finish.
+pub fn run(args: &DaemonArgs, signals: &Signals) -> Result<()> {
+    let _pid_file = PidFile::acquire(&args.pid_path())?;
+    let mut log = EventLog::open(args.log_file.clone())?;
+    let cfg = harbor_core::downloads::load_downloads_config(&args.config)?;
+    let service = WatcherService::new(cfg)
+        .with_config_path(&args.config)
+        .with_log_path(super::open_activity_log(args.log.clone()).path())
+        .with_interval(args.interval_secs);
+    let events = service.subscribe();
+    let handle = |log: &mut EventLog, event: &ServiceEvent| {
+        super::print_service_event(event, &args.config);
+        for record in event_records(event) {
+            log.write(record);
+        }
+    };
+
+    log.write(json!({
+        "level": "info",
+        "event": "started",
+        "pid": std::process::id(),
+        "config": args.config,
+    }));
+    service.start()?;
+    while !signals.stop.load(Ordering::SeqCst) {
+        if signals.reload.swap(false, Ordering::SeqCst) {
+            if let Err(e) = log.reopen() {
+                eprintln!("{:#}", e);
+            }
+            // Success is reported through the ConfigReloaded event
+            if let Err(e) = service.reload() {
+                handle(&mut log, &ServiceEvent::ConfigError(format!("{:#}", e)));
+            }
+        }
+        match events.recv_timeout(Duration::from_millis(200)) {
+            Ok(event) => handle(&mut log, &event),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    log.write(json!({"level": "info", "event": "stopping"}));
+    let exited = service.stop_within(STOP_TIMEOUT);
+    for event in events.try_iter() {
+        handle(&mut log, &event);
+    }
+    if !exited {
+        bail!(
+            "the watcher did not finish within {}s",
+            STOP_TIMEOUT.as_secs()
+        );
+    }
+    Ok(())
+}
+
+/// A systemd user unit that runs `exe daemon` with `args`. Paths in `args`
+/// should be absolute, since user units start in the home directory.
+pub fn systemd_unit(exe: &Path, args: &DaemonArgs) -> String {
+    let mut command = vec![
+        exe.display().to_string(),
+        "daemon".into(),
+        "--config".into(),
+        args.config.clone(),
+        "--interval".into(),
+        args.interval_secs.to_string(),
+    ];
+    if let Some(log) = &args.log {
+        command.extend(["--log".into(), log.clone()]);
+    }
+    if let Some(path) = &args.log_file {
+        command.extend(["--log-file".into(), path.display().to_string()]);
+    }
+    if let Some(path) = &args.pid_file {
+        command.extend(["--pid-file".into(), path.display().to_string()]);
+    }
+    let exec_start = command
+        .iter()
+        .map(|arg| systemd_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "[Unit]
+Description=Harbor downloads organizer
+
+[Service]
+Type=simple
+ExecStart={exec_start}
+ExecReload=/bin/kill -HUP $MAINPID
+Restart=on-failure
+RestartSec=5
+TimeoutStopSec={timeout}
+
+[Install]
+WantedBy=default.target
+",
+        timeout = STOP_TIMEOUT.as_secs() + 10,
+    )
+}
+
+/// Quotes `arg` for an `ExecStart=` line; `%` is a specifier there.
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+        format!("\"{}\"", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// `$XDG_CONFIG_HOME/systemd/user`, or `~/.config/systemd/user`.
+pub fn systemd_user_dir() -> Result<PathBuf> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => harbor_core::paths::home_dir()
+            .context("cannot find the home directory")?
+            .join(".config"),
+    };
+    Ok(config_home.join("systemd").join("user"))
+}
+
+/// Writes the unit for `args` to `output` (the systemd user folder if
+/// `None`), refusing to replace an existing file unless `force`.
+pub fn install_systemd(args: &DaemonArgs, output: Option<PathBuf>, force: bool) -> Result<PathBuf> {
+    let config = std::fs::canonicalize(&args.config)
+        .with_context(|| format!("config not found: {}", args.config))?;
+    let absolute = |p: &Path| std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf());
+    let args = DaemonArgs {
+        config: config.display().to_string(),
+        log: args
+            .log
+            .as_deref()
+            .map(|p| absolute(Path::new(p)).display().to_string()),
+        log_file: args.log_file.as_deref().map(absolute),
+        pid_file: args.pid_file.as_deref().map(absolute),
+        ..args.clone()
+    };
+    let exe = std::env::current_exe().context("cannot find the harbor executable")?;
+
+    let path = match output {
+        Some(path) => path,
+        None => systemd_user_dir()?.join(UNIT_NAME),
+    };
+    if path.exists() && !force {
+        bail!(
+            "{} already exists (use --force to replace it)",
+            path.display()
+        );
+    }
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, systemd_unit(&exe, &args))
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use harbor_core::downloads::RuleFailure;
+    use tempfile::TempDir;
+
+    fn daemon_args(temp: &TempDir) -> DaemonArgs {
+        let dl_dir = temp.path().join("DL");
+        std::fs::create_dir_all(&dl_dir).unwrap();
+        let config = temp.path().join("harbor.downloads.yaml");
+        std::fs::write(
+            &config,
+            format!(
+                "download_dir: {:?}\nrules: []\n",
+                dl_dir.display().to_string()
+            ),
+        )
+        .unwrap();
+        DaemonArgs {
+            config: config.display().to_string(),
+            interval_secs: 1,
+            log: Some(temp.path().join("activity.log").display().to_string()),
+            log_file: Some(temp.path().join("daemon.log")),
+            pid_file: Some(temp.path().join("run").join("daemon.pid")),
+        }
+    }
+
+    #[test]
+    fn test_pid_file_single_instance() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("daemon.pid");
+        let held = PidFile::acquire(&path).unwrap();
+        let pid = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(pid.trim(), std::process::id().to_string());
+
+        let err = PidFile::acquire(&path).unwrap_err().to_string();
+        assert!(err.contains("already running"), "{}", err);
+        assert!(err.contains(pid.trim()), "{}", err);
+
+        drop(held);
+        assert!(!path.exists());
+        let _again = PidFile::acquire(&path).unwrap();
+    }
+
+    #[test]
+    fn test_run_reloads_and_stops() {
+        let temp = TempDir::new().unwrap();
+        let args = daemon_args(&temp);
+        let signals = Signals::default();
+        let pid_path = args.pid_file.clone().unwrap();
+
+        let runner = {
+            let (args, signals) = (args.clone(), signals.clone());
+            std::thread::spawn(move || run(&args, &signals))
+        };
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        // The file exists before it is locked; the pid is written after
+        let written = || std::fs::read_to_string(&pid_path).is_ok_and(|p| !p.is_empty());
+        while !written() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(PidFile::acquire(&pid_path).is_err());
+
+        signals.reload.store(true, Ordering::SeqCst);
+        while signals.reload.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        signals.stop.store(true, Ordering::SeqCst);
+        runner.join().unwrap().unwrap();
+        assert!(!pid_path.exists());
+
+        let log = std::fs::read_to_string(args.log_file.unwrap()).unwrap();
+        let events: Vec<String> = log
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["event"].to_string())
+            .collect();
+        assert_eq!(events.first().map(String::as_str), Some("\"started\""));
+        assert!(events.iter().any(|e| e == "\"config_reloaded\""));
+        assert_eq!(events.last().map(String::as_str), Some("\"state_changed\""));
+    }
+
+    #[test]
+    fn test_event_records() {
+        let failed = ServiceEvent::Failed(vec![RuleFailure {
+            path: PathBuf::from("b.png"),
+            rule: "Images".into(),
+            rule_id: "images".into(),
+            message: "denied".into(),
+            attempts: 3,
+            retry_in: None,
+        }]);
+        let records = event_records(&failed);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["level"], "error");
+        assert_eq!(records[0]["event"], "move_failed");
+        assert_eq!(records[0]["attempts"], 3);
+
+        let moved = ServiceEvent::Organized(vec![
+            (
+                PathBuf::from("a.pdf"),
+                PathBuf::from("Docs/a.pdf"),
+                "Docs".into(),
+                None,
+                "docs".into(),
+            ),
+            (
+                PathBuf::from("c.pdf"),
+                PathBuf::from("Docs/c.pdf"),
+                "Docs".into(),
+                None,
+                "docs".into(),
+            ),
+        ]);
+        let records = event_records(&moved);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["dest"], "Docs/c.pdf");
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let args = DaemonArgs {
+            config: "/home/dev/My Config/harbor.downloads.yaml".into(),
+            interval_secs: 10,
+            log: None,
+            log_file: Some(PathBuf::from("/var/log/harbor/100%.log")),
+            pid_file: None,
+        };
+        let unit = systemd_unit(Path::new("/usr/local/bin/harbor"), &args);
+        assert!(unit.contains(
+            "ExecStart=/usr/local/bin/harbor daemon \
+             --config \"/home/dev/My Config/harbor.downloads.yaml\" \
+             --interval 10 --log-file /var/log/harbor/100%%.log\n"
+        ));
+        assert!(unit.contains("ExecReload=/bin/kill -HUP $MAINPID"));
+        assert!(unit.contains("WantedBy=default.target"));
+
+        let temp = TempDir::new().unwrap();
+        let args = DaemonArgs {
+            log: None,
+            log_file: None,
+            pid_file: None,
+            ..daemon_args(&temp)
+        };
+        let output = temp.path().join("units").join(UNIT_NAME);
+        install_systemd(&args, Some(output.clone()), false).unwrap();
+        let unit = std::fs::read_to_string(&output).unwrap();
+        let config = std::fs::canonicalize(&args.config).unwrap();
+        assert!(unit.contains(&format!("--config {}", config.display())));
+        assert!(install_systemd(&args, Some(output.clone()), false).is_err());
+        install_systemd(&args, Some(output), true).unwrap();
+    }
+}
@@ -1,11 +1,15 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use harbor_core::rule_stats::RuleStatsStore;
+use harbor_core::watcher_health::WatcherHealth;
 use std::path::PathBuf;
 #[cfg(windows)]
 use winreg::enums::HKEY_CURRENT_USER;
 #[cfg(windows)]
 use winreg::RegKey;
 
+mod daemon;
+
 #[derive(Parser)]
 #[command(name = "harbor")]
 #[command(version = env!("CARGO_PKG_VERSION"))]
@@ -23,12 +27,44 @@ enum Commands {
     DownloadsOrganize {
         #[arg(default_value = "harbor.downloads.yaml")]
         path: String,
+        /// Activity log to append moves to (defaults to the shared Harbor log)
+        #[arg(long)]
+        log: Option<String>,
     },
     DownloadsWatch {
         #[arg(default_value = "harbor.downloads.yaml")]
         path: String,
         #[arg(default_value_t = 5)]
         interval_secs: u64,
+        /// Activity log to append moves to (defaults to the shared Harbor log)
+        #[arg(long)]
+        log: Option<String>,
+    },
+    /// Organize files or folders (one level deep) with the downloads rules
+    Organize {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
+        /// Activity log to append moves to (defaults to the shared Harbor log)
+        #[arg(long)]
+        log: Option<String>,
+    },
+    DownloadsValidate {
+        #[arg(default_value = "harbor.downloads.yaml")]
+        path: String,
+    },
+    /// Pause the downloads watcher for a while; it resumes on its own
+    DownloadsPause {
+        /// How long: minutes (15m), hours (1h) or "tomorrow" (8:00)
+        duration: harbor_core::service::SnoozeFor,
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
+    },
+    /// End a pause started with downloads-pause
+    DownloadsResume {
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
     },
     Validate {
         #[arg(default_value = "harbor.config.yaml")]
@@ -53,6 +89,12 @@ enum Commands {
     Status {
         #[arg(default_value = "harbor_state.json")]
         state_path: String,
+        /// Report on the downloads watcher instead of services
+        #[arg(long)]
+        downloads: bool,
+        /// Downloads config the watcher was started with
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
     },
     Logs {
         service: String,
@@ -66,6 +108,239 @@ enum Commands {
         source: Option<String>,
     },
     TrayUninstall,
+    /// Print the JSON Schema for a config file format
+    Schema {
+        #[arg(value_enum)]
+        kind: SchemaArg,
+    },
+    /// List recorded moves, newest first
+    #[command(args_conflicts_with_subcommands = true)]
+    Activity {
+        #[command(subcommand)]
+        action: Option<Box<ActivityAction>>,
+        #[command(flatten)]
+        filter: ActivityFilterArgs,
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+        /// Print records as JSON Lines
+        #[arg(long)]
+        json: bool,
+    },
+    /// Inspect the downloads rules
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+    /// List or switch rule profiles
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+    /// Run the downloads watcher in the background: SIGHUP reloads the
+    /// config, SIGTERM or SIGINT stops after the file being moved
+    #[command(args_conflicts_with_subcommands = true)]
+    Daemon {
+        #[command(subcommand)]
+        action: Option<DaemonAction>,
+        #[command(flatten)]
+        args: daemon::DaemonArgs,
+    },
+    /// Summarize recorded moves
+    Stats {
+        /// Length of the per-day series
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+        /// Activity log to read (defaults to the shared Harbor log)
+        #[arg(long)]
+        log: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Write a systemd user unit that runs the daemon
+    InstallSystemd {
+        #[command(flatten)]
+        args: daemon::DaemonArgs,
+        /// Unit file to write (defaults to ~/.config/systemd/user/harbor.service)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Replace an existing unit file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileAction {
+    /// Show every profile, marking the active one
+    List {
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
+    },
+    /// Make a profile active; a running watcher picks it up on its next poll
+    Use {
+        name: String,
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum RulesAction {
+    /// List rules with how often they matched
+    List {
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
+    },
+    /// Show how each rule judges a file, in order
+    Explain {
+        file: String,
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
+    },
+    /// Write rules as a shareable pack (all rules if none are named)
+    Export {
+        names: Vec<String>,
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
+        /// File to write (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Merge a rule pack into the config
+    Import {
+        pack: String,
+        #[arg(long, default_value = "harbor.downloads.yaml")]
+        config: String,
+        /// What to do with rules whose name already exists
+        #[arg(long, value_enum, default_value_t = CollisionArg::Skip)]
+        on_collision: CollisionArg,
+        /// Move target folders from the exporter's download folder to ours
+        #[arg(long)]
+        rebase: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CollisionArg {
+    Skip,
+    Rename,
+    Replace,
+}
+
+impl From<CollisionArg> for harbor_core::rules::Collision {
+    fn from(arg: CollisionArg) -> Self {
+        match arg {
+            CollisionArg::Skip => Self::Skip,
+            CollisionArg::Rename => Self::Rename,
+            CollisionArg::Replace => Self::Replace,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum ActivityAction {
+    /// Write the full history, oldest first
+    Export {
+        #[arg(long, value_enum)]
+        format: ExportArg,
+        #[command(flatten)]
+        filter: ActivityFilterArgs,
+        /// File to write (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportArg {
+    Csv,
+    Json,
+}
+
+impl From<ExportArg> for harbor_core::activity_store::ExportFormat {
+    fn from(arg: ExportArg) -> Self {
+        match arg {
+            ExportArg::Csv => Self::Csv,
+            ExportArg::Json => Self::Json,
+        }
+    }
+}
+
+#[derive(clap::Args, Clone, Default)]
+struct ActivityFilterArgs {
+    #[arg(long)]
+    rule: Option<String>,
+    /// File extension, with or without the dot
+    #[arg(long)]
+    ext: Option<String>,
+    /// YYYY-MM-DD or RFC 3339
+    #[arg(long)]
+    since: Option<String>,
+    /// YYYY-MM-DD (inclusive) or RFC 3339
+    #[arg(long)]
+    until: Option<String>,
+    #[arg(long, value_enum)]
+    outcome: Option<OutcomeArg>,
+    /// Substring of the file name or either path
+    #[arg(long)]
+    search: Option<String>,
+    /// Activity log to read (defaults to the shared Harbor log)
+    #[arg(long)]
+    log: Option<String>,
+}
+
+impl ActivityFilterArgs {
+    fn query(&self) -> Result<harbor_core::activity_store::ActivityQuery> {
+        use harbor_core::activity_store::{parse_since, parse_until};
+        Ok(harbor_core::activity_store::ActivityQuery {
+            rule: self.rule.clone(),
+            extension: self.ext.clone(),
+            since: self.since.as_deref().map(parse_since).transpose()?,
+            until: self.until.as_deref().map(parse_until).transpose()?,
+            outcome: self.outcome.map(Into::into),
+            text: self.search.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutcomeArg {
+    Success,
+    Failed,
+}
+
+impl From<OutcomeArg> for harbor_core::activity::Outcome {
+    fn from(arg: OutcomeArg) -> Self {
+        match arg {
+            OutcomeArg::Success => Self::Success,
+            OutcomeArg::Failed => Self::Failed,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SchemaArg {
+    /// harbor.downloads.yaml
+    Downloads,
+    /// harbor.config.yaml
+    Workspace,
+}
+
+impl From<SchemaArg> for harbor_core::schema::SchemaKind {
+    fn from(arg: SchemaArg) -> Self {
+        match arg {
+            SchemaArg::Downloads => Self::Downloads,
+            SchemaArg::Workspace => Self::Workspace,
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -75,40 +350,108 @@ fn main() -> Result<()> {
 
 fn execute_command(
     command: Commands,
-    shutdown_signal: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    shutdown_signal: Option<harbor_core::downloads::StopSignal>,
 ) -> Result<()> {
     match command {
         Commands::DownloadsInit { path } => {
             init_downloads_config(&path)?;
             Ok(())
         }
-        Commands::DownloadsOrganize { path } => {
+        Commands::DownloadsOrganize { path, log } => {
             let cfg = harbor_core::downloads::load_downloads_config(&path)?;
-            let actions = harbor_core::downloads::organize_once(&cfg)?;
-            for (from, to, rule, symlink_info) in actions {
-                let sym = symlink_info.unwrap_or_default();
+            let scan = harbor_core::downloads::scan_once(&cfg, None)?;
+            record_scan(&scan, &cfg, log, &RuleStatsStore::for_config(&path))?;
+            for (from, to, rule, symlink_info, _) in &scan.actions {
+                let sym = symlink_info.as_deref().unwrap_or_default();
                 println!("{} -> {} ({}) {}", from.display(), to.display(), rule, sym);
             }
+            fail_on_failures(&scan)
+        }
+        Commands::Organize { paths, config, log } => {
+            let cfg = harbor_core::downloads::load_downloads_config(&config)?;
+            let scan = harbor_core::downloads::organize_paths(&cfg, &paths)?;
+            record_scan(&scan, &cfg, log, &RuleStatsStore::for_config(&config))?;
+            for (from, to, rule, symlink_info, _) in &scan.actions {
+                let sym = symlink_info.as_deref().unwrap_or_default();
+                println!("{} -> {} ({}) {}", from.display(), to.display(), rule, sym);
+            }
+            println!("{} file(s) moved", scan.actions.len());
+            fail_on_failures(&scan)
+        }
+        Commands::DownloadsValidate { path } => {
+            let cfg = harbor_core::downloads::load_downloads_config(&path)?;
+            harbor_core::downloads::validate_downloads_config(&cfg)?;
+            for warning in harbor_core::rules::analyze_rules(&cfg.rules) {
+                println!("warning: {}", warning);
+            }
+            println!("valid");
+            Ok(())
+        }
+        // Saved in the config, so running watchers pick it up on their next reload
+        Commands::DownloadsPause { duration, config } => {
+            let store = harbor_core::config_store::ConfigStore::new(&config);
+            let mut cfg = store.load()?;
+            let until = duration.until_now();
+            cfg.paused_until = Some(until);
+            store.save(&cfg)?;
+            println!("paused until {}", local_time(until));
+            Ok(())
+        }
+        Commands::DownloadsResume { config } => {
+            let store = harbor_core::config_store::ConfigStore::new(&config);
+            let mut cfg = store.load()?;
+            if cfg.paused_until.take().is_some() {
+                store.save(&cfg)?;
+            }
+            println!("resumed");
             Ok(())
         }
         Commands::DownloadsWatch {
             path,
             interval_secs,
+            log,
         } => {
+            use std::sync::mpsc::RecvTimeoutError;
             let cfg = harbor_core::downloads::load_downloads_config(&path)?;
-            let should_continue = shutdown_signal
-                .unwrap_or_else(|| std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true)));
-            harbor_core::downloads::watch_polling(
-                &cfg,
-                interval_secs,
-                &should_continue,
-                |actions| {
-                    for (from, to, rule, symlink_info) in actions {
-                        let sym = symlink_info.as_deref().unwrap_or_default();
-                        println!("{} -> {} ({}) {}", from.display(), to.display(), rule, sym);
-                    }
-                },
-            )?;
+            let service = harbor_core::service::WatcherService::new(cfg)
+                .with_config_path(&path)
+                .with_log_path(open_activity_log(log).path())
+                .with_interval(interval_secs);
+            let events = service.subscribe();
+            let stop = shutdown_signal.unwrap_or_default();
+            service.start()?;
+            while !stop.is_stopped() {
+                match events.recv_timeout(std::time::Duration::from_millis(200)) {
+                    Ok(event) => print_service_event(&event, &path),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            let exited = service.stop();
+            for event in events.try_iter() {
+                print_service_event(&event, &path);
+            }
+            if !exited {
+                anyhow::bail!(
+                    "the watcher did not finish within {}s",
+                    harbor_core::downloads::WatcherThread::STOP_TIMEOUT.as_secs()
+                );
+            }
+            Ok(())
+        }
+        Commands::Daemon { action: None, args } => daemon::run(&args, &daemon::Signals::install()?),
+        Commands::Daemon {
+            action:
+                Some(DaemonAction::InstallSystemd {
+                    args,
+                    output,
+                    force,
+                }),
+            ..
+        } => {
+            let path = daemon::install_systemd(&args, output, force)?;
+            println!("created {}", path.display());
+            println!("enable it with: systemctl --user daemon-reload && systemctl --user enable --now {}", daemon::UNIT_NAME);
             Ok(())
         }
         Commands::Validate { path } => {
@@ -138,7 +481,12 @@ fn execute_command(
             println!("down");
             Ok(())
         }
-        Commands::Status { state_path } => {
+        Commands::Status {
+            downloads: true,
+            config,
+            ..
+        } => print_watcher_health(&config),
+        Commands::Status { state_path, .. } => {
             let st = harbor_core::orchestrator::status(PathBuf::from(state_path))?;
             for (name, pid, alive) in st {
                 println!("{} {} {}", name, pid, if alive { "alive" } else { "dead" });
@@ -161,6 +509,253 @@ fn execute_command(
         }
         Commands::TrayInstall { source } => tray_install(source, None, None),
         Commands::TrayUninstall => tray_uninstall(None),
+        Commands::Schema { kind } => {
+            println!("{}", harbor_core::schema::schema_json(kind.into())?);
+            Ok(())
+        }
+        Commands::Activity {
+            action: Some(action),
+            ..
+        } => {
+            let ActivityAction::Export {
+                format,
+                filter,
+                output,
+            } = *action;
+            let query = filter.query()?;
+            let store =
+                harbor_core::activity_store::ActivityStore::open(open_activity_log(filter.log))?;
+            match output {
+                Some(path) => {
+                    let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+                    let n = store.export(&query, format.into(), file)?;
+                    eprintln!("exported {} record(s) to {}", n, path);
+                }
+                None => {
+                    store.export(&query, format.into(), std::io::stdout().lock())?;
+                }
+            }
+            Ok(())
+        }
+        Commands::Activity {
+            action: None,
+            filter,
+            limit,
+            offset,
+            json,
+        } => {
+            let mut query = filter.query()?;
+            query.limit = Some(limit);
+            query.offset = offset;
+            let page =
+                harbor_core::activity_store::ActivityStore::open(open_activity_log(filter.log))?
+                    .query(&query)?;
+            for r in &page.records {
+                if json {
+                    println!("{}", serde_json::to_string(r)?);
+                    continue;
+                }
+                let ts = r
+                    .timestamp
+                    .map(|t| {
+                        t.with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "-".repeat(19));
+                println!(
+                    "{} {:<7} {} -> {} ({}){}",
+                    ts,
+                    r.outcome.as_str(),
+                    r.source,
+                    r.dest.as_deref().unwrap_or("-"),
+                    r.rule.as_deref().unwrap_or("-"),
+                    r.error
+                        .as_deref()
+                        .map(|e| format!(" {}", e))
+                        .unwrap_or_default()
+                );
+            }
+            if !json {
+                println!("{} of {} record(s)", page.records.len(), page.total);
+            }
+            Ok(())
+        }
+        Commands::Rules {
+            action: RulesAction::Explain { file, config },
+        } => {
+            let cfg = harbor_core::downloads::load_downloads_config(&config)?;
+            let ex = harbor_core::downloads::explain(&cfg, std::path::Path::new(&file))?;
+            println!("{}", ex.path.display());
+            if let Some(skip) = &ex.skip {
+                println!("  skipped: {}", skip);
+            }
+            match &ex.winner {
+                Some(rule) if ex.skip.is_none() => println!("  moves with rule '{}'", rule),
+                Some(rule) => println!("  would match rule '{}'", rule),
+                None => println!("  no rule matches"),
+            }
+            let width = ex.rules.iter().map(|t| t.rule.len()).max().unwrap_or(0);
+            for (i, trace) in ex.rules.iter().enumerate() {
+                println!(
+                    "  {:>2}. {:<width$}  {}",
+                    i + 1,
+                    trace.rule,
+                    trace.verdict,
+                    width = width
+                );
+            }
+            Ok(())
+        }
+        Commands::Rules {
+            action: RulesAction::List { config },
+        } => {
+            let cfg = harbor_core::downloads::load_downloads_config(&config)?;
+            let stats = RuleStatsStore::for_config(&config).load()?;
+            let width = cfg.rules.iter().map(|r| r.name.len()).max().unwrap_or(0);
+            for rule in &cfg.rules {
+                let s = stats.get(&rule.id).cloned().unwrap_or_default();
+                let state = if rule.enabled.unwrap_or(true) {
+                    "on"
+                } else {
+                    "off"
+                };
+                let last = s
+                    .last_matched
+                    .map(|t| {
+                        t.with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "never".to_string());
+                println!(
+                    "{:<width$}  {:<3} {:>6} matches {:>12} bytes  last {}",
+                    rule.name,
+                    state,
+                    s.matches,
+                    s.bytes_moved,
+                    last,
+                    width = width
+                );
+                if let (Some(error), Some(at)) = (&s.last_error, s.last_error_at) {
+                    println!(
+                        "  last error {}: {}",
+                        at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                        error
+                    );
+                }
+            }
+            Ok(())
+        }
+        Commands::Rules {
+            action:
+                RulesAction::Export {
+                    names,
+                    config,
+                    output,
+                },
+        } => {
+            let cfg = harbor_core::downloads::load_downloads_config(&config)?;
+            let yaml = harbor_core::rules::export_pack(&cfg, &names)?.to_yaml()?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, yaml).with_context(|| format!("write {}", path))?
+                }
+                None => print!("{}", yaml),
+            }
+            Ok(())
+        }
+        Commands::Rules {
+            action:
+                RulesAction::Import {
+                    pack,
+                    config,
+                    on_collision,
+                    rebase,
+                },
+        } => {
+            use harbor_core::rules::{import_pack, ImportOptions, RulePack};
+            let pack = RulePack::load(&pack)?;
+            let store = harbor_core::config_store::ConfigStore::new(&config);
+            let mut cfg = store.load()?;
+            let opts = ImportOptions {
+                on_collision: on_collision.into(),
+                rebase,
+            };
+            let report = import_pack(&mut cfg, pack, opts)?;
+            store.save(&cfg)?;
+            for name in &report.added {
+                println!("added {}", name);
+            }
+            for name in &report.replaced {
+                println!("replaced {}", name);
+            }
+            for (from, to) in &report.renamed {
+                println!("added {} as {}", from, to);
+            }
+            for name in &report.skipped {
+                println!("skipped {} (already exists)", name);
+            }
+            for warning in harbor_core::rules::analyze_rules(&cfg.rules) {
+                println!("warning: {}", warning);
+            }
+            Ok(())
+        }
+        Commands::Profile {
+            action: ProfileAction::List { config },
+        } => {
+            let cfg = harbor_core::downloads::load_downloads_config(&config)?;
+            for name in cfg.profile_names() {
+                let marker = if name == cfg.active_profile() {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{} {}", marker, name);
+            }
+            Ok(())
+        }
+        Commands::Profile {
+            action: ProfileAction::Use { name, config },
+        } => {
+            let store = harbor_core::config_store::ConfigStore::new(&config);
+            let mut cfg = store.load()?;
+            cfg.use_profile(&name)?;
+            store.save(&cfg)?;
+            println!("active profile: {} ({} rule(s))", name, cfg.rules.len());
+            Ok(())
+        }
+        Commands::Stats { days, json, log } => {
+            let store = harbor_core::activity_store::ActivityStore::open(open_activity_log(log))?;
+            let stats = store.stats(days)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+                return Ok(());
+            }
+            println!(
+                "moved: {} total, {} today, {} this week ({} bytes)",
+                stats.total_files_moved,
+                stats.files_moved_today,
+                stats.files_moved_this_week,
+                stats.total_bytes_moved
+            );
+            println!("failed: {}", stats.total_failed);
+            if let Some(hour) = stats.busiest_hour {
+                println!("busiest hour: {:02}:00", hour);
+            }
+            for (title, rows) in [("rule", &stats.by_rule), ("extension", &stats.by_extension)] {
+                println!("by {}:", title);
+                for b in rows.iter() {
+                    let key = if b.key.is_empty() { "-" } else { &b.key };
+                    println!("  {:<20} {:>6} {:>12} bytes", key, b.count, b.bytes);
+                }
+            }
+            println!("last {} days:", days);
+            for d in &stats.daily {
+                println!("  {} {:>6}", d.date, d.count);
+            }
+            Ok(())
+        }
     }
 }
 
@@ -294,26 +889,139 @@ fn tray_uninstall(_registry_path: Option<&str>) -> Result<()> {
     anyhow::bail!("windows only");
 }
 
+/// The activity log at `path`, or the shared Harbor log, for reading
+fn open_activity_log(path: Option<String>) -> harbor_core::activity::ActivityLog {
+    harbor_core::activity::ActivityLog::new(
+        path.map(PathBuf::from)
+            .unwrap_or_else(harbor_core::activity::ActivityLog::default_path),
+    )
+}
+
+fn activity_log(
+    path: Option<String>,
+    cfg: &harbor_core::downloads::DownloadsConfig,
+) -> harbor_core::activity::ActivityLog {
+    open_activity_log(path).with_retention(cfg.activity_retention.clone().unwrap_or_default())
+}
+
+fn print_watcher_health(config: &str) -> Result<()> {
+    let Some(health) = WatcherHealth::load(WatcherHealth::path_for_config(config))? else {
+        println!("watcher: never ran for {}", config);
+        return Ok(());
+    };
+    let now = chrono::Utc::now();
+    // A snoozed watcher skips its passes, so its report goes stale
+    let snoozed = harbor_core::downloads::load_downloads_config(config)
+        .ok()
+        .and_then(|cfg| cfg.snoozed_until(now));
+    let state = if let Some(until) = snoozed {
+        format!("paused until {}", local_time(until))
+    } else if health.is_stale(now) {
+        "stopped".to_string()
+    } else if let Some(reason) = &health.degraded {
+        format!("degraded ({})", reason)
+    } else if health.consecutive_failures > 0 {
+        "failing".to_string()
+    } else {
+        "healthy".to_string()
+    };
+    println!("watcher: {} (pid {})", state, health.pid);
+    if let Some(at) = health.last_scan {
+        println!(
+            "last scan: {} ({} ms, {} file(s) examined)",
+            local_time(at),
+            health.last_scan_ms,
+            health.files_examined
+        );
+    }
+    if let (Some(error), Some(at)) = (&health.last_error, health.last_error_at) {
+        println!("last error: {}: {}", local_time(at), error);
+    }
+    println!(
+        "failures: {} in a row, {} total",
+        health.consecutive_failures, health.total_failures
+    );
+    Ok(())
+}
+
+fn local_time(t: chrono::DateTime<chrono::Utc>) -> String {
+    t.with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// Prints what the watcher behind `harbor downloads-watch` did.
+fn print_service_event(event: &harbor_core::service::ServiceEvent, config: &str) {
+    use harbor_core::service::ServiceEvent;
+    match event {
+        ServiceEvent::Organized(actions) => {
+            for (from, to, rule, symlink_info, _) in actions {
+                let sym = symlink_info.as_deref().unwrap_or_default();
+                println!("{} -> {} ({}) {}", from.display(), to.display(), rule, sym);
+            }
+        }
+        ServiceEvent::ConfigReloaded => println!("reloaded {}", config),
+        ServiceEvent::ConfigError(e) => eprintln!("config error, keeping previous: {}", e),
+        ServiceEvent::Failed(failures) => {
+            for failure in failures {
+                match failure.retry_in {
+                    Some(delay) => eprintln!(
+                        "failed: {} ({}), retrying in {}s",
+                        failure.path.display(),
+                        failure,
+                        delay.as_secs()
+                    ),
+                    None => eprintln!(
+                        "failed: {} ({}), giving up after {} attempt(s)",
+                        failure.path.display(),
+                        failure,
+                        failure.attempts
+                    ),
+                }
+            }
+        }
+        ServiceEvent::Scanned(health) => {
+            // Report the first failure of a streak, not every retry
+            if health.consecutive_failures == 1 {
+                eprintln!(
+                    "organize error: {}",
+                    health.last_error.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        ServiceEvent::RecordError(e) => eprintln!("failed to record activity: {}", e),
+        ServiceEvent::StateChanged(_) => {}
+    }
+}
+
+/// Writes a one-off organize run's moves and failures to the activity log and rule stats.
+fn record_scan(
+    scan: &harbor_core::downloads::ScanReport,
+    cfg: &harbor_core::downloads::DownloadsConfig,
+    log: Option<String>,
+    stats: &RuleStatsStore,
+) -> Result<()> {
+    let activity = activity_log(log, cfg);
+    activity.record_moves(&scan.actions)?;
+    activity.record_failures(&scan.failures)?;
+    stats.record_moves(&scan.actions)?;
+    stats.record_failures(&scan.failures)?;
+    Ok(())
+}
+
+fn fail_on_failures(scan: &harbor_core::downloads::ScanReport) -> Result<()> {
+    for failure in &scan.failures {
+        eprintln!("failed: {} ({})", failure.path.display(), failure);
+    }
+    if !scan.failures.is_empty() {
+        anyhow::bail!("{} file(s) could not be moved", scan.failures.len());
+    }
+    Ok(())
+}
+
 fn init_downloads_config(path: &str) -> Result<()> {
-    let sample = r#"download_dir: "C:\\Users\\%USERNAME%\\Downloads"
-min_age_secs: 5
-rules:
-  - name: images
-    extensions: ["jpg", "jpeg", "png", "gif", "webp"]
-    target_dir: "C:\\Users\\%USERNAME%\\Downloads\\Images"
-  - name: videos
-    extensions: ["mp4", "mov", "mkv", "avi"]
-    target_dir: "C:\\Users\\%USERNAME%\\Downloads\\Videos"
-  - name: archives
-    extensions: ["zip", "rar", "7z", "tar", "gz"]
-    target_dir: "C:\\Users\\%USERNAME%\\Downloads\\Archives"
-  - name: docs
-    extensions: ["pdf", "docx", "xlsx", "pptx", "txt"]
-    target_dir: "C:\\Users\\%USERNAME%\\Downloads\\Documents"
-  - name: installers
-    extensions: ["exe", "msi"]
-    target_dir: "C:\\Users\\%USERNAME%\\Downloads\\Installers"
-"#;
+    // Paths come from the platform's Downloads folder, so the sample is usable as-is
+    let sample = serde_yaml::to_string(&harbor_core::downloads::default_config())?;
     std::fs::write(path, sample)?;
     println!("created {}", path);
     Ok(())
@@ -323,8 +1031,6 @@ rules:
 mod tests {
     use super::*;
     use std::io::Write;
-    use std::sync::atomic::AtomicBool;
-    use std::sync::Arc;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -359,6 +1065,23 @@ mod tests {
         assert!(content.contains("rules:"));
     }
 
+    #[test]
+    fn test_downloads_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("harbor.downloads.yaml");
+        std::fs::write(
+            &path,
+            "download_dir: /tmp/dl\nrules:\n  - name: All\n    target_dir: /tmp/all\n  - name: Pdf\n    extensions: [pdf]\n    target_dir: /tmp/all\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap().to_string();
+        // Shadowed rules are warnings, not errors
+        assert!(execute_command(Commands::DownloadsValidate { path: path.clone() }, None).is_ok());
+
+        std::fs::write(&path, "download_dir: ''\nrules: []\n").unwrap();
+        assert!(execute_command(Commands::DownloadsValidate { path }, None).is_err());
+    }
+
     #[test]
     fn test_validate_valid() {
         let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
@@ -375,6 +1098,398 @@ mod tests {
         assert!(execute_command(Commands::Validate { path }, None).is_err());
     }
 
+    #[test]
+    fn test_schema() {
+        assert!(execute_command(
+            Commands::Schema {
+                kind: SchemaArg::Downloads
+            },
+            None
+        )
+        .is_ok());
+        assert!(execute_command(
+            Commands::Schema {
+                kind: SchemaArg::Workspace
+            },
+            None
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_rules_explain() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dl_dir = temp.path().join("DL");
+        std::fs::create_dir(&dl_dir).unwrap();
+        std::fs::write(dl_dir.join("a.txt"), "x").unwrap();
+        let cfg_path = temp.path().join("config.yaml");
+        std::fs::write(
+            &cfg_path,
+            format!(
+                "download_dir: \"{}\"\nrules:\n  - name: Text\n    extensions: [\"txt\"]\n    target_dir: \"{}\"\n",
+                dl_dir.display().to_string().replace('\\', "\\\\"),
+                temp.path().join("T").display().to_string().replace('\\', "\\\\")
+            ),
+        )
+        .unwrap();
+
+        let explain = |file: &str| {
+            execute_command(
+                Commands::Rules {
+                    action: RulesAction::Explain {
+                        file: dl_dir.join(file).display().to_string(),
+                        config: cfg_path.display().to_string(),
+                    },
+                },
+                None,
+            )
+        };
+        assert!(explain("a.txt").is_ok());
+        assert!(explain("missing.txt").is_err());
+    }
+
+    #[test]
+    fn test_organize() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let old = temp.path().join("Old");
+        std::fs::create_dir(&old).unwrap();
+        std::fs::write(old.join("a.txt"), "x").unwrap();
+        std::fs::write(old.join("b.bin"), "x").unwrap();
+        let target = temp.path().join("Text");
+        let config = temp.path().join("harbor.downloads.yaml");
+        std::fs::write(
+            &config,
+            format!(
+                "download_dir: /dl\nmin_age_secs: 0\nrules:\n  - name: Text\n    extensions: [txt]\n    target_dir: \"{}\"\n",
+                target.display().to_string().replace('\\', "\\\\")
+            ),
+        )
+        .unwrap();
+        let log = temp.path().join("activity.log");
+
+        execute_command(
+            Commands::Organize {
+                paths: vec![old.clone()],
+                config: config.display().to_string(),
+                log: Some(log.display().to_string()),
+            },
+            None,
+        )
+        .unwrap();
+        assert!(target.join("a.txt").exists());
+        assert!(old.join("b.bin").exists());
+        let records = harbor_core::activity::ActivityLog::new(&log)
+            .read_all()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].rule.as_deref(), Some("Text"));
+
+        let cfg = harbor_core::downloads::load_downloads_config(&config).unwrap();
+        let stats = RuleStatsStore::for_config(&config).load().unwrap();
+        assert_eq!(stats[&cfg.rules[0].id].matches, 1);
+        assert_eq!(stats[&cfg.rules[0].id].bytes_moved, 1);
+        execute_command(
+            Commands::Rules {
+                action: RulesAction::List {
+                    config: config.display().to_string(),
+                },
+            },
+            None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_organize_reports_failures() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dropped = [temp.path().join("a.txt"), temp.path().join("b.bin")];
+        for file in &dropped {
+            std::fs::write(file, "x").unwrap();
+        }
+        let blocker = temp.path().join("blocker");
+        std::fs::write(&blocker, "not a folder").unwrap();
+        let config = temp.path().join("harbor.downloads.yaml");
+        std::fs::write(
+            &config,
+            format!(
+                "download_dir: /dl\nmin_age_secs: 0\nrules:\n  - name: Text\n    extensions: [txt]\n    target_dir: \"{}\"\n  - name: Bin\n    extensions: [bin]\n    target_dir: \"{}\"\n",
+                blocker.join("Text").display().to_string().replace('\\', "\\\\"),
+                temp.path().join("Bin").display().to_string().replace('\\', "\\\\")
+            ),
+        )
+        .unwrap();
+        let log = temp.path().join("activity.log");
+
+        let err = execute_command(
+            Commands::Organize {
+                paths: dropped.to_vec(),
+                config: config.display().to_string(),
+                log: Some(log.display().to_string()),
+            },
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("1 file(s) could not be moved"));
+        // The other file still moved
+        assert!(temp.path().join("Bin/b.bin").exists());
+        let records = harbor_core::activity::ActivityLog::new(&log)
+            .read_all()
+            .unwrap();
+        let outcomes: Vec<_> = records.iter().map(|r| r.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                harbor_core::activity::Outcome::Success,
+                harbor_core::activity::Outcome::Failed
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_downloads() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = temp.path().join("harbor.downloads.yaml");
+        let status = || {
+            execute_command(
+                Commands::Status {
+                    state_path: "harbor_state.json".to_string(),
+                    downloads: true,
+                    config: config.display().to_string(),
+                },
+                None,
+            )
+        };
+        status().unwrap();
+
+        let mut health = WatcherHealth::new(5);
+        let err = anyhow::anyhow!("list /missing");
+        health.record_pass(
+            std::path::Path::new("/missing"),
+            std::time::Duration::from_millis(1),
+            Err(&err),
+        );
+        health
+            .save(WatcherHealth::path_for_config(&config))
+            .unwrap();
+        status().unwrap();
+    }
+
+    #[test]
+    fn test_downloads_pause_resume() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = temp.path().join("harbor.downloads.yaml");
+        std::fs::write(&config, "download_dir: /dl\nrules: []\n").unwrap();
+        let config = config.display().to_string();
+
+        execute_command(
+            Commands::DownloadsPause {
+                duration: "1h".parse().unwrap(),
+                config: config.clone(),
+            },
+            None,
+        )
+        .unwrap();
+        let cfg = harbor_core::downloads::load_downloads_config(&config).unwrap();
+        let until = cfg.snoozed_until(chrono::Utc::now()).unwrap();
+        assert!(until > chrono::Utc::now() + chrono::Duration::minutes(59));
+
+        execute_command(
+            Commands::DownloadsResume {
+                config: config.clone(),
+            },
+            None,
+        )
+        .unwrap();
+        let cfg = harbor_core::downloads::load_downloads_config(&config).unwrap();
+        assert!(cfg.paused_until.is_none());
+    }
+
+    #[test]
+    fn test_profile_use() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = temp.path().join("harbor.downloads.yaml");
+        std::fs::write(
+            &config,
+            "download_dir: /dl\nrules:\n  - name: Home\n    target_dir: /home\nprofiles:\n  work:\n    - name: Work\n      target_dir: /work\n",
+        )
+        .unwrap();
+        let config = config.display().to_string();
+        let profile = |action| execute_command(Commands::Profile { action }, None);
+
+        profile(ProfileAction::List {
+            config: config.clone(),
+        })
+        .unwrap();
+        profile(ProfileAction::Use {
+            name: "work".into(),
+            config: config.clone(),
+        })
+        .unwrap();
+        let cfg = harbor_core::downloads::load_downloads_config(&config).unwrap();
+        assert_eq!(cfg.active_profile(), "work");
+        assert_eq!(cfg.rules[0].name, "Work");
+        assert_eq!(cfg.profiles.unwrap()["default"][0].name, "Home");
+
+        assert!(profile(ProfileAction::Use {
+            name: "missing".into(),
+            config,
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_rules_export_import() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let source = temp.path().join("source.yaml");
+        std::fs::write(
+            &source,
+            "download_dir: /home/a/Downloads\nrules:\n  - name: Text\n    extensions: [txt]\n    target_dir: /home/a/Downloads/Text\n  - name: Pdf\n    extensions: [pdf]\n    target_dir: /docs\n",
+        )
+        .unwrap();
+        let pack = temp.path().join("pack.yaml");
+        execute_command(
+            Commands::Rules {
+                action: RulesAction::Export {
+                    names: vec!["Text".into()],
+                    config: source.display().to_string(),
+                    output: Some(pack.display().to_string()),
+                },
+            },
+            None,
+        )
+        .unwrap();
+
+        let local = temp.path().join("local.yaml");
+        std::fs::write(
+            &local,
+            "download_dir: /home/b/Downloads\ntutorial_completed: true\nrules:\n  - name: Text\n    target_dir: /mine\n",
+        )
+        .unwrap();
+        let import = |on_collision| {
+            execute_command(
+                Commands::Rules {
+                    action: RulesAction::Import {
+                        pack: pack.display().to_string(),
+                        config: local.display().to_string(),
+                        on_collision,
+                        rebase: true,
+                    },
+                },
+                None,
+            )
+        };
+        import(CollisionArg::Skip).unwrap();
+        let cfg = harbor_core::downloads::load_downloads_config(&local).unwrap();
+        assert_eq!(cfg.rules.len(), 1);
+
+        import(CollisionArg::Rename).unwrap();
+        let cfg = harbor_core::downloads::load_downloads_config(&local).unwrap();
+        assert_eq!(cfg.rules[1].name, "Text (2)");
+        assert_eq!(
+            PathBuf::from(&cfg.rules[1].target_dir),
+            PathBuf::from("/home/b/Downloads/Text")
+        );
+        assert_eq!(cfg.tutorial_completed, Some(true));
+    }
+
+    #[test]
+    fn test_activity() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let log_path = temp.path().join("activity.log");
+        harbor_core::activity::ActivityLog::new(&log_path)
+            .record_moves(&[(
+                PathBuf::from("/dl/a.pdf"),
+                PathBuf::from("/docs/a.pdf"),
+                "Docs".to_string(),
+                None,
+                "docs".to_string(),
+            )])
+            .unwrap();
+
+        let filter = ActivityFilterArgs {
+            rule: Some("Docs".into()),
+            ext: Some("pdf".into()),
+            since: Some("2000-01-01".into()),
+            outcome: Some(OutcomeArg::Success),
+            search: Some("a.pdf".into()),
+            log: Some(log_path.display().to_string()),
+            ..Default::default()
+        };
+        let query = filter.query().unwrap();
+        assert_eq!(
+            harbor_core::activity_store::ActivityStore::open(
+                harbor_core::activity::ActivityLog::new(&log_path)
+            )
+            .unwrap()
+            .query(&query)
+            .unwrap()
+            .total,
+            1
+        );
+        assert!(execute_command(
+            Commands::Activity {
+                action: None,
+                filter: filter.clone(),
+                limit: 10,
+                offset: 0,
+                json: true,
+            },
+            None
+        )
+        .is_ok());
+
+        assert!(execute_command(
+            Commands::Stats {
+                days: 7,
+                json: false,
+                log: Some(log_path.display().to_string()),
+            },
+            None
+        )
+        .is_ok());
+
+        let csv = temp.path().join("export.csv");
+        assert!(execute_command(
+            Commands::Activity {
+                action: Some(Box::new(ActivityAction::Export {
+                    format: ExportArg::Csv,
+                    filter: ActivityFilterArgs {
+                        since: Some("2000-01-01".into()),
+                        rule: Some("Docs".into()),
+                        log: Some(log_path.display().to_string()),
+                        ..Default::default()
+                    },
+                    output: Some(csv.display().to_string()),
+                })),
+                filter: ActivityFilterArgs::default(),
+                limit: 50,
+                offset: 0,
+                json: false,
+            },
+            None
+        )
+        .is_ok());
+        let csv = std::fs::read_to_string(csv).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.contains("/dl/a.pdf,/docs/a.pdf,Docs,docs,success"));
+
+        let bad = ActivityFilterArgs {
+            since: Some("yesterday".into()),
+            ..filter
+        };
+        assert!(execute_command(
+            Commands::Activity {
+                action: None,
+                filter: bad,
+                limit: 10,
+                offset: 0,
+                json: false,
+            },
+            None
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_downloads_organize() {
         let temp = tempfile::TempDir::new().unwrap();
@@ -403,16 +1518,24 @@ rules:
 
         // Create file
         std::fs::write(dl_dir.join("test.txt"), "content").unwrap();
+        let log_path = temp.path().join("activity.log");
 
         assert!(execute_command(
             Commands::DownloadsOrganize {
-                path: cfg_path.to_str().unwrap().to_string()
+                path: cfg_path.to_str().unwrap().to_string(),
+                log: Some(log_path.to_str().unwrap().to_string()),
             },
             None
         )
         .is_ok());
 
         assert!(temp.path().join("Target").join("test.txt").exists());
+        let records = harbor_core::activity::ActivityLog::new(&log_path)
+            .read_all()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].rule.as_deref(), Some("test"));
+        assert_eq!(records[0].size_bytes, Some(7));
     }
 
     #[test]
@@ -430,11 +1553,13 @@ rules:
         )
         .unwrap();
 
-        let signal = Arc::new(AtomicBool::new(false)); // Stop immediately
+        let signal = harbor_core::downloads::StopSignal::new();
+        signal.stop(); // Stop immediately
         assert!(execute_command(
             Commands::DownloadsWatch {
                 path: cfg_path.to_str().unwrap().to_string(),
-                interval_secs: 1
+                interval_secs: 1,
+                log: Some(temp.path().join("activity.log").display().to_string()),
             },
             Some(signal)
         )
@@ -484,6 +1609,8 @@ services:
         assert!(execute_command(
             Commands::Status {
                 state_path: state_path.to_str().unwrap().to_string(),
+                downloads: false,
+                config: "harbor.downloads.yaml".to_string(),
             },
             None
         )
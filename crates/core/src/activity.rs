@@ -0,0 +1,740 @@
+//! The activity log shared by the CLI, tray and desktop app.
+//!
+//! Records are stored one JSON object per line. Older builds wrote plain text
+//! lines in two different formats; [`parse_line`] still reads those so existing
+//! history survives the upgrade.
+//!
+//! The live log is rotated into gzip archives next to it once it grows too big
+//! or too old, and old archives are pruned (see [`ActivityRetention`]).
+
+use crate::downloads::{OrganizeResult, RuleFailure};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Version written to the `v` field of every new record.
+pub const RECORD_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Success,
+    Failed,
+}
+
+impl Outcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "success" => Ok(Outcome::Success),
+            "failed" => Ok(Outcome::Failed),
+            _ => anyhow::bail!("unknown outcome '{}': expected success or failed", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityRecord {
+    /// Record format version.
+    pub v: u32,
+    /// When the file was handled. Missing only for lines imported from the
+    /// old desktop app format, which never stored one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    pub outcome: Outcome,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dest: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// [`Rule::id`](crate::types::Rule::id) of `rule`; missing in records
+    /// written before rules had ids.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symlink_info: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ActivityRecord {
+    /// A successful move, stamped with the current time. The size is read from
+    /// the destination, which holds the file once the move has happened.
+    pub fn moved(action: &OrganizeResult) -> Self {
+        let (from, to, rule, symlink_info, rule_id) = action;
+        Self {
+            v: RECORD_VERSION,
+            timestamp: Some(Utc::now()),
+            outcome: Outcome::Success,
+            source: from.to_string_lossy().to_string(),
+            dest: Some(to.to_string_lossy().to_string()),
+            rule: Some(rule.clone()),
+            rule_id: Some(rule_id.clone()).filter(|id| !id.is_empty()),
+            size_bytes: fs::metadata(to).ok().map(|m| m.len()),
+            symlink_info: symlink_info.clone(),
+            error: None,
+        }
+    }
+
+    /// A move that failed for good, stamped with the current time.
+    pub fn failed(failure: &RuleFailure) -> Self {
+        Self {
+            v: RECORD_VERSION,
+            timestamp: Some(Utc::now()),
+            outcome: Outcome::Failed,
+            source: failure.path.to_string_lossy().to_string(),
+            dest: None,
+            rule: Some(failure.rule.clone()),
+            rule_id: Some(failure.rule_id.clone()).filter(|id| !id.is_empty()),
+            size_bytes: fs::metadata(&failure.path).ok().map(|m| m.len()),
+            symlink_info: None,
+            error: Some(failure.message.clone()),
+        }
+    }
+
+    /// File name of the destination, falling back to the source.
+    pub fn file_name(&self) -> String {
+        let path = Path::new(self.dest.as_deref().unwrap_or(&self.source));
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.source.clone())
+    }
+}
+
+/// Parses one log line, accepting JSON records as well as the legacy
+/// `src -> dst (rule) info` and `[ts] Moved name -> dst (Rule: x)` lines.
+pub fn parse_line(line: &str) -> Option<ActivityRecord> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    if line.starts_with('{') {
+        return serde_json::from_str(line).ok();
+    }
+    parse_tray_line(line).or_else(|| parse_app_line(line))
+}
+
+fn parse_tray_line(line: &str) -> Option<ActivityRecord> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(
+            r"^\[(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2})\] Moved (.+?) -> (.+) \(Rule: (.*)\)$",
+        )
+        .unwrap()
+    });
+    let caps = re.captures(line)?;
+    let naive = NaiveDateTime::parse_from_str(&caps[1], "%Y-%m-%d %H:%M:%S").ok()?;
+    let timestamp = Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc));
+    Some(ActivityRecord {
+        v: 0,
+        timestamp,
+        outcome: Outcome::Success,
+        source: caps[2].to_string(),
+        dest: Some(caps[3].to_string()),
+        rule: Some(caps[4].to_string()),
+        rule_id: None,
+        size_bytes: None,
+        symlink_info: None,
+        error: None,
+    })
+}
+
+fn parse_app_line(line: &str) -> Option<ActivityRecord> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"^(.+?) -> (.+) \(([^)]+)\)\s*(.*)$").unwrap());
+    let caps = re.captures(line)?;
+    let info = caps[4].trim();
+    Some(ActivityRecord {
+        v: 0,
+        timestamp: None,
+        outcome: Outcome::Success,
+        source: caps[1].to_string(),
+        dest: Some(caps[2].to_string()),
+        rule: Some(caps[3].to_string()),
+        rule_id: None,
+        size_bytes: None,
+        symlink_info: (!info.is_empty()).then(|| info.to_string()),
+        error: None,
+    })
+}
+
+/// Reads every record from `reader`, oldest first, skipping lines that are
+/// neither records nor legacy moves (headers, startup notes).
+pub fn read_records(reader: impl BufRead) -> Vec<ActivityRecord> {
+    reader
+        .lines()
+        .map_while(std::result::Result::ok)
+        .filter_map(|line| parse_line(&line))
+        .collect()
+}
+
+/// When the live log is rotated into a compressed archive and how long
+/// archives are kept. Unset fields use the defaults below.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ActivityRetention {
+    /// Rotate once the live log reaches this size (default: 1 MiB).
+    pub max_size_bytes: Option<u64>,
+    /// Rotate once the oldest record in the live log is this many days old (default: 7).
+    pub rotate_after_days: Option<u32>,
+    /// Delete archives rotated more than this many days ago (default: 90).
+    pub keep_days: Option<u32>,
+    /// Keep at most this many archives (default: 20).
+    pub max_archives: Option<usize>,
+}
+
+impl ActivityRetention {
+    pub fn max_size_bytes(&self) -> u64 {
+        self.max_size_bytes.unwrap_or(1024 * 1024)
+    }
+
+    pub fn rotate_after_days(&self) -> u32 {
+        self.rotate_after_days.unwrap_or(7)
+    }
+
+    pub fn keep_days(&self) -> u32 {
+        self.keep_days.unwrap_or(90)
+    }
+
+    pub fn max_archives(&self) -> usize {
+        self.max_archives.unwrap_or(20)
+    }
+}
+
+/// One page of records, newest first, plus the number of records across the
+/// live log and every archive.
+#[derive(Debug, Clone)]
+pub struct ActivityPage {
+    pub records: Vec<ActivityRecord>,
+    pub total: usize,
+}
+
+/// A rotated segment, named `<log name>.<rotated at, unix ms>.<records>.gz`
+/// so paging can skip it without decompressing.
+#[derive(Debug, Clone)]
+pub(crate) struct Archive {
+    pub(crate) path: PathBuf,
+    rotated_ms: i64,
+    records: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivityLog {
+    path: PathBuf,
+    retention: ActivityRetention,
+}
+
+impl ActivityLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            retention: ActivityRetention::default(),
+        }
+    }
+
+    pub fn with_retention(mut self, retention: ActivityRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// `recent_moves.log` in the Harbor data directory.
+    pub fn default_path() -> PathBuf {
+        crate::paths::harbor_data_dir().join("recent_moves.log")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn retention(&self) -> &ActivityRetention {
+        &self.retention
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "activity.log".to_string())
+    }
+
+    fn sibling(&self, name: &str) -> PathBuf {
+        self.path.with_file_name(name)
+    }
+
+    /// Takes the writer lock shared by every process appending to this log.
+    fn lock(&self) -> Result<fs::File> {
+        let lock_path = self.sibling(&format!("{}.lock", self.file_name()));
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("opening {}", lock_path.display()))?;
+        file.lock()
+            .with_context(|| format!("locking {}", lock_path.display()))?;
+        Ok(file)
+    }
+
+    pub fn append(&self, records: &[ActivityRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _lock = self.lock()?;
+        if self.needs_rotation() {
+            self.rotate_locked()?;
+        }
+
+        let mut buf = String::new();
+        for record in records {
+            buf.push_str(&serde_json::to_string(record)?);
+            buf.push('\n');
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("opening {}", self.path.display()))?;
+        file.write_all(buf.as_bytes())?;
+        Ok(())
+    }
+
+    /// Appends a success record for each organize action.
+    pub fn record_moves(&self, actions: &[OrganizeResult]) -> Result<()> {
+        let records: Vec<ActivityRecord> = actions.iter().map(ActivityRecord::moved).collect();
+        self.append(&records)
+    }
+
+    /// Appends a failure record for each of `failures` that will not be
+    /// retried; the ones still waiting for a retry are left out.
+    pub fn record_failures(&self, failures: &[RuleFailure]) -> Result<()> {
+        let records: Vec<ActivityRecord> = failures
+            .iter()
+            .filter(|f| f.gave_up())
+            .map(ActivityRecord::failed)
+            .collect();
+        self.append(&records)
+    }
+
+    fn needs_rotation(&self) -> bool {
+        let Ok(meta) = fs::metadata(&self.path) else {
+            return false;
+        };
+        if meta.len() == 0 {
+            return false;
+        }
+        if meta.len() >= self.retention.max_size_bytes() {
+            return true;
+        }
+        // Only the first line is read: records are appended in time order.
+        let first = fs::File::open(&self.path).ok().and_then(|f| {
+            BufReader::new(f)
+                .lines()
+                .map_while(std::result::Result::ok)
+                .find_map(|line| parse_line(&line))
+        });
+        let max_age = chrono::Duration::days(self.retention.rotate_after_days() as i64);
+        matches!(
+            first.and_then(|r| r.timestamp),
+            Some(ts) if Utc::now() - ts >= max_age
+        )
+    }
+
+    /// Moves the live log into a new compressed archive, then applies the
+    /// retention limits. Does nothing when the live log is empty.
+    pub fn rotate(&self) -> Result<()> {
+        let _lock = self.lock()?;
+        self.rotate_locked()
+    }
+
+    fn rotate_locked(&self) -> Result<()> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", self.path.display())),
+        };
+        let count = read_records(&data[..]).len();
+        if count > 0 {
+            let mut rotated_ms = Utc::now().timestamp_millis();
+            let mut target = self.archive_path(rotated_ms, count);
+            while target.exists() {
+                rotated_ms += 1;
+                target = self.archive_path(rotated_ms, count);
+            }
+            let tmp = self.sibling(&format!("{}.gz.tmp", self.file_name()));
+            let mut encoder = GzEncoder::new(fs::File::create(&tmp)?, Compression::default());
+            encoder.write_all(&data)?;
+            encoder.finish()?.sync_all()?;
+            fs::rename(&tmp, &target).with_context(|| format!("writing {}", target.display()))?;
+        }
+        fs::write(&self.path, "")?;
+        self.prune()
+    }
+
+    fn archive_path(&self, rotated_ms: i64, records: usize) -> PathBuf {
+        self.sibling(&format!(
+            "{}.{}.{}.gz",
+            self.file_name(),
+            rotated_ms,
+            records
+        ))
+    }
+
+    /// Archives of this log, oldest first.
+    pub(crate) fn archives(&self) -> Result<Vec<Archive>> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let prefix = format!("{}.", self.file_name());
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut archives: Vec<Archive> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let rest = name.strip_prefix(&prefix)?.strip_suffix(".gz")?;
+                let (ms, records) = rest.split_once('.')?;
+                Some(Archive {
+                    path: e.path(),
+                    rotated_ms: ms.parse().ok()?,
+                    records: records.parse().ok()?,
+                })
+            })
+            .collect();
+        archives.sort_by_key(|a| a.rotated_ms);
+        Ok(archives)
+    }
+
+    fn prune(&self) -> Result<()> {
+        let mut archives = self.archives()?;
+        let cutoff = Utc::now() - chrono::Duration::days(self.retention.keep_days() as i64);
+        let excess = archives.len().saturating_sub(self.retention.max_archives());
+        for (i, archive) in archives.drain(..).enumerate() {
+            if i < excess || archive.rotated_ms < cutoff.timestamp_millis() {
+                fs::remove_file(&archive.path)
+                    .with_context(|| format!("removing {}", archive.path.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_live(&self) -> Result<Vec<ActivityRecord>> {
+        match fs::File::open(&self.path) {
+            Ok(file) => Ok(read_records(BufReader::new(file))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e).with_context(|| format!("opening {}", self.path.display())),
+        }
+    }
+
+    pub(crate) fn read_archive(archive: &Archive) -> Result<Vec<ActivityRecord>> {
+        let file = fs::File::open(&archive.path)
+            .with_context(|| format!("opening {}", archive.path.display()))?;
+        Ok(read_records(BufReader::new(GzDecoder::new(file))))
+    }
+
+    /// All records across archives and the live log, oldest first.
+    pub fn read_all(&self) -> Result<Vec<ActivityRecord>> {
+        let mut records = Vec::new();
+        for archive in self.archives()? {
+            records.extend(Self::read_archive(&archive)?);
+        }
+        records.extend(self.read_live()?);
+        Ok(records)
+    }
+
+    /// Records `offset..offset + limit`, newest first. Archives that fall
+    /// entirely before the page are skipped without being decompressed.
+    pub fn read_page(&self, offset: usize, limit: usize) -> Result<ActivityPage> {
+        let live = self.read_live()?;
+        let archives = self.archives()?;
+        let total = live.len() + archives.iter().map(|a| a.records).sum::<usize>();
+
+        let mut records = Vec::new();
+        let mut skip = offset;
+        take_newest(live, &mut skip, limit, &mut records);
+        for archive in archives.iter().rev() {
+            if records.len() >= limit {
+                break;
+            }
+            if skip >= archive.records {
+                skip -= archive.records;
+                continue;
+            }
+            take_newest(Self::read_archive(archive)?, &mut skip, limit, &mut records);
+        }
+        Ok(ActivityPage { records, total })
+    }
+
+    /// Empties the live log and deletes every archive.
+    pub fn clear(&self) -> Result<()> {
+        let _lock = self.lock()?;
+        for archive in self.archives()? {
+            fs::remove_file(&archive.path)?;
+        }
+        if self.path.exists() {
+            fs::write(&self.path, "")?;
+        }
+        Ok(())
+    }
+}
+
+/// Moves up to `limit - out.len()` records from the end of `segment` into
+/// `out`, after skipping `skip` of them.
+fn take_newest(
+    segment: Vec<ActivityRecord>,
+    skip: &mut usize,
+    limit: usize,
+    out: &mut Vec<ActivityRecord>,
+) {
+    let available = segment.len();
+    let skipped = (*skip).min(available);
+    *skip -= skipped;
+    let wanted = limit.saturating_sub(out.len());
+    out.extend(segment.into_iter().rev().skip(skipped).take(wanted));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_failures_skips_pending_retries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(tmp.path().join("recent_moves.log"));
+        let failure = |name: &str, retry_in| RuleFailure {
+            path: PathBuf::from(format!("/dl/{}", name)),
+            rule: "Docs".into(),
+            rule_id: "docs-id".into(),
+            message: "permission denied".into(),
+            attempts: 1,
+            retry_in,
+        };
+        log.record_failures(&[
+            failure("a.pdf", Some(std::time::Duration::from_secs(5))),
+            failure("b.pdf", None),
+        ])
+        .unwrap();
+
+        let records = log.read_all().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].outcome, Outcome::Failed);
+        assert_eq!(records[0].source, "/dl/b.pdf");
+        assert_eq!(records[0].error.as_deref(), Some("permission denied"));
+        assert_eq!(records[0].rule_id.as_deref(), Some("docs-id"));
+        assert_eq!(records[0].dest, None);
+    }
+
+    #[test]
+    fn test_record_moves_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("a.pdf");
+        fs::write(&dest, b"12345").unwrap();
+        let log = ActivityLog::new(tmp.path().join("logs").join("recent_moves.log"));
+
+        let action = (
+            PathBuf::from("/dl/a.pdf"),
+            dest.clone(),
+            "Docs".to_string(),
+            Some("Symlink created".to_string()),
+            "docs-id".to_string(),
+        );
+        log.record_moves(&[action]).unwrap();
+        log.record_moves(&[]).unwrap();
+
+        let records = log.read_all().unwrap();
+        assert_eq!(records.len(), 1);
+        let r = &records[0];
+        assert_eq!(r.v, RECORD_VERSION);
+        assert!(r.timestamp.is_some());
+        assert_eq!(r.outcome, Outcome::Success);
+        assert_eq!(r.source, "/dl/a.pdf");
+        assert_eq!(r.rule.as_deref(), Some("Docs"));
+        assert_eq!(r.rule_id.as_deref(), Some("docs-id"));
+        assert_eq!(r.size_bytes, Some(5));
+        assert_eq!(r.symlink_info.as_deref(), Some("Symlink created"));
+        assert_eq!(r.file_name(), "a.pdf");
+
+        log.clear().unwrap();
+        assert!(log.read_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_missing_log() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(tmp.path().join("none.log"));
+        assert!(log.read_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_legacy_lines() {
+        let app = parse_line(r"C:\src\file.txt -> C:\dst\file.txt (Docs) Symlink created").unwrap();
+        assert_eq!(app.source, r"C:\src\file.txt");
+        assert_eq!(app.dest.as_deref(), Some(r"C:\dst\file.txt"));
+        assert_eq!(app.rule.as_deref(), Some("Docs"));
+        assert_eq!(app.symlink_info.as_deref(), Some("Symlink created"));
+        assert_eq!(app.timestamp, None);
+
+        let app = parse_line(r"C:\src\img.png -> C:\dst\img.png (Images) ").unwrap();
+        assert_eq!(app.symlink_info, None);
+
+        let tray =
+            parse_line(r"[2024-03-01 09:30:00] Moved a.zip -> C:\dst\a.zip (Rule: Archives)")
+                .unwrap();
+        assert_eq!(tray.source, "a.zip");
+        assert_eq!(tray.dest.as_deref(), Some(r"C:\dst\a.zip"));
+        assert_eq!(tray.rule.as_deref(), Some("Archives"));
+        let local = tray.timestamp.unwrap().with_timezone(&Local);
+        assert_eq!(
+            local.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2024-03-01 09:30:00"
+        );
+    }
+
+    #[test]
+    fn test_read_records_mixed() {
+        let json = serde_json::to_string(&ActivityRecord {
+            v: RECORD_VERSION,
+            timestamp: Some(Utc::now()),
+            outcome: Outcome::Failed,
+            source: "/dl/b.iso".into(),
+            dest: None,
+            rule: Some("Images".into()),
+            rule_id: None,
+            size_bytes: Some(10),
+            symlink_info: None,
+            error: Some("permission denied".into()),
+        })
+        .unwrap();
+        let data = format!(
+            "Recent Moves Log\n----------------\n\n/a -> /b (RuleA)\nStartup: Cleaned up 1 old symlink(s)\n[2024-01-01 00:00:00] Moved c -> /d (Rule: RuleB)\n{}\nnot json {{\n",
+            json
+        );
+        let records = read_records(std::io::Cursor::new(data));
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].rule.as_deref(), Some("RuleA"));
+        assert_eq!(records[1].rule.as_deref(), Some("RuleB"));
+        assert_eq!(records[2].outcome, Outcome::Failed);
+        assert_eq!(records[2].error.as_deref(), Some("permission denied"));
+        assert_eq!(records[2].file_name(), "b.iso");
+    }
+
+    fn record(source: &str, timestamp: DateTime<Utc>) -> ActivityRecord {
+        ActivityRecord {
+            v: RECORD_VERSION,
+            timestamp: Some(timestamp),
+            outcome: Outcome::Success,
+            source: source.to_string(),
+            dest: None,
+            rule: Some("Docs".into()),
+            rule_id: None,
+            size_bytes: None,
+            symlink_info: None,
+            error: None,
+        }
+    }
+
+    fn sources(records: &[ActivityRecord]) -> Vec<&str> {
+        records.iter().map(|r| r.source.as_str()).collect()
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_pages_across_archives() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(tmp.path().join("recent_moves.log")).with_retention(
+            ActivityRetention {
+                max_size_bytes: Some(1),
+                ..Default::default()
+            },
+        );
+        // Each append after the first finds the live log over the limit
+        for batch in [["a", "b"], ["c", "d"], ["e", "f"]] {
+            let now = Utc::now();
+            log.append(&[record(batch[0], now), record(batch[1], now)])
+                .unwrap();
+        }
+        assert_eq!(log.archives().unwrap().len(), 2);
+
+        assert_eq!(
+            sources(&log.read_all().unwrap()),
+            ["a", "b", "c", "d", "e", "f"]
+        );
+
+        let page = log.read_page(0, 3).unwrap();
+        assert_eq!(page.total, 6);
+        assert_eq!(sources(&page.records), ["f", "e", "d"]);
+        let page = log.read_page(3, 2).unwrap();
+        assert_eq!(sources(&page.records), ["c", "b"]);
+        let page = log.read_page(5, 10).unwrap();
+        assert_eq!(sources(&page.records), ["a"]);
+        assert!(log.read_page(6, 10).unwrap().records.is_empty());
+
+        log.clear().unwrap();
+        assert!(log.archives().unwrap().is_empty());
+        assert_eq!(log.read_page(0, 10).unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_rotates_by_age_and_prunes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("recent_moves.log");
+        let old = Utc::now() - chrono::Duration::days(10);
+        ActivityLog::new(&path)
+            .append(&[record("old", old)])
+            .unwrap();
+
+        // An archive rotated long ago, past keep_days
+        let stale = ActivityLog::new(&path).archive_path(old.timestamp_millis() - 1, 1);
+        fs::write(&stale, b"").unwrap();
+
+        let log = ActivityLog::new(&path).with_retention(ActivityRetention {
+            rotate_after_days: Some(7),
+            keep_days: Some(5),
+            ..Default::default()
+        });
+        log.append(&[record("new", Utc::now())]).unwrap();
+
+        let archives = log.archives().unwrap();
+        assert_eq!(archives.len(), 1);
+        assert!(!stale.exists());
+        assert_eq!(sources(&log.read_live().unwrap()), ["new"]);
+        assert_eq!(sources(&log.read_all().unwrap()), ["old", "new"]);
+
+        // max_archives keeps only the newest
+        let log = log.with_retention(ActivityRetention {
+            max_archives: Some(1),
+            ..Default::default()
+        });
+        log.rotate().unwrap();
+        assert_eq!(log.archives().unwrap().len(), 1);
+        assert_eq!(sources(&log.read_all().unwrap()), ["new"]);
+    }
+}
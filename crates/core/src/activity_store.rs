@@ -0,0 +1,1002 @@
+//! An SQLite index over the activity log for filtered, paged queries.
+//!
+//! The JSON Lines log stays the source of truth and every frontend keeps
+//! appending to it. [`ActivityStore::sync`] imports whatever was added since
+//! the last sync, so the index can be deleted at any time and is rebuilt on
+//! the next open.
+
+use crate::activity::{ActivityLog, ActivityPage, ActivityRecord, Outcome};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Filters for [`ActivityStore::query`]. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct ActivityQuery {
+    pub rule: Option<String>,
+    /// Matches by [`Rule::id`](crate::types::Rule::id), so renamed rules
+    /// keep their history.
+    pub rule_id: Option<String>,
+    /// Case-insensitive, with or without the leading dot.
+    pub extension: Option<String>,
+    /// Inclusive lower bound.
+    pub since: Option<DateTime<Utc>>,
+    /// Exclusive upper bound.
+    pub until: Option<DateTime<Utc>>,
+    pub outcome: Option<Outcome>,
+    /// Case-insensitive substring of the file name, source or destination.
+    pub text: Option<String>,
+    pub offset: usize,
+    /// Defaults to 50.
+    pub limit: Option<usize>,
+}
+
+/// Bumped whenever [`SCHEMA`] changes; older indexes are dropped and rebuilt
+/// from the log.
+const SCHEMA_VERSION: i32 = 2;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS activity (
+    id INTEGER PRIMARY KEY,
+    ts INTEGER,
+    outcome TEXT NOT NULL,
+    source TEXT NOT NULL,
+    dest TEXT,
+    rule TEXT,
+    rule_id TEXT,
+    file_name TEXT NOT NULL,
+    ext TEXT NOT NULL,
+    size_bytes INTEGER,
+    symlink_info TEXT,
+    error TEXT,
+    v INTEGER NOT NULL,
+    -- Archive the record was last read from; empty for the live log
+    origin TEXT NOT NULL DEFAULT ''
+);
+CREATE UNIQUE INDEX IF NOT EXISTS activity_unique
+    ON activity (COALESCE(ts, -1), source, COALESCE(dest, ''), outcome);
+CREATE INDEX IF NOT EXISTS activity_ts ON activity (ts);
+CREATE INDEX IF NOT EXISTS activity_rule ON activity (rule);
+CREATE INDEX IF NOT EXISTS activity_rule_id ON activity (rule_id);
+CREATE INDEX IF NOT EXISTS activity_ext ON activity (ext);
+CREATE INDEX IF NOT EXISTS activity_outcome ON activity (outcome);
+CREATE INDEX IF NOT EXISTS activity_origin ON activity (origin);
+CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS imported_archives (name TEXT PRIMARY KEY);
+";
+
+/// Count and size of moves sharing a rule or extension.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Breakdown {
+    pub key: String,
+    pub count: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub count: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityStats {
+    pub total_files_moved: usize,
+    pub files_moved_today: usize,
+    /// Since Monday.
+    pub files_moved_this_week: usize,
+    pub total_bytes_moved: u64,
+    pub total_failed: usize,
+    pub most_active_rule: Option<String>,
+    /// Local hour (0-23) with the most moves.
+    pub busiest_hour: Option<u32>,
+    pub by_rule: Vec<Breakdown>,
+    pub by_extension: Vec<Breakdown>,
+    /// Oldest day first, including days without moves.
+    pub daily: Vec<DailyCount>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => bail!("unknown export format '{}': expected csv or json", s),
+        }
+    }
+}
+
+/// One exported record. Field names match the desktop app's activity
+/// entries; timestamps are RFC 3339 in UTC, empty when unknown.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportRow {
+    pub timestamp: String,
+    pub filename: String,
+    pub source_path: String,
+    pub dest_path: String,
+    pub rule_name: String,
+    pub rule_id: String,
+    pub status: String,
+    pub size_bytes: Option<u64>,
+    pub symlink_info: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ExportRow {
+    const CSV_HEADER: [&'static str; 10] = [
+        "timestamp",
+        "filename",
+        "source_path",
+        "dest_path",
+        "rule_name",
+        "rule_id",
+        "status",
+        "size_bytes",
+        "symlink_info",
+        "error",
+    ];
+
+    fn csv_fields(&self) -> [String; 10] {
+        [
+            self.timestamp.clone(),
+            self.filename.clone(),
+            self.source_path.clone(),
+            self.dest_path.clone(),
+            self.rule_name.clone(),
+            self.rule_id.clone(),
+            self.status.clone(),
+            self.size_bytes.map(|n| n.to_string()).unwrap_or_default(),
+            self.symlink_info.clone().unwrap_or_default(),
+            self.error.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+impl From<ActivityRecord> for ExportRow {
+    fn from(r: ActivityRecord) -> Self {
+        Self {
+            timestamp: r
+                .timestamp
+                .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+                .unwrap_or_default(),
+            filename: r.file_name(),
+            source_path: r.source,
+            dest_path: r.dest.unwrap_or_default(),
+            rule_name: r.rule.unwrap_or_default(),
+            rule_id: r.rule_id.unwrap_or_default(),
+            status: r.outcome.as_str().to_string(),
+            size_bytes: r.size_bytes,
+            symlink_info: r.symlink_info,
+            error: r.error,
+        }
+    }
+}
+
+/// Quotes a CSV field when it holds a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub struct ActivityStore {
+    conn: Connection,
+    log: ActivityLog,
+}
+
+impl ActivityStore {
+    /// Opens the index stored next to `log` (`recent_moves.db` for
+    /// `recent_moves.log`) and syncs it.
+    pub fn open(log: ActivityLog) -> Result<Self> {
+        let db_path = Self::db_path(&log);
+        Self::open_at(&db_path, log)
+    }
+
+    pub fn db_path(log: &ActivityLog) -> PathBuf {
+        log.path().with_extension("db")
+    }
+
+    pub fn open_at(db_path: &Path, log: ActivityLog) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn =
+            Connection::open(db_path).with_context(|| format!("opening {}", db_path.display()))?;
+        // The tray, CLI and app may all sync at once
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        let version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version < SCHEMA_VERSION {
+            conn.execute_batch(
+                "DROP TABLE IF EXISTS activity; DROP TABLE IF EXISTS meta; \
+                 DROP TABLE IF EXISTS imported_archives;",
+            )?;
+        }
+        conn.execute_batch(SCHEMA)?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        let mut store = Self { conn, log };
+        store.sync()?;
+        Ok(store)
+    }
+
+    pub fn log(&self) -> &ActivityLog {
+        &self.log
+    }
+
+    /// Imports records appended to the live log and any archives not seen
+    /// before, then drops the rows of archives that were deleted. Returns
+    /// the number of new rows.
+    ///
+    /// Rows follow the files rather than a retention window, so a reader
+    /// opening the log with other retention settings never drops history.
+    pub fn sync(&mut self) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut added = 0;
+
+        let archives = self.log.archives()?;
+        let mut names = Vec::new();
+        for archive in &archives {
+            let name = file_name(&archive.path);
+            let seen: Option<String> = tx
+                .query_row(
+                    "SELECT name FROM imported_archives WHERE name = ?1",
+                    [&name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if seen.is_none() {
+                // Rotated records are usually already indexed from the live
+                // log; the unique index makes re-importing them a no-op.
+                for record in ActivityLog::read_archive(archive)? {
+                    added += insert(&tx, &record, &name)?;
+                }
+                tx.execute("INSERT INTO imported_archives (name) VALUES (?1)", [&name])?;
+            }
+            names.push(name);
+        }
+        let known: Vec<String> = tx
+            .prepare("SELECT name FROM imported_archives")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        for name in known.iter().filter(|n| !names.contains(n)) {
+            // Pruned for age or by max_archives
+            tx.execute("DELETE FROM activity WHERE origin = ?1", [name])?;
+            tx.execute("DELETE FROM imported_archives WHERE name = ?1", [name])?;
+        }
+
+        let (records, offset, head) = read_live_from(
+            self.log.path(),
+            get_meta(&tx, "live_offset")?.and_then(|v| v.parse().ok()),
+            get_meta(&tx, "live_head")?,
+        )?;
+        for record in &records {
+            added += insert(&tx, record, LIVE_ORIGIN)?;
+        }
+        set_meta(&tx, "live_offset", &offset.to_string())?;
+        set_meta(&tx, "live_head", &head)?;
+
+        if archives.is_empty() && offset == 0 {
+            // The log was cleared
+            tx.execute("DELETE FROM activity", [])?;
+        }
+
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Matching records, newest first, and how many match in total.
+    pub fn query(&self, q: &ActivityQuery) -> Result<ActivityPage> {
+        let mut clauses = Vec::new();
+        let mut args: Vec<Value> = Vec::new();
+        if let Some(rule) = &q.rule {
+            args.push(Value::Text(rule.clone()));
+            clauses.push(format!("rule = ?{}", args.len()));
+        }
+        if let Some(rule_id) = &q.rule_id {
+            args.push(Value::Text(rule_id.clone()));
+            clauses.push(format!("rule_id = ?{}", args.len()));
+        }
+        if let Some(ext) = &q.extension {
+            args.push(Value::Text(ext.trim_start_matches('.').to_lowercase()));
+            clauses.push(format!("ext = ?{}", args.len()));
+        }
+        if let Some(since) = q.since {
+            args.push(Value::Integer(since.timestamp_millis()));
+            clauses.push(format!("ts >= ?{}", args.len()));
+        }
+        if let Some(until) = q.until {
+            args.push(Value::Integer(until.timestamp_millis()));
+            clauses.push(format!("ts < ?{}", args.len()));
+        }
+        if let Some(outcome) = q.outcome {
+            args.push(Value::Text(outcome.as_str().to_string()));
+            clauses.push(format!("outcome = ?{}", args.len()));
+        }
+        if let Some(text) = q.text.as_deref().filter(|t| !t.is_empty()) {
+            let escaped = text
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            args.push(Value::Text(format!("%{}%", escaped)));
+            let n = args.len();
+            clauses.push(format!(
+                "(file_name LIKE ?{n} ESCAPE '\\' OR source LIKE ?{n} ESCAPE '\\' \
+                 OR dest LIKE ?{n} ESCAPE '\\')"
+            ));
+        }
+        let filter = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM activity {}", filter),
+            rusqlite::params_from_iter(args.iter()),
+            |row| row.get(0),
+        )?;
+
+        let mut page_args = args.clone();
+        page_args.push(Value::Integer(q.limit.unwrap_or(50) as i64));
+        page_args.push(Value::Integer(q.offset as i64));
+        let sql = format!(
+            "SELECT ts, outcome, source, dest, rule, size_bytes, symlink_info, error, v, rule_id \
+             FROM activity {} ORDER BY ts IS NULL, ts DESC, id DESC LIMIT ?{} OFFSET ?{}",
+            filter,
+            args.len() + 1,
+            args.len() + 2
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let records = stmt
+            .query_map(rusqlite::params_from_iter(page_args.iter()), |row| {
+                let ts: Option<i64> = row.get(0)?;
+                let outcome: String = row.get(1)?;
+                Ok(ActivityRecord {
+                    v: row.get(8)?,
+                    timestamp: ts.and_then(DateTime::from_timestamp_millis),
+                    outcome: outcome.parse().unwrap_or(Outcome::Success),
+                    source: row.get(2)?,
+                    dest: row.get(3)?,
+                    rule: row.get(4)?,
+                    rule_id: row.get(9)?,
+                    size_bytes: row.get::<_, Option<i64>>(5)?.map(|n| n as u64),
+                    symlink_info: row.get(6)?,
+                    error: row.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(ActivityPage {
+            records,
+            total: total as usize,
+        })
+    }
+
+    /// Totals and breakdowns over successful moves, with a per-day series
+    /// covering the last `days` days. Days and hours are in local time.
+    pub fn stats(&self, days: u32) -> Result<ActivityStats> {
+        let today = Local::now().date_naive();
+        let week_start =
+            today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+        let series_start = today - chrono::Duration::days(days.max(1) as i64 - 1);
+        let ms = |date: NaiveDate| local_midnight(date).map_or(0, |t| t.timestamp_millis());
+
+        let (total, bytes): (i64, i64) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(size_bytes), 0) FROM activity \
+             WHERE outcome = 'success'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let failed: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM activity WHERE outcome = 'failed'",
+            [],
+            |row| row.get(0),
+        )?;
+        let count_since = |from: i64| -> Result<usize> {
+            let n: i64 = self.conn.query_row(
+                "SELECT COUNT(*) FROM activity WHERE outcome = 'success' AND ts >= ?1",
+                [from],
+                |row| row.get(0),
+            )?;
+            Ok(n as usize)
+        };
+
+        let by_rule = self.breakdown("COALESCE(rule, '')")?;
+        let by_extension = self.breakdown("ext")?;
+
+        let mut per_day: std::collections::HashMap<String, (usize, u64)> = self
+            .conn
+            .prepare(
+                "SELECT date(ts / 1000, 'unixepoch', 'localtime') AS d, COUNT(*), \
+                 COALESCE(SUM(size_bytes), 0) FROM activity \
+                 WHERE outcome = 'success' AND ts >= ?1 GROUP BY d",
+            )?
+            .query_map([ms(series_start)], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    (row.get::<_, i64>(1)? as usize, row.get::<_, i64>(2)? as u64),
+                ))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        let daily = series_start
+            .iter_days()
+            .take_while(|d| *d <= today)
+            .map(|date| {
+                let (count, bytes) = per_day
+                    .remove(&date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                DailyCount { date, count, bytes }
+            })
+            .collect();
+
+        let busiest_hour = self
+            .conn
+            .query_row(
+                "SELECT CAST(strftime('%H', ts / 1000, 'unixepoch', 'localtime') AS INTEGER) \
+                 AS h FROM activity WHERE outcome = 'success' AND ts IS NOT NULL \
+                 GROUP BY h ORDER BY COUNT(*) DESC, h LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(ActivityStats {
+            total_files_moved: total as usize,
+            files_moved_today: count_since(ms(today))?,
+            files_moved_this_week: count_since(ms(week_start))?,
+            total_bytes_moved: bytes as u64,
+            total_failed: failed as usize,
+            most_active_rule: by_rule.first().map(|b| b.key.clone()),
+            busiest_hour,
+            by_rule,
+            by_extension,
+            daily,
+        })
+    }
+
+    fn breakdown(&self, column: &str) -> Result<Vec<Breakdown>> {
+        let sql = format!(
+            "SELECT {column} AS k, COUNT(*), COALESCE(SUM(size_bytes), 0) FROM activity \
+             WHERE outcome = 'success' GROUP BY k ORDER BY COUNT(*) DESC, k"
+        );
+        let rows = self
+            .conn
+            .prepare(&sql)?
+            .query_map([], |row| {
+                Ok(Breakdown {
+                    key: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as usize,
+                    bytes: row.get::<_, i64>(2)? as u64,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(rows)
+    }
+
+    /// Writes every record matching `query` (ignoring its paging), oldest
+    /// first, and returns how many were written.
+    pub fn export(
+        &self,
+        query: &ActivityQuery,
+        format: ExportFormat,
+        mut out: impl Write,
+    ) -> Result<usize> {
+        let all = ActivityQuery {
+            offset: 0,
+            limit: Some(i64::MAX as usize),
+            ..query.clone()
+        };
+        let rows: Vec<ExportRow> = self
+            .query(&all)?
+            .records
+            .into_iter()
+            .rev()
+            .map(ExportRow::from)
+            .collect();
+        match format {
+            ExportFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, &rows)?;
+                writeln!(out)?;
+            }
+            ExportFormat::Csv => {
+                writeln!(out, "{}", ExportRow::CSV_HEADER.join(","))?;
+                for row in &rows {
+                    let line: Vec<String> = row.csv_fields().iter().map(|f| csv_field(f)).collect();
+                    writeln!(out, "{}", line.join(","))?;
+                }
+            }
+        }
+        out.flush()?;
+        Ok(rows.len())
+    }
+
+    /// Clears the log and the index together.
+    pub fn clear(&mut self) -> Result<()> {
+        self.log.clear()?;
+        self.sync()?;
+        Ok(())
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// `origin` of rows read from the live log
+const LIVE_ORIGIN: &str = "";
+
+/// Adds `record` unless it is already indexed. A record seen again in an
+/// archive, usually after the live log rotated, moves to that archive so
+/// its row goes when the archive is pruned.
+fn insert(conn: &Connection, record: &ActivityRecord, origin: &str) -> Result<usize> {
+    let name = record.file_name();
+    let ext = Path::new(&name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let n = conn.execute(
+        "INSERT OR IGNORE INTO activity \
+         (ts, outcome, source, dest, rule, rule_id, file_name, ext, size_bytes, symlink_info, \
+         error, v, origin) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            record.timestamp.map(|t| t.timestamp_millis()),
+            record.outcome.as_str(),
+            record.source,
+            record.dest,
+            record.rule,
+            record.rule_id,
+            name,
+            ext,
+            record.size_bytes.map(|n| n as i64),
+            record.symlink_info,
+            record.error,
+            record.v,
+            origin,
+        ],
+    )?;
+    if n == 0 && origin != LIVE_ORIGIN {
+        conn.execute(
+            "UPDATE activity SET origin = ?5 \
+             WHERE COALESCE(ts, -1) = COALESCE(?1, -1) AND source = ?2 \
+             AND COALESCE(dest, '') = COALESCE(?3, '') AND outcome = ?4",
+            params![
+                record.timestamp.map(|t| t.timestamp_millis()),
+                record.source,
+                record.dest,
+                record.outcome.as_str(),
+                origin,
+            ],
+        )?;
+    }
+    Ok(n)
+}
+
+fn get_meta(conn: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+            row.get(0)
+        })
+        .optional()?)
+}
+
+fn set_meta(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2) \
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+/// Reads complete lines from the live log past `offset`. The first line is
+/// remembered so a rotated or cleared log is read again from the start.
+fn read_live_from(
+    path: &Path,
+    offset: Option<u64>,
+    head: Option<String>,
+) -> Result<(Vec<ActivityRecord>, u64, String)> {
+    let mut file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok((Vec::new(), 0, String::new()))
+        }
+        Err(e) => return Err(e).with_context(|| format!("opening {}", path.display())),
+    };
+    let mut first = String::new();
+    BufReader::new(&mut file).read_line(&mut first)?;
+    let len = file.metadata()?.len();
+    let offset = match offset {
+        Some(o) if o <= len && head.as_deref() == Some(first.as_str()) => o,
+        _ => 0,
+    };
+
+    file.seek(SeekFrom::Start(offset))?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    // A writer may be midway through a line; leave it for the next sync
+    let complete = data.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let records = crate::activity::read_records(&data[..complete]);
+    Ok((records, offset + complete as u64, first))
+}
+
+/// Parses the start of a date range: RFC 3339, or `YYYY-MM-DD` as local
+/// midnight.
+pub fn parse_since(input: &str) -> Result<DateTime<Utc>> {
+    parse_bound(input, false)
+}
+
+/// Parses the end of a date range. A bare `YYYY-MM-DD` includes that whole
+/// day.
+pub fn parse_until(input: &str) -> Result<DateTime<Utc>> {
+    parse_bound(input, true)
+}
+
+fn parse_bound(input: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(input) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") else {
+        bail!("invalid date '{}': expected YYYY-MM-DD or RFC 3339", input);
+    };
+    let date = if end_of_day {
+        date.succ_opt().unwrap_or(date)
+    } else {
+        date
+    };
+    match local_midnight(date) {
+        Some(t) => Ok(t),
+        None => bail!("invalid date '{}'", input),
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Utc>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity::RECORD_VERSION;
+
+    fn record(source: &str, rule: &str, outcome: Outcome, ts: DateTime<Utc>) -> ActivityRecord {
+        ActivityRecord {
+            v: RECORD_VERSION,
+            timestamp: Some(ts),
+            outcome,
+            source: source.to_string(),
+            dest: Some(format!("/sorted/{}", source)),
+            rule: Some(rule.to_string()),
+            rule_id: Some(rule.to_lowercase()),
+            size_bytes: Some(1),
+            symlink_info: None,
+            error: None,
+        }
+    }
+
+    fn sources(page: &ActivityPage) -> Vec<&str> {
+        page.records.iter().map(|r| r.source.as_str()).collect()
+    }
+
+    fn seeded() -> (tempfile::TempDir, ActivityStore) {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(tmp.path().join("recent_moves.log"));
+        let now = Utc::now();
+        let hour = chrono::Duration::hours(1);
+        log.append(&[
+            record("report.PDF", "Docs", Outcome::Success, now - hour * 50),
+            record("photo.jpg", "Images", Outcome::Success, now - hour * 3),
+            record("notes.pdf", "Docs", Outcome::Failed, now - hour * 2),
+            record("report_100%.txt", "Docs", Outcome::Success, now - hour),
+        ])
+        .unwrap();
+        let store = ActivityStore::open(log).unwrap();
+        (tmp, store)
+    }
+
+    #[test]
+    fn test_old_index_is_rebuilt() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(tmp.path().join("recent_moves.log"));
+        log.append(&[record("a.pdf", "Docs", Outcome::Success, Utc::now())])
+            .unwrap();
+        let db = ActivityStore::db_path(&log);
+        {
+            // An index from before rule ids, with a stale row
+            let conn = Connection::open(&db).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE activity (id INTEGER PRIMARY KEY, source TEXT); \
+                 INSERT INTO activity (source) VALUES ('stale');",
+            )
+            .unwrap();
+        }
+        let store = ActivityStore::open(log).unwrap();
+        let all = store.query(&ActivityQuery::default()).unwrap();
+        assert_eq!(sources(&all), ["a.pdf"]);
+        assert_eq!(all.records[0].rule_id.as_deref(), Some("docs"));
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let (_tmp, store) = seeded();
+
+        let all = store.query(&ActivityQuery::default()).unwrap();
+        assert_eq!(all.total, 4);
+        assert_eq!(
+            sources(&all),
+            ["report_100%.txt", "notes.pdf", "photo.jpg", "report.PDF"]
+        );
+
+        let docs = store
+            .query(&ActivityQuery {
+                rule: Some("Docs".into()),
+                limit: Some(1),
+                offset: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(docs.total, 3);
+        assert_eq!(sources(&docs), ["notes.pdf"]);
+
+        let images = store
+            .query(&ActivityQuery {
+                rule_id: Some("images".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(sources(&images), ["photo.jpg"]);
+        assert_eq!(images.records[0].rule_id.as_deref(), Some("images"));
+
+        let pdf = store
+            .query(&ActivityQuery {
+                extension: Some(".pdf".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(sources(&pdf), ["notes.pdf", "report.PDF"]);
+
+        let failed = store
+            .query(&ActivityQuery {
+                outcome: Some(Outcome::Failed),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(sources(&failed), ["notes.pdf"]);
+
+        let recent = store
+            .query(&ActivityQuery {
+                since: Some(Utc::now() - chrono::Duration::hours(24)),
+                until: Some(Utc::now() - chrono::Duration::minutes(90)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(sources(&recent), ["notes.pdf", "photo.jpg"]);
+
+        let text = store
+            .query(&ActivityQuery {
+                text: Some("REPORT".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(text.total, 2);
+        // LIKE wildcards in the search text are literal
+        let text = store
+            .query(&ActivityQuery {
+                text: Some("100%".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(sources(&text), ["report_100%.txt"]);
+        let text = store
+            .query(&ActivityQuery {
+                text: Some("sorted/photo".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(sources(&text), ["photo.jpg"]);
+    }
+
+    #[test]
+    fn test_sync_is_incremental_and_survives_rotation() {
+        let (_tmp, mut store) = seeded();
+        assert_eq!(store.sync().unwrap(), 0);
+
+        let log = store.log().clone();
+        log.append(&[record("a.zip", "Archives", Outcome::Success, Utc::now())])
+            .unwrap();
+        // A partial line from a writer still in progress is left for later
+        let mut f = fs::OpenOptions::new()
+            .append(true)
+            .open(log.path())
+            .unwrap();
+        f.write_all(b"{\"v\":1,").unwrap();
+        assert_eq!(store.sync().unwrap(), 1);
+
+        fs::write(log.path(), "").unwrap();
+        log.append(&[record("b.zip", "Archives", Outcome::Success, Utc::now())])
+            .unwrap();
+        log.rotate().unwrap();
+        log.append(&[record("c.zip", "Archives", Outcome::Success, Utc::now())])
+            .unwrap();
+        assert_eq!(store.sync().unwrap(), 2);
+        assert_eq!(store.query(&ActivityQuery::default()).unwrap().total, 7);
+
+        store.clear().unwrap();
+        assert_eq!(store.query(&ActivityQuery::default()).unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_rows_follow_archives_not_reader_retention() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(tmp.path().join("recent_moves.log"));
+        let old = Utc::now() - chrono::Duration::days(200);
+        log.append(&[record("old.pdf", "Docs", Outcome::Success, old)])
+            .unwrap();
+        log.rotate().unwrap();
+
+        // A reader with the default 90 day retention keeps the archived record
+        let mut store = ActivityStore::open(log.clone()).unwrap();
+        assert_eq!(
+            sources(&store.query(&ActivityQuery::default()).unwrap()),
+            ["old.pdf"]
+        );
+
+        // Once max_archives prunes that archive, its rows go too
+        let log = log.with_retention(crate::activity::ActivityRetention {
+            max_archives: Some(1),
+            ..Default::default()
+        });
+        log.append(&[record("new.pdf", "Docs", Outcome::Success, Utc::now())])
+            .unwrap();
+        log.rotate().unwrap();
+        store.sync().unwrap();
+        assert_eq!(
+            sources(&store.query(&ActivityQuery::default()).unwrap()),
+            ["new.pdf"]
+        );
+    }
+
+    #[test]
+    fn test_parse_bounds() {
+        let since = parse_since("2024-03-01").unwrap().with_timezone(&Local);
+        assert_eq!(
+            since.format("%Y-%m-%d %H:%M").to_string(),
+            "2024-03-01 00:00"
+        );
+        let until = parse_until("2024-03-01").unwrap().with_timezone(&Local);
+        assert_eq!(
+            until.format("%Y-%m-%d %H:%M").to_string(),
+            "2024-03-02 00:00"
+        );
+        assert_eq!(
+            parse_since("2024-03-01T10:00:00Z").unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap()
+        );
+        assert!(parse_since("March").is_err());
+    }
+
+    #[test]
+    fn test_stats() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(tmp.path().join("recent_moves.log"));
+        let today = Local::now().date_naive();
+        let start = local_midnight(today).unwrap();
+        let secs = chrono::Duration::seconds;
+        let day = chrono::Duration::days(1);
+        let sized = |mut r: ActivityRecord, size| {
+            r.size_bytes = Some(size);
+            r
+        };
+        log.append(&[
+            sized(
+                record("old.zip", "Archives", Outcome::Success, start - day * 40),
+                100,
+            ),
+            sized(
+                record("y.pdf", "Docs", Outcome::Success, start - day + secs(5)),
+                10,
+            ),
+            sized(
+                record("a.pdf", "Docs", Outcome::Success, start + secs(1)),
+                20,
+            ),
+            sized(
+                record("b.jpg", "Images", Outcome::Success, start + secs(2)),
+                30,
+            ),
+            record("c.pdf", "Docs", Outcome::Failed, start + secs(3)),
+        ])
+        .unwrap();
+        let store = ActivityStore::open(log).unwrap();
+        let stats = store.stats(30).unwrap();
+
+        assert_eq!(stats.total_files_moved, 4);
+        assert_eq!(stats.total_failed, 1);
+        assert_eq!(stats.total_bytes_moved, 160);
+        assert_eq!(stats.files_moved_today, 2);
+        let yesterday_in_week = today.weekday() != chrono::Weekday::Mon;
+        assert_eq!(
+            stats.files_moved_this_week,
+            if yesterday_in_week { 3 } else { 2 }
+        );
+        assert_eq!(stats.most_active_rule.as_deref(), Some("Docs"));
+        assert_eq!(stats.busiest_hour, Some(0));
+        assert_eq!(
+            stats.by_rule[0],
+            Breakdown {
+                key: "Docs".into(),
+                count: 2,
+                bytes: 30
+            }
+        );
+        assert_eq!(stats.by_extension[0].key, "pdf");
+        assert_eq!(stats.by_extension.len(), 3);
+
+        assert_eq!(stats.daily.len(), 30);
+        assert_eq!(stats.daily.last().unwrap().date, today);
+        assert_eq!(stats.daily.last().unwrap().count, 2);
+        assert_eq!(stats.daily[28].count, 1);
+        assert_eq!(stats.daily.iter().map(|d| d.count).sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn test_export() {
+        let (_tmp, store) = seeded();
+        let docs = ActivityQuery {
+            rule: Some("Docs".into()),
+            limit: Some(1),
+            ..Default::default()
+        };
+
+        let mut csv = Vec::new();
+        assert_eq!(store.export(&docs, ExportFormat::Csv, &mut csv).unwrap(), 3);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,filename,source_path,dest_path,rule_name,rule_id,status,size_bytes,symlink_info,error"
+        );
+        assert_eq!(lines.len(), 4);
+        // Oldest first
+        assert!(
+            lines[1].contains(",report.PDF,report.PDF,/sorted/report.PDF,Docs,docs,success,1,,")
+        );
+        assert!(lines[2].contains(",failed,"));
+
+        let mut json = Vec::new();
+        store
+            .export(&ActivityQuery::default(), ExportFormat::Json, &mut json)
+            .unwrap();
+        let rows: Vec<serde_json::Value> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[3]["filename"], "report_100%.txt");
+        assert_eq!(rows[3]["rule_name"], "Docs");
+        assert_eq!(rows[3]["rule_id"], "docs");
+        assert!(rows[3]["timestamp"].as_str().unwrap().ends_with('Z'));
+
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!("csv".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+}
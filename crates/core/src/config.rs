@@ -1,3 +1,4 @@
+use crate::paths::expand_env;
 use crate::types::WorkspaceConfig;
 use anyhow::{bail, Context, Result};
 use std::collections::HashSet;
@@ -5,7 +6,12 @@ use std::fs;
 use std::path::Path;
 
 pub fn load_config(path: impl AsRef<Path>) -> Result<WorkspaceConfig> {
-    let p = path.as_ref();
+    let mut cfg = parse_config(path.as_ref())?;
+    expand_service_env(&mut cfg)?;
+    Ok(cfg)
+}
+
+fn parse_config(p: &Path) -> Result<WorkspaceConfig> {
     let content = fs::read_to_string(p).with_context(|| format!("read {}", p.display()))?;
     let ext = p
         .extension()
@@ -35,6 +41,22 @@ pub fn load_config(path: impl AsRef<Path>) -> Result<WorkspaceConfig> {
     }
 }
 
+/// Expands environment references in each service's `cwd` and `env` values.
+fn expand_service_env(cfg: &mut WorkspaceConfig) -> Result<()> {
+    for s in cfg.services.iter_mut() {
+        if let Some(cwd) = s.cwd.as_mut() {
+            *cwd = expand_env(cwd).with_context(|| format!("service '{}': cwd", s.name))?;
+        }
+        if let Some(env) = s.env.as_mut() {
+            for (key, value) in env.iter_mut() {
+                *value = expand_env(value)
+                    .with_context(|| format!("service '{}': env {}", s.name, key))?;
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn validate_config(cfg: &WorkspaceConfig) -> Result<()> {
     let mut names = HashSet::new();
     for s in &cfg.services {
@@ -146,6 +168,50 @@ services:
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_load_config_expands_cwd_and_env() {
+        let Some(home) = crate::paths::home_dir() else {
+            return;
+        };
+        let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        writeln!(
+            file,
+            r#"
+services:
+  - name: web
+    command: echo hello
+    cwd: "~/web"
+    env:
+      PORT: "${{HARBOR_TEST_SVC_MISSING:-3000}}"
+"#
+        )
+        .unwrap();
+
+        let cfg = load_config(file.path()).unwrap();
+        let expected = format!("{}/web", home.display());
+        assert_eq!(cfg.services[0].cwd.as_deref(), Some(expected.as_str()));
+        assert_eq!(cfg.services[0].env.as_ref().unwrap()["PORT"], "3000");
+    }
+
+    #[test]
+    fn test_load_config_missing_var_names_service() {
+        let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        writeln!(
+            file,
+            r#"
+services:
+  - name: api
+    command: echo hello
+    cwd: "%HARBOR_TEST_SVC_MISSING%"
+"#
+        )
+        .unwrap();
+
+        let msg = format!("{:#}", load_config(file.path()).unwrap_err());
+        assert!(msg.contains("service 'api'"));
+        assert!(msg.contains("HARBOR_TEST_SVC_MISSING"));
+    }
+
     #[test]
     fn test_validate_config_duplicate() {
         let s = Service {
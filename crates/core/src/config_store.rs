@@ -0,0 +1,533 @@
+use crate::downloads::{load_downloads_config, parse_downloads_config, DownloadsConfig};
+use anyhow::Context;
+use serde_yaml::{Mapping, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigStoreError {
+    /// The file on disk no longer matches the version this store last loaded or wrote.
+    #[error("{} was changed by another program; reload it before saving", .0.display())]
+    Conflict(PathBuf),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Identifies a version of a file by its metadata and a hash of its content,
+/// so same-size edits within the filesystem's mtime granularity still count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Fingerprint {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+impl Fingerprint {
+    pub(crate) fn read(path: &Path) -> Option<Self> {
+        let content = fs::read(path).ok()?;
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        Some(Self {
+            modified,
+            len: content.len() as u64,
+            hash: hasher.finish(),
+        })
+    }
+
+    /// Whether the file still has this content. A new length settles it
+    /// early; equal metadata does not, so the content is always hashed then.
+    fn still_matches(&self, path: &Path) -> bool {
+        if fs::metadata(path).is_ok_and(|meta| meta.len() != self.len) {
+            return false;
+        }
+        Fingerprint::read(path).map(|fp| fp.hash) == Some(self.hash)
+    }
+}
+
+/// Reads and writes `harbor.downloads.yaml` for every frontend.
+///
+/// Writes go to a temporary file that is renamed over the config, so a crash
+/// never leaves a half-written file behind. Writers take an advisory lock on a
+/// sibling `.lock` file, keep the last few versions as `.bak.N` files, and refuse
+/// to overwrite the config if someone else changed it since this store last saw it.
+///
+/// Callers hold configs with `${VAR}`, `~` and `$$` already expanded. Saving
+/// merges their changes into the document on disk, so values they did not
+/// touch keep their original form and unset options stay out of the file.
+pub struct ConfigStore {
+    path: PathBuf,
+    backups: usize,
+    last_seen: Mutex<Option<Fingerprint>>,
+}
+
+impl ConfigStore {
+    pub const DEFAULT_BACKUPS: usize = 3;
+
+    /// Creates a store for `path`. The file currently on disk (if any) is taken as
+    /// the version the caller has already loaded.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let last_seen = Fingerprint::read(&path);
+        Self {
+            path,
+            backups: Self::DEFAULT_BACKUPS,
+            last_seen: Mutex::new(last_seen),
+        }
+    }
+
+    /// Sets how many rotating backups to keep (0 disables them).
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of the `n`th most recent backup, starting at 1.
+    pub fn backup_path(&self, n: usize) -> PathBuf {
+        Self::sibling(&self.path, &format!("bak.{}", n))
+    }
+
+    fn sibling(path: &Path, suffix: &str) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(suffix);
+        path.with_file_name(name)
+    }
+
+    /// Loads the config and remembers its version for later saves. Rule ids
+    /// missing from the file are written to it first.
+    pub fn load(&self) -> anyhow::Result<DownloadsConfig> {
+        self.resync();
+        self.persist_rule_ids()?;
+        load_downloads_config(&self.path)
+    }
+
+    /// Writes the ids `load` derives for rules that have none in the file, so a
+    /// rule renamed in the file keeps its id, stats and history. Returns whether
+    /// the file was rewritten.
+    pub fn persist_rule_ids(&self) -> Result<bool, ConfigStoreError> {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return Ok(false);
+        };
+        let (Ok(raw), Ok(cfg)) = (
+            serde_yaml::from_str::<Value>(&content),
+            parse_downloads_config(&content),
+        ) else {
+            // Reported by whoever loads it
+            return Ok(false);
+        };
+        let new = serde_yaml::to_value(&cfg).context("serialize downloads config")?;
+        if self.merge_into_file(&new) == raw {
+            return Ok(false);
+        }
+        self.save(&cfg)?;
+        Ok(true)
+    }
+
+    /// Accepts whatever is currently on disk as the expected version, e.g. after
+    /// the watcher reloaded an external edit.
+    pub fn resync(&self) {
+        let fp = Fingerprint::read(&self.path);
+        *self.last_seen.lock().unwrap_or_else(|e| e.into_inner()) = fp;
+    }
+
+    /// Writes `cfg`, failing with [`ConfigStoreError::Conflict`] if the file was
+    /// modified externally since it was last loaded or saved through this store.
+    pub fn save(&self, cfg: &DownloadsConfig) -> Result<(), ConfigStoreError> {
+        self.write(cfg, true)
+    }
+
+    /// Writes `cfg` without the external-edit check, e.g. for "reset to defaults".
+    pub fn save_force(&self, cfg: &DownloadsConfig) -> Result<(), ConfigStoreError> {
+        self.write(cfg, false)
+    }
+
+    fn write(&self, cfg: &DownloadsConfig, check: bool) -> Result<(), ConfigStoreError> {
+        let new = serde_yaml::to_value(cfg).context("serialize downloads config")?;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+        }
+
+        let mut last_seen = self.last_seen.lock().unwrap_or_else(|e| e.into_inner());
+        let _lock = self.lock()?;
+
+        if check && self.path.exists() {
+            let unchanged = last_seen
+                .as_ref()
+                .map(|fp| fp.still_matches(&self.path))
+                .unwrap_or(false);
+            if !unchanged {
+                return Err(ConfigStoreError::Conflict(self.path.clone()));
+            }
+        }
+
+        let yaml = serde_yaml::to_string(&self.merge_into_file(&new))
+            .context("serialize downloads config")?;
+        self.rotate_backups()?;
+
+        let tmp = Self::sibling(&self.path, "tmp");
+        {
+            let mut f = File::create(&tmp).with_context(|| format!("create {}", tmp.display()))?;
+            f.write_all(yaml.as_bytes())
+                .and_then(|_| f.sync_all())
+                .with_context(|| format!("write {}", tmp.display()))?;
+        }
+        fs::rename(&tmp, &self.path).with_context(|| format!("replace {}", self.path.display()))?;
+
+        *last_seen = Fingerprint::read(&self.path);
+        Ok(())
+    }
+
+    /// `new` written over the current file (see [`patch_document`]), or just
+    /// `new` without its unset options if there is no readable file.
+    fn merge_into_file(&self, new: &Value) -> Value {
+        let base = fs::read_to_string(&self.path).ok().and_then(|content| {
+            let raw: Value = serde_yaml::from_str(&content).ok()?;
+            let loaded = serde_yaml::to_value(parse_downloads_config(&content).ok()?).ok()?;
+            Some((raw, loaded))
+        });
+        let (raw, loaded) = match &base {
+            Some((raw, loaded)) => (Some(raw), loaded),
+            None => (None, &Value::Null),
+        };
+        let mut rules = HashMap::new();
+        index_rules(raw, loaded, &mut rules);
+        let mut merged = patch_document(raw, loaded, new, &rules).unwrap_or(Value::Null);
+        fill_rule_ids(&mut merged, new);
+        merged
+    }
+
+    /// Blocks until no other Harbor process is writing the config.
+    fn lock(&self) -> anyhow::Result<File> {
+        let lock_path = Self::sibling(&self.path, "lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("open {}", lock_path.display()))?;
+        file.lock()
+            .with_context(|| format!("lock {}", lock_path.display()))?;
+        Ok(file)
+    }
+
+    fn rotate_backups(&self) -> anyhow::Result<()> {
+        if self.backups == 0 || !self.path.exists() {
+            return Ok(());
+        }
+        let _ = fs::remove_file(self.backup_path(self.backups));
+        for n in (1..self.backups).rev() {
+            let from = self.backup_path(n);
+            if from.exists() {
+                fs::rename(&from, self.backup_path(n + 1))
+                    .with_context(|| format!("rotate {}", from.display()))?;
+            }
+        }
+        fs::copy(&self.path, self.backup_path(1))
+            .with_context(|| format!("back up {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// Rules found anywhere in the loaded config by id, with their raw form. A rule
+/// moved between `rules` and a profile still keeps its original values.
+type RuleIndex<'a> = HashMap<&'a str, (Option<&'a Value>, &'a Value)>;
+
+fn rule_id(value: &Value) -> Option<&str> {
+    value.as_mapping()?.get("id")?.as_str()
+}
+
+/// Walks `loaded` and its raw form side by side; `load` never reorders or
+/// drops entries, so sequence positions line up.
+fn index_rules<'a>(raw: Option<&'a Value>, loaded: &'a Value, out: &mut RuleIndex<'a>) {
+    match loaded {
+        Value::Sequence(items) => {
+            let raw = raw.and_then(Value::as_sequence);
+            for (i, item) in items.iter().enumerate() {
+                let raw_item = raw.and_then(|r| r.get(i));
+                if let Some(id) = rule_id(item) {
+                    out.insert(id, (raw_item, item));
+                }
+                index_rules(raw_item, item, out);
+            }
+        }
+        Value::Mapping(map) => {
+            let raw = raw.and_then(Value::as_mapping);
+            for (key, value) in map {
+                index_rules(raw.and_then(|r| r.get(key)), value, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The value to write for `new`, given the same value as it was on disk
+/// (`raw`, `None` if absent) and as the caller loaded it (`loaded`). Unchanged
+/// values keep their raw form; `None` means leave the key out.
+fn patch_document(
+    raw: Option<&Value>,
+    loaded: &Value,
+    new: &Value,
+    rules: &RuleIndex,
+) -> Option<Value> {
+    if new == loaded {
+        return raw.cloned();
+    }
+    match new {
+        Value::Null => None,
+        Value::Mapping(map) => {
+            let raw_map = raw.and_then(Value::as_mapping);
+            let mut out = Mapping::new();
+            // Keys already in the file first, in the file's order
+            let keys = raw_map
+                .into_iter()
+                .flat_map(|m| m.keys())
+                .filter(|k| map.contains_key(*k))
+                .chain(
+                    map.keys()
+                        .filter(|k| !raw_map.is_some_and(|m| m.contains_key(*k))),
+                );
+            for key in keys {
+                let raw_value = raw_map.and_then(|m| m.get(key));
+                let loaded_value = loaded.get(key).unwrap_or(&Value::Null);
+                if let Some(patched) = patch_document(raw_value, loaded_value, &map[key], rules) {
+                    out.insert(key.clone(), patched);
+                }
+            }
+            Some(Value::Mapping(out))
+        }
+        Value::Sequence(items) if items.iter().any(|item| rule_id(item).is_some()) => Some(
+            items
+                .iter()
+                .filter_map(|item| {
+                    let (raw_item, loaded_item) = rule_id(item)
+                        .and_then(|id| rules.get(id).copied())
+                        .unwrap_or((None, &Value::Null));
+                    patch_document(raw_item, loaded_item, item, rules)
+                })
+                .collect(),
+        ),
+        _ => Some(new.clone()),
+    }
+}
+
+/// Writes the id of every rule in `new` into its counterpart in `out`, even
+/// when `load` only derived it, so a rule keeps its id when renamed. `out` lines up with `new`, as [`patch_document`] keeps order.
+fn fill_rule_ids(out: &mut Value, new: &Value) {
+    match (out, new) {
+        (Value::Sequence(out), Value::Sequence(new)) => {
+            for (out_item, new_item) in out.iter_mut().zip(new) {
+                fill_rule_ids(out_item, new_item);
+            }
+        }
+        (Value::Mapping(out), Value::Mapping(new)) => {
+            for (key, new_value) in new {
+                if let Some(out_value) = out.get_mut(key) {
+                    fill_rule_ids(out_value, new_value);
+                }
+            }
+            // Also replaces an empty or duplicate id that `load` gave a new one
+            if let Some(id) = new
+                .get("id")
+                .filter(|id| id.as_str().is_some_and(|s| !s.is_empty()))
+            {
+                if out.get("id") != Some(id) {
+                    out.insert("id".into(), id.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::downloads::default_config;
+    use tempfile::TempDir;
+
+    fn store_in(dir: &TempDir) -> ConfigStore {
+        ConfigStore::new(dir.path().join("harbor.downloads.yaml"))
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new().unwrap();
+        let store = store_in(&dir);
+        let mut cfg = default_config();
+        cfg.min_age_secs = Some(42);
+        store.save(&cfg).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.min_age_secs, Some(42));
+        assert!(!dir.path().join("harbor.downloads.yaml.tmp").exists());
+    }
+
+    #[test]
+    fn test_save_keeps_templates_and_unset_options() {
+        if std::env::var_os("HOME").is_none() || crate::paths::home_dir().is_none() {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("harbor.downloads.yaml");
+        fs::write(
+            &path,
+            "download_dir: \"${HOME}/Downloads\"\n\
+             rules:\n\
+             - name: Docs\n  extensions: [pdf]\n  target_dir: \"~/Docs/$$keep\"\n\
+             - name: Images\n  extensions: [png]\n  target_dir: /img\n",
+        )
+        .unwrap();
+        let store = ConfigStore::new(&path);
+
+        let mut cfg = store.load().unwrap();
+        assert!(cfg.rules[0].target_dir.ends_with("$keep"));
+        cfg.paused_until = Some(chrono::Utc::now());
+        cfg.rules[1].target_dir = "/pictures".into();
+        store.save(&cfg).unwrap();
+
+        let yaml = fs::read_to_string(&path).unwrap();
+        assert!(yaml.contains("${HOME}/Downloads"), "{}", yaml);
+        assert!(yaml.contains("~/Docs/$$keep"), "{}", yaml);
+        assert!(yaml.contains("/pictures"), "{}", yaml);
+        assert!(yaml.contains("paused_until"), "{}", yaml);
+        assert!(
+            yaml.contains(&format!("id: {}", cfg.rules[1].id)),
+            "{}",
+            yaml
+        );
+        assert!(!yaml.contains("null"), "{}", yaml);
+        assert!(!yaml.contains("enabled"), "{}", yaml);
+
+        let reloaded = store.load().unwrap();
+        assert_eq!(reloaded.download_dir, cfg.download_dir);
+        assert_eq!(reloaded.rules[0].target_dir, cfg.rules[0].target_dir);
+        assert_eq!(reloaded.paused_until, cfg.paused_until);
+
+        // Clearing an option takes it out of the file again, and a renamed
+        // rule keeps the id derived from its old name
+        let mut cfg = reloaded;
+        cfg.paused_until = None;
+        cfg.rules[0].name = "Papers".into();
+        store.save(&cfg).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("paused_until"));
+        assert_eq!(store.load().unwrap().rules[0].id, cfg.rules[0].id);
+    }
+
+    #[test]
+    fn test_load_persists_derived_rule_ids() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("harbor.downloads.yaml");
+        fs::write(
+            &path,
+            "download_dir: /dl\n\
+             rules:\n\
+             - name: Docs\n  extensions: [pdf]\n  target_dir: /docs\n\
+             - name: Images\n  id: ''\n  extensions: [png]\n  target_dir: /img\n",
+        )
+        .unwrap();
+        let store = ConfigStore::new(&path);
+        let cfg = store.load().unwrap();
+        let yaml = fs::read_to_string(&path).unwrap();
+        for rule in &cfg.rules {
+            assert!(yaml.contains(&format!("id: {}", rule.id)), "{}", yaml);
+        }
+        assert!(!store.persist_rule_ids().unwrap());
+
+        // Renaming the rule in the file before anything else saves keeps its id
+        fs::write(&path, yaml.replace("name: Docs", "name: Papers")).unwrap();
+        let renamed = store.load().unwrap();
+        assert_eq!(renamed.rules[0].name, "Papers");
+        assert_eq!(renamed.rules[0].id, cfg.rules[0].id);
+    }
+
+    #[test]
+    fn test_rotating_backups() {
+        let dir = TempDir::new().unwrap();
+        let store = store_in(&dir).with_backups(2);
+        let mut cfg = default_config();
+        for age in 1..=4 {
+            cfg.min_age_secs = Some(age);
+            store.save(&cfg).unwrap();
+        }
+
+        let backup = |n| load_downloads_config(store.backup_path(n)).unwrap();
+        assert_eq!(backup(1).min_age_secs, Some(3));
+        assert_eq!(backup(2).min_age_secs, Some(2));
+        assert!(!store.backup_path(3).exists());
+    }
+
+    #[test]
+    fn test_external_edit_is_a_conflict() {
+        let dir = TempDir::new().unwrap();
+        let store = store_in(&dir);
+        let cfg = default_config();
+        store.save(&cfg).unwrap();
+
+        fs::write(store.path(), "download_dir: elsewhere\nrules: []\n").unwrap();
+        assert!(matches!(
+            store.save(&cfg),
+            Err(ConfigStoreError::Conflict(_))
+        ));
+        // The external edit survives
+        assert_eq!(store.load().unwrap().download_dir, "elsewhere");
+
+        // After loading, saving is allowed again
+        store.save(&cfg).unwrap();
+        store.save_force(&cfg).unwrap();
+    }
+
+    #[test]
+    fn test_same_size_edit_within_mtime_is_a_conflict() {
+        let dir = TempDir::new().unwrap();
+        let store = store_in(&dir);
+        let mut cfg = default_config();
+        cfg.min_age_secs = Some(10);
+        store.save(&cfg).unwrap();
+
+        let modified = fs::metadata(store.path()).unwrap().modified().unwrap();
+        let yaml = fs::read_to_string(store.path()).unwrap();
+        fs::write(store.path(), yaml.replace("10", "20")).unwrap();
+        File::options()
+            .write(true)
+            .open(store.path())
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        assert!(matches!(
+            store.save(&cfg),
+            Err(ConfigStoreError::Conflict(_))
+        ));
+        assert_eq!(store.load().unwrap().min_age_secs, Some(20));
+    }
+
+    #[test]
+    fn test_two_stores_do_not_clobber_each_other() {
+        let dir = TempDir::new().unwrap();
+        let a = store_in(&dir);
+        a.save(&default_config()).unwrap();
+        let b = store_in(&dir);
+
+        let mut from_b = default_config();
+        from_b.check_updates = Some(false);
+        b.save(&from_b).unwrap();
+
+        assert!(matches!(
+            a.save(&default_config()),
+            Err(ConfigStoreError::Conflict(_))
+        ));
+        a.resync();
+        a.save(&default_config()).unwrap();
+    }
+}
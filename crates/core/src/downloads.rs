@@ -1,27 +1,116 @@
+use crate::activity::ActivityRetention;
+use crate::config_store::Fingerprint;
+use crate::notifications::NotificationSettings;
+use crate::paths::{expand_env, normalize_separators};
+use crate::rules::assign_rule_ids;
 use crate::types::Rule;
-use anyhow::{Context, Result};
+use crate::watcher_health::WatcherHealth;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
 
 use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DownloadsConfig {
+    /// Directory watched for new downloads.
     pub download_dir: String,
+    /// Rules evaluated in order; the first match wins. These belong to the
+    /// active profile.
     pub rules: Vec<Rule>,
+    /// Files younger than this are left alone (default: 5).
     pub min_age_secs: Option<u64>,
     pub tutorial_completed: Option<bool>,
+    /// Whether the watcher starts with the app.
     pub service_enabled: Option<bool>,
     pub check_updates: Option<bool>,
     pub last_notified_version: Option<String>,
+    /// Rotation and retention of the activity log.
+    pub activity_retention: Option<ActivityRetention>,
+    /// Desktop notifications about moves, failures and unmatched files.
+    pub notifications: Option<NotificationSettings>,
+    /// The watcher skips its passes until this time, then clears it. Set by
+    /// "pause for 15 minutes" and similar.
+    #[schemars(with = "Option<String>")]
+    pub paused_until: Option<DateTime<Utc>>,
+    /// Name of the profile whose rules are in `rules` (default: "default").
+    pub active_profile: Option<String>,
+    /// Rule sets of the other profiles. Switching profiles swaps `rules`
+    /// with one of these.
+    pub profiles: Option<BTreeMap<String, Vec<Rule>>>,
 }
 
-pub type OrganizeResult = (PathBuf, PathBuf, String, Option<String>);
+/// Profile name used when `active_profile` is unset.
+pub const DEFAULT_PROFILE: &str = "default";
+
+impl DownloadsConfig {
+    pub fn active_profile(&self) -> &str {
+        self.active_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// When a timed pause ends, if one is in effect at `now`.
+    pub fn snoozed_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.paused_until.filter(|until| *until > now)
+    }
+
+    /// Every profile name, including the active one, sorted.
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .profiles
+            .iter()
+            .flat_map(|p| p.keys().cloned())
+            .collect();
+        names.push(self.active_profile().to_string());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Makes `name` the active profile, storing the current rules under the
+    /// previously active one.
+    pub fn use_profile(&mut self, name: &str) -> Result<()> {
+        if name == self.active_profile() {
+            return Ok(());
+        }
+        let Some(rules) = self.profiles.as_mut().and_then(|p| p.remove(name)) else {
+            bail!(
+                "no profile named '{}' (available: {})",
+                name,
+                self.profile_names().join(", ")
+            );
+        };
+        let previous = self.active_profile().to_string();
+        let stashed = std::mem::replace(&mut self.rules, rules);
+        self.profiles
+            .get_or_insert_with(BTreeMap::new)
+            .insert(previous, stashed);
+        self.active_profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Active rules followed by those of the other profiles.
+    fn all_rules_mut(&mut self) -> impl Iterator<Item = &mut Rule> {
+        self.rules.iter_mut().chain(
+            self.profiles
+                .iter_mut()
+                .flat_map(|p| p.values_mut().flatten()),
+        )
+    }
+}
+
+/// A move: source, destination, rule name, symlink outcome and the id of
+/// the rule, which stays valid after a rename.
+pub type OrganizeResult = (PathBuf, PathBuf, String, Option<String>, String);
 
 /// Loads and parses the downloads configuration file.
 ///
@@ -43,29 +132,36 @@ pub type OrganizeResult = (PathBuf, PathBuf, String, Option<String>);
 /// }
 /// ```
 pub fn default_config() -> DownloadsConfig {
-    let user = std::env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Public".to_string());
-    let dl = format!("{}\\Downloads", user);
-    let pictures = format!("{}\\Downloads\\Images", user);
-    let videos = format!("{}\\Downloads\\Videos", user);
-    let music = format!("{}\\Downloads\\Music", user);
-    let docs = format!("{}\\Downloads\\Documents", user);
-    let archives = format!("{}\\Downloads\\Archives", user);
-    let installers = format!("{}\\Downloads\\Installers", user);
-    let torrents = format!("{}\\Downloads\\Torrents", user);
-    let isos = format!("{}\\Downloads\\ISOs", user);
-    let dev = format!("{}\\Downloads\\Dev", user);
-    let subtitles = format!("{}\\Downloads\\Subtitles", user);
-    let webpages = format!("{}\\Downloads\\Webpages", user);
-
-    DownloadsConfig {
+    let dl = crate::paths::download_dir();
+    let sub = |name: &str| dl.join(name).to_string_lossy().into_owned();
+    let pictures = sub("Images");
+    let videos = sub("Videos");
+    let music = sub("Music");
+    let docs = sub("Documents");
+    let archives = sub("Archives");
+    let installers = sub("Installers");
+    let torrents = sub("Torrents");
+    let isos = sub("ISOs");
+    let dev = sub("Dev");
+    let subtitles = sub("Subtitles");
+    let webpages = sub("Webpages");
+    let dl = dl.to_string_lossy().into_owned();
+
+    let mut cfg = DownloadsConfig {
         download_dir: dl,
         min_age_secs: Some(5),
         tutorial_completed: Some(false),
         service_enabled: Some(true),
         check_updates: Some(true),
         last_notified_version: None,
+        activity_retention: None,
+        notifications: None,
+        paused_until: None,
+        active_profile: None,
+        profiles: None,
         rules: vec![
             Rule {
+                id: String::new(),
                 name: "Images".to_string(),
                 extensions: Some(
                     [
@@ -81,8 +177,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: pictures,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Videos".to_string(),
                 extensions: Some(
                     ["mp4", "mkv", "avi", "mov", "wmv", "webm"]
@@ -96,8 +194,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: videos,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Music".to_string(),
                 extensions: Some(
                     ["mp3", "flac", "wav", "aac", "ogg"]
@@ -111,8 +211,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: music,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Archives".to_string(),
                 extensions: Some(
                     ["zip", "rar", "7z", "tar", "gz", "xz"]
@@ -126,8 +228,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: archives,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Documents".to_string(),
                 extensions: Some(
                     [
@@ -143,8 +247,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: docs.clone(),
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Installers".to_string(),
                 extensions: Some(
                     ["exe", "msi", "msix", "dmg", "pkg", "apk"]
@@ -158,8 +264,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: installers,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "ISOs".to_string(),
                 extensions: Some(["iso"].iter().map(|s| s.to_string()).collect()),
                 pattern: None,
@@ -168,8 +276,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: isos,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Torrents".to_string(),
                 extensions: Some(["torrent"].iter().map(|s| s.to_string()).collect()),
                 pattern: None,
@@ -178,8 +288,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: torrents,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Dev".to_string(),
                 extensions: Some(
                     ["json", "env", "xml", "plist"]
@@ -193,8 +305,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: dev,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Web Pages".to_string(),
                 extensions: Some(["html", "htm"].iter().map(|s| s.to_string()).collect()),
                 pattern: None,
@@ -203,8 +317,10 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: webpages,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
             Rule {
+                id: String::new(),
                 name: "Subtitles".to_string(),
                 extensions: Some(["srt", "vtt"].iter().map(|s| s.to_string()).collect()),
                 pattern: None,
@@ -213,20 +329,31 @@ pub fn default_config() -> DownloadsConfig {
                 target_dir: subtitles,
                 create_symlink: None,
                 enabled: Some(true),
+                notify: None,
             },
         ],
-    }
+    };
+    assign_rule_ids(cfg.all_rules_mut());
+    cfg
 }
 
 pub fn load_downloads_config(path: impl AsRef<Path>) -> Result<DownloadsConfig> {
     let p = path.as_ref();
     let content = fs::read_to_string(p).with_context(|| format!("read {}", p.display()))?;
-    let mut cfg: DownloadsConfig =
-        serde_yaml::from_str(&content).context("parse downloads yaml")?;
-    cfg.download_dir = expand_env(&cfg.download_dir);
-    for r in cfg.rules.iter_mut() {
-        r.target_dir = expand_env(&r.target_dir);
+    parse_downloads_config(&content)
+}
+
+/// [`load_downloads_config`] for YAML already in memory.
+pub fn parse_downloads_config(content: &str) -> Result<DownloadsConfig> {
+    let mut cfg: DownloadsConfig = serde_yaml::from_str(content).context("parse downloads yaml")?;
+    cfg.download_dir =
+        normalize_separators(&expand_env(&cfg.download_dir).context("download_dir")?);
+    for r in cfg.all_rules_mut() {
+        let target =
+            expand_env(&r.target_dir).with_context(|| format!("rule '{}': target_dir", r.name))?;
+        r.target_dir = normalize_separators(&target);
     }
+    assign_rule_ids(cfg.all_rules_mut());
     Ok(cfg)
 }
 
@@ -238,7 +365,126 @@ fn is_partial(name: &str) -> bool {
         || lower.ends_with(".download")
 }
 
-fn matches_rule(path: &Path, meta: &fs::Metadata, rule: &Rule) -> bool {
+/// Why the watcher leaves a file alone before looking at any rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FileSkip {
+    /// The file is not directly inside `download_dir`.
+    OutsideDownloadDir,
+    Symlink,
+    NotAFile,
+    /// A browser download still in progress (`.crdownload`, `.part`, ...).
+    Partial,
+    TooYoung {
+        age_secs: u64,
+        min_age_secs: u64,
+    },
+}
+
+impl std::fmt::Display for FileSkip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileSkip::OutsideDownloadDir => write!(f, "not in the download folder"),
+            FileSkip::Symlink => write!(f, "is a symlink"),
+            FileSkip::NotAFile => write!(f, "not a regular file"),
+            FileSkip::Partial => write!(f, "partial download"),
+            FileSkip::TooYoung {
+                age_secs,
+                min_age_secs,
+            } => write!(
+                f,
+                "too young ({}s old, min_age_secs is {})",
+                age_secs, min_age_secs
+            ),
+        }
+    }
+}
+
+/// How one rule judged a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleVerdict {
+    Matched,
+    Disabled,
+    ExtensionMismatch {
+        extension: String,
+        expected: Vec<String>,
+    },
+    PatternMismatch {
+        pattern: String,
+    },
+    SizeOutOfRange {
+        size: u64,
+        min: Option<u64>,
+        max: Option<u64>,
+    },
+    /// The rule would match, but an earlier rule already won.
+    Shadowed {
+        by: String,
+    },
+}
+
+impl std::fmt::Display for RuleVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleVerdict::Matched => write!(f, "matched"),
+            RuleVerdict::Disabled => write!(f, "disabled"),
+            RuleVerdict::ExtensionMismatch {
+                extension,
+                expected,
+            } => {
+                let ext = if extension.is_empty() {
+                    "no extension".to_string()
+                } else {
+                    format!("'.{}'", extension)
+                };
+                write!(f, "{} is not one of {}", ext, expected.join(", "))
+            }
+            RuleVerdict::PatternMismatch { pattern } => {
+                write!(f, "name does not match pattern {}", pattern)
+            }
+            RuleVerdict::SizeOutOfRange { size, min, max } => {
+                let bound = |b: &Option<u64>| b.map(|n| n.to_string()).unwrap_or("-".into());
+                write!(
+                    f,
+                    "size {} bytes is outside {}..{}",
+                    size,
+                    bound(min),
+                    bound(max)
+                )
+            }
+            RuleVerdict::Shadowed { by } => write!(f, "would match, but '{}' comes first", by),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleTrace {
+    pub rule: String,
+    pub verdict: RuleVerdict,
+}
+
+/// The result of [`explain`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    pub path: PathBuf,
+    /// Set when the watcher skips the file regardless of rules.
+    pub skip: Option<FileSkip>,
+    /// Every rule in order.
+    pub rules: Vec<RuleTrace>,
+    /// The first matching enabled rule, if any.
+    pub winner: Option<String>,
+}
+
+impl Explanation {
+    /// Whether the next organize pass would move the file.
+    pub fn would_move(&self) -> bool {
+        self.skip.is_none() && self.winner.is_some()
+    }
+}
+
+/// Checks everything but `enabled` and rule order.
+fn check_rule(path: &Path, meta: &fs::Metadata, rule: &Rule) -> RuleVerdict {
     if let Some(exts) = &rule.extensions {
         let ext = path
             .extension()
@@ -246,30 +492,116 @@ fn matches_rule(path: &Path, meta: &fs::Metadata, rule: &Rule) -> bool {
             .map(|e| e.to_ascii_lowercase())
             .unwrap_or_default();
         if !exts.iter().any(|x| x.to_ascii_lowercase() == ext) {
-            return false;
+            return RuleVerdict::ExtensionMismatch {
+                extension: ext,
+                expected: exts.clone(),
+            };
         }
     }
     if let Some(pat) = &rule.pattern {
         if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
             if let Ok(re) = Regex::new(pat) {
                 if !re.is_match(name) {
-                    return false;
+                    return RuleVerdict::PatternMismatch {
+                        pattern: pat.clone(),
+                    };
                 }
             }
         }
     }
     let size: u64 = meta.len();
-    if let Some(min) = rule.min_size_bytes {
-        if size < min {
-            return false;
-        }
+    let too_small = rule.min_size_bytes.is_some_and(|min| size < min);
+    let too_large = rule.max_size_bytes.is_some_and(|max| size > max);
+    if too_small || too_large {
+        return RuleVerdict::SizeOutOfRange {
+            size,
+            min: rule.min_size_bytes,
+            max: rule.max_size_bytes,
+        };
     }
-    if let Some(max) = rule.max_size_bytes {
-        if size > max {
-            return false;
+    RuleVerdict::Matched
+}
+
+fn matches_rule(path: &Path, meta: &fs::Metadata, rule: &Rule) -> bool {
+    check_rule(path, meta, rule) == RuleVerdict::Matched
+}
+
+/// The file-level checks `organize_once` makes before trying rules.
+fn check_file(path: &Path, meta: &fs::Metadata, min_age: Duration) -> Option<FileSkip> {
+    if meta.file_type().is_symlink() {
+        return Some(FileSkip::Symlink);
+    }
+    if !meta.is_file() {
+        return Some(FileSkip::NotAFile);
+    }
+    if path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(is_partial)
+    {
+        return Some(FileSkip::Partial);
+    }
+    if let Ok(modified) = meta.modified() {
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or(Duration::from_secs(0));
+        if age < min_age {
+            return Some(FileSkip::TooYoung {
+                age_secs: age.as_secs(),
+                min_age_secs: min_age.as_secs(),
+            });
         }
     }
-    true
+    None
+}
+
+/// Reports what the next organize pass would do with `path` and why: the
+/// file-level skip, if any, and each rule's verdict in order.
+pub fn explain(cfg: &DownloadsConfig, path: &Path) -> Result<Explanation> {
+    let meta =
+        fs::symlink_metadata(path).with_context(|| format!("cannot read {}", path.display()))?;
+    let min_age = Duration::from_secs(cfg.min_age_secs.unwrap_or(5));
+
+    let in_download_dir = match (path.parent(), fs::canonicalize(&cfg.download_dir)) {
+        (Some(parent), Ok(dir)) => fs::canonicalize(parent).is_ok_and(|p| p == dir),
+        _ => false,
+    };
+    let skip = if in_download_dir {
+        check_file(path, &meta, min_age)
+    } else {
+        Some(FileSkip::OutsideDownloadDir)
+    };
+
+    let mut winner: Option<String> = None;
+    let rules = cfg
+        .rules
+        .iter()
+        .map(|rule| {
+            let verdict = if !rule.enabled.unwrap_or(true) {
+                RuleVerdict::Disabled
+            } else {
+                match (check_rule(path, &meta, rule), &winner) {
+                    (RuleVerdict::Matched, Some(by)) => RuleVerdict::Shadowed { by: by.clone() },
+                    (RuleVerdict::Matched, None) => {
+                        winner = Some(rule.name.clone());
+                        RuleVerdict::Matched
+                    }
+                    (other, _) => other,
+                }
+            };
+            RuleTrace {
+                rule: rule.name.clone(),
+                verdict,
+            }
+        })
+        .collect();
+
+    Ok(Explanation {
+        path: path.to_path_buf(),
+        skip,
+        rules,
+        winner,
+    })
 }
 
 fn ensure_dir(dir: &Path) -> Result<()> {
@@ -309,138 +641,681 @@ fn unique_target(target: &Path) -> PathBuf {
 /// (to avoid overwrites) and optional symlink creation.
 ///
 /// Returns a list of actions taken, where each action is a tuple:
-/// `(original_path, new_path, rule_name, symlink_info)`.
+/// `(original_path, new_path, rule_name, symlink_info, rule_id)`. Files that could not be
+/// moved stay where they are; [`scan_once`] reports them.
 pub fn organize_once(cfg: &DownloadsConfig) -> Result<Vec<OrganizeResult>> {
-    let base = PathBuf::from(&cfg.download_dir);
+    scan_once(cfg, None).map(|scan| scan.actions)
+}
+
+/// What one pass over a folder looked at and did.
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Entries seen in the folder, including the ones that were skipped.
+    pub examined: usize,
+    pub actions: Vec<OrganizeResult>,
+    /// Files that matched a rule but could not be moved. The rest of the
+    /// folder is still processed.
+    pub failures: Vec<RuleFailure>,
+}
+
+impl ScanReport {
+    fn merge(&mut self, other: ScanReport) {
+        self.examined += other.examined;
+        self.actions.extend(other.actions);
+        self.failures.extend(other.failures);
+    }
+}
+
+/// Organizes the download folder like [`organize_once`] and reports everything
+/// the pass did. With `retries`, files that failed before are skipped until
+/// their backoff has passed; without it every failure is final.
+pub fn scan_once(cfg: &DownloadsConfig, retries: Option<&mut RetryQueue>) -> Result<ScanReport> {
+    scan_dir(cfg, Path::new(&cfg.download_dir), retries)
+}
+
+/// Organizes the files directly inside `dir` exactly as [`organize_once`]
+/// does for the download folder. Subfolders are left alone.
+pub fn organize_dir(cfg: &DownloadsConfig, dir: &Path) -> Result<ScanReport> {
+    scan_dir(cfg, dir, None)
+}
+
+fn scan_dir(
+    cfg: &DownloadsConfig,
+    dir: &Path,
+    mut retries: Option<&mut RetryQueue>,
+) -> Result<ScanReport> {
     let min_age = Duration::from_secs(cfg.min_age_secs.unwrap_or(5));
-    let mut actions = Vec::new();
-    for entry in fs::read_dir(&base).with_context(|| format!("list {}", base.display()))? {
-        let entry = entry?;
+    let now = Instant::now();
+    let mut scan = ScanReport::default();
+    let mut seen = HashSet::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("list {}", dir.display()))? {
+        let Ok(entry) = entry else {
+            continue;
+        };
         let path = entry.path();
+        scan.examined += 1;
         let meta = match fs::symlink_metadata(&path) {
             Ok(m) => m,
             Err(_) => continue,
         };
-        if meta.file_type().is_symlink() || !meta.is_file() {
+        if check_file(&path, &meta, min_age).is_some() {
             continue;
         }
-        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            if is_partial(name) {
+        if let Some(queue) = retries.as_deref_mut() {
+            seen.insert(path.clone());
+            if !queue.is_due(&path, now) {
                 continue;
             }
         }
-        if let Ok(modified) = meta.modified() {
-            if SystemTime::now()
-                .duration_since(modified)
-                .unwrap_or(Duration::from_secs(0))
-                < min_age
-            {
-                continue;
+        match organize_file(cfg, path.clone(), &meta) {
+            Ok(action) => {
+                if let Some(queue) = retries.as_deref_mut() {
+                    queue.succeeded(&path);
+                }
+                scan.actions.extend(action);
             }
+            Err(failure) => scan.failures.push(match retries.as_deref_mut() {
+                Some(queue) => queue.failed(failure, now),
+                None => failure,
+            }),
         }
-        let mut applied: Option<(&Rule, PathBuf)> = None;
-        for rule in &cfg.rules {
-            // Skip disabled rules
-            if !rule.enabled.unwrap_or(true) {
-                continue;
-            }
-            if matches_rule(&path, &meta, rule) {
-                let target_dir = PathBuf::from(&rule.target_dir);
-                ensure_dir(&target_dir)?;
-                let target = target_dir.join(
-                    path.file_name()
-                        .map(|n| n.to_os_string())
-                        .unwrap_or_default(),
-                );
-                let target = unique_target(&target);
-                applied = Some((rule, target));
-                break;
+    }
+    if let Some(queue) = retries {
+        queue.forget_missing(&seen);
+    }
+    Ok(scan)
+}
+
+/// Organizes each of `paths`: folders as with [`organize_dir`], files on
+/// their own. Every path is checked before anything moves, so a typo does
+/// not leave the job half done.
+pub fn organize_paths(cfg: &DownloadsConfig, paths: &[PathBuf]) -> Result<ScanReport> {
+    let min_age = Duration::from_secs(cfg.min_age_secs.unwrap_or(5));
+    let entries = paths
+        .iter()
+        .map(|path| {
+            fs::symlink_metadata(path)
+                .map(|meta| (path, meta))
+                .with_context(|| format!("cannot read {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut scan = ScanReport::default();
+    for (path, meta) in entries {
+        if meta.is_dir() {
+            scan.merge(organize_dir(cfg, path)?);
+            continue;
+        }
+        scan.examined += 1;
+        if check_file(path, &meta, min_age).is_none() {
+            match organize_file(cfg, path.clone(), &meta) {
+                Ok(action) => scan.actions.extend(action),
+                Err(failure) => scan.failures.push(failure),
             }
         }
-        if let Some((rule, target)) = applied {
+    }
+    Ok(scan)
+}
+
+/// Files directly inside the download folder that have been there longer than
+/// `older_than` and that no enabled rule matches, so the watcher will never
+/// move them. Sorted by path.
+pub fn unmatched_files(cfg: &DownloadsConfig, older_than: Duration) -> Result<Vec<PathBuf>> {
+    let dir = Path::new(&cfg.download_dir);
+    let mut unmatched = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("list {}", dir.display()))? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if check_file(&path, &meta, older_than).is_some() {
+            continue;
+        }
+        let matched = cfg
+            .rules
+            .iter()
+            .any(|r| r.enabled.unwrap_or(true) && matches_rule(&path, &meta, r));
+        if !matched {
+            unmatched.push(path);
+        }
+    }
+    unmatched.sort();
+    Ok(unmatched)
+}
+
+/// Files whose move failed, waiting to be tried again by later passes.
+///
+/// The wait doubles after every failure, starting at the base delay and capped
+/// at [`RetryQueue::MAX_DELAY`]. After the last allowed attempt the file is left
+/// alone until it disappears from the folder or the queue is dropped.
+#[derive(Debug, Clone)]
+pub struct RetryQueue {
+    max_attempts: u32,
+    base_delay: Duration,
+    entries: std::collections::HashMap<PathBuf, RetryEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct RetryEntry {
+    attempts: u32,
+    /// `None` once the file has used up its attempts.
+    next_try: Option<Instant>,
+}
+
+impl Default for RetryQueue {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_ATTEMPTS, Self::DEFAULT_BASE_DELAY)
+    }
+}
+
+impl RetryQueue {
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+    pub const DEFAULT_BASE_DELAY: Duration = Duration::from_secs(5);
+    pub const MAX_DELAY: Duration = Duration::from_secs(15 * 60);
+
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            entries: Default::default(),
+        }
+    }
+
+    /// Files that will be tried again.
+    pub fn pending(&self) -> usize {
+        self.entries
+            .values()
+            .filter(|e| e.next_try.is_some())
+            .count()
+    }
+
+    fn is_due(&self, path: &Path, now: Instant) -> bool {
+        match self.entries.get(path) {
+            None => true,
+            Some(entry) => entry.next_try.is_some_and(|at| at <= now),
+        }
+    }
+
+    fn succeeded(&mut self, path: &Path) {
+        self.entries.remove(path);
+    }
+
+    fn failed(&mut self, mut failure: RuleFailure, now: Instant) -> RuleFailure {
+        let entry = self
+            .entries
+            .entry(failure.path.clone())
+            .or_insert(RetryEntry {
+                attempts: 0,
+                next_try: None,
+            });
+        entry.attempts += 1;
+        failure.attempts = entry.attempts;
+        failure.retry_in = (entry.attempts < self.max_attempts).then(|| {
+            self.base_delay
+                .saturating_mul(1 << (entry.attempts - 1).min(16))
+                .min(Self::MAX_DELAY)
+        });
+        entry.next_try = failure.retry_in.map(|delay| now + delay);
+        failure
+    }
+
+    fn forget_missing(&mut self, seen: &HashSet<PathBuf>) {
+        self.entries.retain(|path, _| seen.contains(path));
+    }
+}
+
+/// Moves `path` with the first matching enabled rule. Files already in that
+/// rule's folder stay put, so organizing a target folder is a no-op.
+fn organize_file(
+    cfg: &DownloadsConfig,
+    path: PathBuf,
+    meta: &fs::Metadata,
+) -> std::result::Result<Option<OrganizeResult>, RuleFailure> {
+    let Some(rule) = cfg
+        .rules
+        .iter()
+        .filter(|rule| rule.enabled.unwrap_or(true))
+        .find(|rule| matches_rule(&path, meta, rule))
+    else {
+        return Ok(None);
+    };
+    let target_dir = PathBuf::from(&rule.target_dir);
+    let already_there = match (
+        path.parent().map(fs::canonicalize),
+        fs::canonicalize(&target_dir),
+    ) {
+        (Some(Ok(parent)), Ok(target)) => parent == target,
+        _ => false,
+    };
+    if already_there {
+        return Ok(None);
+    }
+    let target = target_dir.join(
+        path.file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_default(),
+    );
+    let target = unique_target(&target);
+    ensure_dir(&target_dir)
+        .and_then(|_| {
             fs::rename(&path, &target)
-                .with_context(|| format!("move {} -> {}", path.display(), target.display()))?;
+                .with_context(|| format!("move {} -> {}", path.display(), target.display()))
+        })
+        .map_err(|e| RuleFailure {
+            path: path.clone(),
+            rule: rule.name.clone(),
+            rule_id: rule.id.clone(),
+            message: format!("{:#}", e),
+            attempts: 1,
+            retry_in: None,
+        })?;
+
+    let mut symlink_info = None;
+    if rule.create_symlink.unwrap_or(false) {
+        #[cfg(windows)]
+        let res = std::os::windows::fs::symlink_file(&target, &path);
+        #[cfg(unix)]
+        let res = std::os::unix::fs::symlink(&target, &path);
 
-            let mut symlink_info = None;
-            if rule.create_symlink.unwrap_or(false) {
+        match res {
+            Ok(_) => {
+                symlink_info = Some("Symlink created".to_string());
                 #[cfg(windows)]
-                let res = std::os::windows::fs::symlink_file(&target, &path);
-                #[cfg(unix)]
-                let res = std::os::unix::fs::symlink(&target, &path);
-
-                match res {
-                    Ok(_) => {
-                        symlink_info = Some("Symlink created".to_string());
-                        #[cfg(windows)]
-                        {
-                            let _ = std::process::Command::new("attrib")
-                                .arg("+h")
-                                .arg(&path)
-                                .arg("/L")
-                                .creation_flags(0x08000000) // CREATE_NO_WINDOW
-                                .status();
-                        }
-                    }
-                    Err(e) => symlink_info = Some(format!("Symlink failed: {}", e)),
+                {
+                    let _ = std::process::Command::new("attrib")
+                        .arg("+h")
+                        .arg(&path)
+                        .arg("/L")
+                        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+                        .status();
                 }
             }
-
-            actions.push((path, target.clone(), rule.name.clone(), symlink_info));
+            Err(e) => symlink_info = Some(format!("Symlink failed: {}", e)),
         }
     }
-    Ok(actions)
+
+    Ok(Some((
+        path,
+        target,
+        rule.name.clone(),
+        symlink_info,
+        rule.id.clone(),
+    )))
 }
 
 /// Continuously polls the download directory and runs organization logic.
 ///
-/// This runs `organize_once` in a loop, sleeping for `interval_secs` between iterations.
+/// This runs `organize_once` in a loop, waiting `interval_secs` between iterations.
 /// When actions are taken, the `callback` is invoked with the list of actions.
-/// The function checks the `should_continue` flag on each iteration; when set to false, it exits.
+/// The loop exits as soon as `stop` is signalled, even in the middle of a wait.
 pub fn watch_polling<F>(
     cfg: &DownloadsConfig,
     interval_secs: u64,
-    should_continue: &std::sync::atomic::AtomicBool,
+    stop: &StopSignal,
     callback: F,
 ) -> Result<()>
 where
     F: Fn(&[OrganizeResult]),
 {
-    use std::sync::atomic::Ordering;
+    let shared = RwLock::new(cfg.clone());
+    watch_config_polling(&shared, None, interval_secs, stop, |event| {
+        if let WatchEvent::Organized(actions) = event {
+            callback(&actions);
+        }
+    })
+}
+
+/// A file matched a rule but could not be moved to the rule's target.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("rule '{rule}': {message}")]
+pub struct RuleFailure {
+    pub path: PathBuf,
+    pub rule: String,
+    pub rule_id: String,
+    pub message: String,
+    /// Tries so far, this one included
+    pub attempts: u32,
+    /// When the file will be tried again; `None` means Harbor gave up on it.
+    pub retry_in: Option<Duration>,
+}
+
+impl RuleFailure {
+    pub fn gave_up(&self) -> bool {
+        self.retry_in.is_none()
+    }
+}
+
+/// Something that happened inside the watcher loop.
+#[derive(Debug, Clone)]
+pub enum WatchEvent {
+    /// A pass moved one or more files.
+    Organized(Vec<OrganizeResult>),
+    /// The config file changed on disk and the new version is now active.
+    ConfigReloaded,
+    /// The config file changed but could not be loaded; the previous config stays active.
+    ConfigError(String),
+    /// Files in a pass matched a rule but could not be moved.
+    Failed(Vec<RuleFailure>),
+    /// A pass finished, successfully or not; sent after every pass.
+    Scanned(WatcherHealth),
+}
+
+/// Like [`watch_polling`], but reads the configuration from a shared lock on every pass.
+///
+/// When `config_path` is set, the file is checked for changes before each pass. A valid
+/// edit replaces the contents of `config` (so other holders of the lock see it too) and
+/// emits [`WatchEvent::ConfigReloaded`]; an invalid one emits [`WatchEvent::ConfigError`]
+/// and the previous configuration keeps being used. Every pass ends with a
+/// [`WatchEvent::Scanned`] carrying the watcher's health. No passes run while
+/// the config's `paused_until` lies ahead.
+pub fn watch_config_polling<F>(
+    config: &RwLock<DownloadsConfig>,
+    config_path: Option<&Path>,
+    interval_secs: u64,
+    stop: &StopSignal,
+    callback: F,
+) -> Result<()>
+where
+    F: FnMut(WatchEvent),
+{
+    let paused = AtomicBool::new(false);
+    watch_loop(config, config_path, interval_secs, stop, &paused, callback)
+}
+
+/// The loop behind [`watch_config_polling`]. While `paused` is set or the
+/// config's `paused_until` lies ahead, config changes are still picked up but
+/// no passes run.
+fn watch_loop<F>(
+    config: &RwLock<DownloadsConfig>,
+    config_path: Option<&Path>,
+    interval_secs: u64,
+    stop: &StopSignal,
+    paused: &AtomicBool,
+    mut callback: F,
+) -> Result<()>
+where
+    F: FnMut(WatchEvent),
+{
+    let mut reloader = config_path.map(ConfigReloader::new);
+    let mut health = WatcherHealth::new(interval_secs);
+    let mut retries = RetryQueue::default();
     loop {
-        if !should_continue.load(Ordering::Relaxed) {
+        if stop.is_stopped() {
             break;
         }
-        match organize_once(cfg) {
-            Ok(actions) => {
-                if !actions.is_empty() {
-                    callback(&actions);
+        if let Some(reloader) = reloader.as_mut() {
+            match reloader.poll() {
+                Some(Ok(new_cfg)) => {
+                    if let Ok(mut guard) = config.write() {
+                        *guard = new_cfg;
+                    }
+                    callback(WatchEvent::ConfigReloaded);
                 }
+                Some(Err(e)) => callback(WatchEvent::ConfigError(format!("{:#}", e))),
+                None => {}
             }
-            Err(e) => eprintln!("organize error: {}", e),
         }
-        thread::sleep(Duration::from_secs(interval_secs));
+        // Work on a snapshot so the lock is never held across file system calls.
+        let cfg = match config.read() {
+            Ok(guard) => guard.clone(),
+            Err(_) => break,
+        };
+        if paused.load(Ordering::SeqCst) || cfg.snoozed_until(Utc::now()).is_some() {
+            if stop.wait(Duration::from_secs(interval_secs)) {
+                break;
+            }
+            continue;
+        }
+        let started = Instant::now();
+        let result = scan_once(&cfg, Some(&mut retries));
+        health.record_pass(
+            Path::new(&cfg.download_dir),
+            started.elapsed(),
+            result.as_ref(),
+        );
+        health.pending_retries = retries.pending();
+        if let Ok(scan) = result {
+            if !scan.actions.is_empty() {
+                callback(WatchEvent::Organized(scan.actions));
+            }
+            if !scan.failures.is_empty() {
+                callback(WatchEvent::Failed(scan.failures));
+            }
+        }
+        callback(WatchEvent::Scanned(health.clone()));
+        if stop.wait(Duration::from_secs(interval_secs)) {
+            break;
+        }
     }
     Ok(())
 }
 
-fn expand_env(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    let mut i = 0;
-    let bytes = input.as_bytes();
-    while i < bytes.len() {
-        if bytes[i] == b'%' {
-            if let Some(end) = input[i + 1..].find('%') {
-                let var = &input[i + 1..i + 1 + end];
-                let val = std::env::var(var).unwrap_or_else(|_| "".to_string());
-                out.push_str(&val);
-                i += end + 2;
-                continue;
+/// Tells a watcher loop to stop, waking it from the wait between passes.
+#[derive(Debug, Clone, Default)]
+pub struct StopSignal(Arc<(Mutex<bool>, Condvar)>);
+
+impl StopSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stop(&self) {
+        let (stopped, wake) = &*self.0;
+        *stopped.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        wake.notify_all();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        *self.0 .0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits up to `timeout`, returning early (with `true`) once stopped.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let (stopped, wake) = &*self.0;
+        let guard = stopped.lock().unwrap_or_else(|e| e.into_inner());
+        let (guard, _) = wake
+            .wait_timeout_while(guard, timeout, |stopped| !*stopped)
+            .unwrap_or_else(|e| e.into_inner());
+        *guard
+    }
+}
+
+/// A [`watch_config_polling`] loop running on its own thread.
+#[derive(Debug)]
+pub struct WatcherThread {
+    stop: StopSignal,
+    /// Set by the thread as it exits, even by panicking
+    exited: StopSignal,
+    paused: Arc<AtomicBool>,
+    handle: thread::JoinHandle<()>,
+}
+
+/// Sets its signal when dropped, i.e. when the thread holding it ends.
+struct SignalOnDrop(StopSignal);
+
+impl Drop for SignalOnDrop {
+    fn drop(&mut self) {
+        self.0.stop();
+    }
+}
+
+/// Starts [`watch_config_polling`] on a new thread.
+pub fn spawn_watcher<F>(
+    config: Arc<RwLock<DownloadsConfig>>,
+    config_path: Option<PathBuf>,
+    interval_secs: u64,
+    callback: F,
+) -> WatcherThread
+where
+    F: FnMut(WatchEvent) + Send + 'static,
+{
+    let (stop, exited) = (StopSignal::new(), StopSignal::new());
+    let paused = Arc::new(AtomicBool::new(false));
+    let (signal, pause_flag) = (stop.clone(), paused.clone());
+    let on_exit = SignalOnDrop(exited.clone());
+    let handle = thread::spawn(move || {
+        let _on_exit = on_exit;
+        let _ = watch_loop(
+            &config,
+            config_path.as_deref(),
+            interval_secs,
+            &signal,
+            &pause_flag,
+            callback,
+        );
+    });
+    WatcherThread {
+        stop,
+        exited,
+        paused,
+        handle,
+    }
+}
+
+impl WatcherThread {
+    /// How long frontends wait for a pass in progress when stopping.
+    pub const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn thread(&self) -> &thread::Thread {
+        self.handle.thread()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.exited.is_stopped() || self.handle.is_finished()
+    }
+
+    /// Skips passes until [`WatcherThread::resume`]; a pass in progress still finishes.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Signals the loop to stop. With a `timeout`, also waits up to that long
+    /// for the thread to exit, which takes milliseconds unless a pass is in
+    /// progress. Returns whether the thread has exited.
+    pub fn stop(self, timeout: Option<Duration>) -> bool {
+        self.signal_stop();
+        let Some(timeout) = timeout else {
+            return self.is_finished();
+        };
+        if !self.wait(timeout) {
+            return false;
+        }
+        let _ = self.handle.join();
+        true
+    }
+
+    /// Asks the loop to stop after the pass in progress, without waiting.
+    pub fn signal_stop(&self) {
+        self.stop.stop();
+    }
+
+    /// Whether the loop was asked to stop, whether or not it has exited yet.
+    pub fn is_stopping(&self) -> bool {
+        self.stop.is_stopped()
+    }
+
+    /// Waits up to `timeout` for the thread to exit; returns whether it did.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        self.exited.wait(timeout)
+    }
+}
+
+/// Detects changes to a downloads config file between watcher passes.
+pub struct ConfigReloader {
+    path: PathBuf,
+    last_seen: Option<Fingerprint>,
+}
+
+impl ConfigReloader {
+    /// Starts tracking `path`; its current contents are treated as already loaded.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let last_seen = Fingerprint::read(&path);
+        Self { path, last_seen }
+    }
+
+    /// Returns `None` when the file is unchanged, otherwise the result of loading and
+    /// validating the new version. A failed load is reported once per change.
+    pub fn poll(&mut self) -> Option<Result<DownloadsConfig>> {
+        // Always hashed: the file is small, and metadata alone misses
+        // same-size edits made within one mtime tick
+        let current = Fingerprint::read(&self.path);
+        if current.is_none() || current == self.last_seen {
+            return None;
+        }
+        self.last_seen = current;
+        Some(load_downloads_config(&self.path).and_then(|cfg| {
+            validate_downloads_config(&cfg)?;
+            Ok(cfg)
+        }))
+    }
+}
+
+/// Checks a loaded downloads configuration for mistakes that would make the
+/// organizer misbehave: empty paths, duplicate rule names, invalid regexes and
+/// inverted size ranges.
+pub fn validate_downloads_config(cfg: &DownloadsConfig) -> Result<()> {
+    if cfg.download_dir.trim().is_empty() {
+        bail!("download_dir is empty");
+    }
+    // Ids are unique across profiles so activity never mixes up two rules
+    let mut ids = HashSet::new();
+    validate_rules(&cfg.rules, &mut ids)?;
+    for (name, rules) in cfg.profiles.iter().flatten() {
+        if name == cfg.active_profile() {
+            bail!(
+                "profile '{}' is active, so its rules belong in `rules`",
+                name
+            );
+        }
+        validate_rules(rules, &mut ids).with_context(|| format!("profile '{}'", name))?;
+    }
+    if let Some(quiet) = cfg
+        .notifications
+        .as_ref()
+        .and_then(|n| n.quiet_hours.as_ref())
+    {
+        quiet.parse()?;
+    }
+    Ok(())
+}
+
+fn validate_rules<'a>(rules: &'a [Rule], ids: &mut HashSet<&'a String>) -> Result<()> {
+    let mut names = HashSet::new();
+    for r in rules {
+        if !names.insert(&r.name) {
+            bail!("duplicate rule name {}", r.name);
+        }
+        if !r.id.is_empty() && !ids.insert(&r.id) {
+            bail!("rule '{}' reuses the id {}", r.name, r.id);
+        }
+        if r.target_dir.trim().is_empty() {
+            bail!("rule '{}' has an empty target_dir", r.name);
+        }
+        if let Some(pat) = &r.pattern {
+            Regex::new(pat).with_context(|| format!("rule '{}' has an invalid pattern", r.name))?;
+        }
+        if let (Some(min), Some(max)) = (r.min_size_bytes, r.max_size_bytes) {
+            if min > max {
+                bail!(
+                    "rule '{}' has min_size_bytes greater than max_size_bytes",
+                    r.name
+                );
             }
         }
-        out.push(bytes[i] as char);
-        i += 1;
     }
-    out
+    Ok(())
 }
 
 /// Scans the download directory for old symlinks created by Harbor and removes them.
@@ -503,15 +1378,6 @@ mod tests {
     use std::io::Write;
     use tempfile::TempDir;
 
-    #[test]
-    fn test_expand_env() {
-        std::env::set_var("TEST_VAR", "world");
-        assert_eq!(expand_env("Hello %TEST_VAR%"), "Hello world");
-        assert_eq!(expand_env("%TEST_VAR%"), "world");
-        assert_eq!(expand_env("No vars"), "No vars");
-        assert_eq!(expand_env("Unknown %MISSING_VAR%"), "Unknown ");
-    }
-
     #[test]
     fn test_is_partial() {
         assert!(is_partial("file.crdownload"));
@@ -534,6 +1400,7 @@ mod tests {
         let meta = fs::metadata(&file_path).unwrap();
 
         let rule_ext = Rule {
+            id: String::new(),
             name: "Ext".into(),
             extensions: Some(vec!["png".into()]),
             pattern: None,
@@ -542,10 +1409,12 @@ mod tests {
             target_dir: "target".into(),
             create_symlink: None,
             enabled: None,
+            notify: None,
         };
         assert!(matches_rule(&file_path, &meta, &rule_ext));
 
         let rule_pat = Rule {
+            id: String::new(),
             name: "Pat".into(),
             extensions: None,
             pattern: Some(".*st\\.png".into()),
@@ -554,10 +1423,12 @@ mod tests {
             target_dir: "target".into(),
             create_symlink: None,
             enabled: None,
+            notify: None,
         };
         assert!(matches_rule(&file_path, &meta, &rule_pat));
 
         let rule_size = Rule {
+            id: String::new(),
             name: "Size".into(),
             extensions: None,
             pattern: None,
@@ -566,10 +1437,12 @@ mod tests {
             target_dir: "target".into(),
             create_symlink: None,
             enabled: None,
+            notify: None,
         };
         assert!(matches_rule(&file_path, &meta, &rule_size));
 
         let rule_fail = Rule {
+            id: String::new(),
             name: "Fail".into(),
             extensions: Some(vec!["jpg".into()]),
             pattern: None,
@@ -578,10 +1451,121 @@ mod tests {
             target_dir: "target".into(),
             create_symlink: None,
             enabled: None,
+            notify: None,
         };
         assert!(!matches_rule(&file_path, &meta, &rule_fail));
     }
 
+    #[test]
+    fn test_explain() {
+        let temp = TempDir::new().unwrap();
+        let dl = temp.path().join("DL");
+        fs::create_dir(&dl).unwrap();
+        let file = dl.join("Invoice.PDF");
+        fs::write(&file, b"12345").unwrap();
+
+        let rule = |name: &str, exts: &[&str]| Rule {
+            id: String::new(),
+            name: name.into(),
+            extensions: Some(exts.iter().map(|e| e.to_string()).collect()),
+            pattern: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            target_dir: "target".into(),
+            create_symlink: None,
+            enabled: None,
+            notify: None,
+        };
+        let mut cfg = default_config();
+        cfg.download_dir = dl.to_string_lossy().to_string();
+        cfg.min_age_secs = Some(0);
+        cfg.rules = vec![
+            Rule {
+                enabled: Some(false),
+                notify: None,
+                ..rule("Off", &["pdf"])
+            },
+            rule("Images", &["png", "jpg"]),
+            Rule {
+                pattern: Some("^receipt".into()),
+                ..rule("Receipts", &["pdf"])
+            },
+            Rule {
+                min_size_bytes: Some(100),
+                ..rule("BigDocs", &["pdf"])
+            },
+            rule("Docs", &["pdf"]),
+            rule("AllDocs", &["pdf", "docx"]),
+        ];
+
+        let ex = explain(&cfg, &file).unwrap();
+        assert_eq!(ex.skip, None);
+        assert_eq!(ex.winner.as_deref(), Some("Docs"));
+        assert!(ex.would_move());
+        let verdicts: Vec<&RuleVerdict> = ex.rules.iter().map(|t| &t.verdict).collect();
+        assert_eq!(
+            verdicts,
+            [
+                &RuleVerdict::Disabled,
+                &RuleVerdict::ExtensionMismatch {
+                    extension: "pdf".into(),
+                    expected: vec!["png".into(), "jpg".into()],
+                },
+                &RuleVerdict::PatternMismatch {
+                    pattern: "^receipt".into()
+                },
+                &RuleVerdict::SizeOutOfRange {
+                    size: 5,
+                    min: Some(100),
+                    max: None
+                },
+                &RuleVerdict::Matched,
+                &RuleVerdict::Shadowed { by: "Docs".into() },
+            ]
+        );
+        assert_eq!(
+            ex.rules[1].verdict.to_string(),
+            "'.pdf' is not one of png, jpg"
+        );
+
+        // File-level skips are reported alongside the rule trace
+        cfg.min_age_secs = Some(3600);
+        let ex = explain(&cfg, &file).unwrap();
+        assert!(matches!(
+            ex.skip,
+            Some(FileSkip::TooYoung {
+                min_age_secs: 3600,
+                ..
+            })
+        ));
+        assert_eq!(ex.winner.as_deref(), Some("Docs"));
+        assert!(!ex.would_move());
+
+        cfg.min_age_secs = Some(0);
+        let partial = dl.join("big.iso.crdownload");
+        fs::write(&partial, b"").unwrap();
+        assert_eq!(
+            explain(&cfg, &partial).unwrap().skip,
+            Some(FileSkip::Partial)
+        );
+
+        let outside = temp.path().join("elsewhere.pdf");
+        fs::write(&outside, b"").unwrap();
+        assert_eq!(
+            explain(&cfg, &outside).unwrap().skip,
+            Some(FileSkip::OutsideDownloadDir)
+        );
+
+        #[cfg(unix)]
+        {
+            let link = dl.join("link.pdf");
+            std::os::unix::fs::symlink(&file, &link).unwrap();
+            assert_eq!(explain(&cfg, &link).unwrap().skip, Some(FileSkip::Symlink));
+        }
+
+        assert!(explain(&cfg, &dl.join("missing.pdf")).is_err());
+    }
+
     #[test]
     fn test_unique_target() {
         let temp = TempDir::new().unwrap();
@@ -623,7 +1607,13 @@ mod tests {
             service_enabled: None,
             check_updates: None,
             last_notified_version: None,
+            activity_retention: None,
+            notifications: None,
+            paused_until: None,
+            active_profile: None,
+            profiles: None,
             rules: vec![Rule {
+                id: String::new(),
                 name: "Images".into(),
                 extensions: Some(vec!["png".into()]),
                 pattern: None,
@@ -632,6 +1622,7 @@ mod tests {
                 target_dir: target.to_str().unwrap().into(),
                 create_symlink: Some(false),
                 enabled: None,
+                notify: None,
             }],
         };
 
@@ -642,6 +1633,158 @@ mod tests {
         assert!(target.join("test.png").exists());
     }
 
+    #[test]
+    fn test_organize_paths() {
+        let root = TempDir::new().unwrap();
+        let old = root.path().join("Old");
+        let loose = root.path().join("loose.png");
+        let target = root.path().join("Images");
+        fs::create_dir_all(old.join("nested")).unwrap();
+        for file in [
+            old.join("a.png"),
+            old.join("b.txt"),
+            old.join("nested/c.png"),
+            loose.clone(),
+        ] {
+            fs::write(file, b"data").unwrap();
+        }
+        let mut cfg = default_config();
+        cfg.min_age_secs = Some(0);
+        cfg.rules = vec![Rule {
+            id: String::new(),
+            name: "Images".into(),
+            extensions: Some(vec!["png".into()]),
+            pattern: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            target_dir: target.to_str().unwrap().into(),
+            create_symlink: None,
+            enabled: None,
+            notify: None,
+        }];
+
+        // Nothing moves if any path is missing
+        let missing = root.path().join("missing.png");
+        assert!(organize_paths(&cfg, &[loose.clone(), missing]).is_err());
+        assert!(loose.exists());
+
+        let scan = organize_paths(&cfg, &[old.clone(), loose.clone()]).unwrap();
+        assert!(scan.failures.is_empty());
+        let moved: Vec<&Path> = scan
+            .actions
+            .iter()
+            .map(|(from, ..)| from.as_path())
+            .collect();
+        assert_eq!(moved, [old.join("a.png").as_path(), loose.as_path()]);
+        assert!(old.join("b.txt").exists());
+        assert!(old.join("nested/c.png").exists());
+        assert!(target.join("a.png").exists());
+
+        // Files already in their rule's folder stay where they are
+        assert!(organize_dir(&cfg, &target).unwrap().actions.is_empty());
+        assert!(target.join("loose.png").exists());
+        assert!(!target.join("loose (1).png").exists());
+    }
+
+    #[test]
+    fn test_failed_moves_are_isolated_and_retried() {
+        let root = TempDir::new().unwrap();
+        let dl = root.path().join("Downloads");
+        let blocker = root.path().join("blocker");
+        fs::create_dir(&dl).unwrap();
+        fs::write(dl.join("a.png"), b"data").unwrap();
+        fs::write(dl.join("b.txt"), b"data").unwrap();
+        fs::write(&blocker, b"not a folder").unwrap();
+        let rule = |name: &str, ext: &str, target: PathBuf| Rule {
+            id: String::new(),
+            name: name.into(),
+            extensions: Some(vec![ext.into()]),
+            pattern: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            target_dir: target.to_str().unwrap().into(),
+            create_symlink: None,
+            enabled: None,
+            notify: None,
+        };
+        let mut cfg = default_config();
+        cfg.download_dir = dl.to_str().unwrap().into();
+        cfg.min_age_secs = Some(0);
+        cfg.rules = vec![
+            rule("Images", "png", blocker.join("Images")),
+            rule("Text", "txt", root.path().join("Text")),
+        ];
+
+        // One failing file does not stop the rest of the pass
+        let mut retries = RetryQueue::new(2, Duration::ZERO);
+        let scan = scan_once(&cfg, Some(&mut retries)).unwrap();
+        assert_eq!(scan.actions.len(), 1);
+        assert!(root.path().join("Text/b.txt").exists());
+        assert_eq!(scan.failures.len(), 1);
+        let failure = &scan.failures[0];
+        assert_eq!(failure.rule, "Images");
+        assert_eq!(failure.path, dl.join("a.png"));
+        assert_eq!((failure.attempts, failure.gave_up()), (1, false));
+        assert_eq!(retries.pending(), 1);
+
+        // The last allowed attempt gives up, and later passes leave the file alone
+        let scan = scan_once(&cfg, Some(&mut retries)).unwrap();
+        assert_eq!(
+            (scan.failures[0].attempts, scan.failures[0].gave_up()),
+            (2, true)
+        );
+        assert!(scan_once(&cfg, Some(&mut retries))
+            .unwrap()
+            .failures
+            .is_empty());
+        assert_eq!(retries.pending(), 0);
+
+        // Files are not retried before their backoff has passed
+        let mut retries = RetryQueue::new(3, Duration::from_secs(60));
+        assert_eq!(
+            scan_once(&cfg, Some(&mut retries)).unwrap().failures[0].retry_in,
+            Some(Duration::from_secs(60))
+        );
+        assert!(scan_once(&cfg, Some(&mut retries))
+            .unwrap()
+            .failures
+            .is_empty());
+        assert_eq!(retries.pending(), 1);
+
+        // Files that leave the folder are forgotten
+        fs::remove_file(dl.join("a.png")).unwrap();
+        scan_once(&cfg, Some(&mut retries)).unwrap();
+        assert_eq!(retries.pending(), 0);
+
+        // Without a queue every failure is final
+        fs::write(dl.join("c.png"), b"data").unwrap();
+        let scan = scan_once(&cfg, None).unwrap();
+        assert!(scan.failures[0].gave_up());
+        assert!(dl.join("c.png").exists());
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles() {
+        let mut retries = RetryQueue::new(10, Duration::from_secs(5));
+        let now = Instant::now();
+        let delays: Vec<_> = (0..4)
+            .map(|_| {
+                let failure = RuleFailure {
+                    path: PathBuf::from("/dl/a.png"),
+                    rule: "Images".into(),
+                    rule_id: "images".into(),
+                    message: "locked".into(),
+                    attempts: 1,
+                    retry_in: None,
+                };
+                retries.failed(failure, now).retry_in.unwrap().as_secs()
+            })
+            .collect();
+        assert_eq!(delays, [5, 10, 20, 40]);
+        assert!(!retries.is_due(Path::new("/dl/a.png"), now));
+        assert!(retries.is_due(Path::new("/dl/a.png"), now + Duration::from_secs(40)));
+    }
+
     #[test]
     fn test_cleanup_old_symlinks() {
         let root = TempDir::new().unwrap();
@@ -666,6 +1809,7 @@ mod tests {
         let cfg = DownloadsConfig {
             download_dir: dl.to_str().unwrap().into(),
             rules: vec![Rule {
+                id: String::new(),
                 name: "Images".into(),
                 extensions: None,
                 pattern: None,
@@ -674,12 +1818,18 @@ mod tests {
                 target_dir: target.to_str().unwrap().into(),
                 create_symlink: None,
                 enabled: None,
+                notify: None,
             }],
             min_age_secs: None,
             tutorial_completed: None,
             service_enabled: None,
             check_updates: None,
             last_notified_version: None,
+            activity_retention: None,
+            notifications: None,
+            paused_until: None,
+            active_profile: None,
+            profiles: None,
         };
 
         // Clean up
@@ -727,6 +1877,280 @@ rules:
         let cfg = load_downloads_config(file.path()).unwrap();
         assert_eq!(cfg.rules.len(), 1);
         assert_eq!(cfg.rules[0].name, "test");
+        // Configs from before rule ids get a stable one on every load
+        assert!(!cfg.rules[0].id.is_empty());
+        assert_eq!(
+            load_downloads_config(file.path()).unwrap().rules[0].id,
+            cfg.rules[0].id
+        );
+    }
+
+    #[test]
+    fn test_profiles() {
+        let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        writeln!(
+            file,
+            r#"
+download_dir: /dl
+rules:
+  - name: Images
+    target_dir: /home/pics
+profiles:
+  work:
+    - name: Images
+      target_dir: /work/pics
+    - name: Docs
+      target_dir: /work/docs
+"#
+        )
+        .unwrap();
+
+        let mut cfg = load_downloads_config(file.path()).unwrap();
+        validate_downloads_config(&cfg).unwrap();
+        assert_eq!(cfg.active_profile(), DEFAULT_PROFILE);
+        assert_eq!(cfg.profile_names(), ["default", "work"]);
+        // Same name in two profiles, still two rules
+        let work_images = &cfg.profiles.as_ref().unwrap()["work"][0];
+        assert_ne!(work_images.id, cfg.rules[0].id);
+        let home_id = cfg.rules[0].id.clone();
+
+        cfg.use_profile("work").unwrap();
+        assert_eq!(cfg.active_profile(), "work");
+        assert_eq!(cfg.rules.len(), 2);
+        assert_eq!(cfg.rules[0].target_dir, "/work/pics");
+        assert_eq!(cfg.profile_names(), ["default", "work"]);
+        validate_downloads_config(&cfg).unwrap();
+
+        cfg.use_profile("default").unwrap();
+        assert_eq!(cfg.rules[0].id, home_id);
+        let err = cfg.use_profile("presentation").unwrap_err();
+        assert!(err.to_string().contains("available: default, work"));
+
+        // The active profile's rules live in `rules` only
+        let mut cfg2 = cfg.clone();
+        cfg2.profiles
+            .as_mut()
+            .unwrap()
+            .insert(DEFAULT_PROFILE.into(), vec![]);
+        assert!(validate_downloads_config(&cfg2).is_err());
+    }
+
+    fn write_config(path: &Path, download_dir: &Path, rule_names: &[&str]) {
+        let mut yaml = format!(
+            "download_dir: \"{}\"\nmin_age_secs: 3600\nrules:\n",
+            download_dir.display().to_string().replace('\\', "\\\\")
+        );
+        for name in rule_names {
+            yaml.push_str(&format!("  - name: {}\n    target_dir: \"T\"\n", name));
+        }
+        fs::write(path, yaml).unwrap();
+    }
+
+    #[test]
+    fn test_config_reloader() {
+        let root = TempDir::new().unwrap();
+        let cfg_path = root.path().join("harbor.downloads.yaml");
+        write_config(&cfg_path, root.path(), &["a"]);
+
+        let mut reloader = ConfigReloader::new(&cfg_path);
+        assert!(reloader.poll().is_none());
+
+        write_config(&cfg_path, root.path(), &["a", "b"]);
+        let cfg = reloader.poll().unwrap().unwrap();
+        assert_eq!(cfg.rules.len(), 2);
+        assert!(reloader.poll().is_none());
+
+        // A same-size edit that keeps the mtime is still picked up
+        let modified = fs::metadata(&cfg_path).unwrap().modified().unwrap();
+        let yaml = fs::read_to_string(&cfg_path).unwrap();
+        fs::write(&cfg_path, yaml.replace("name: b", "name: c")).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&cfg_path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        let cfg = reloader.poll().unwrap().unwrap();
+        assert_eq!(cfg.rules[1].name, "c");
+
+        // Invalid edits are reported once and not retried until the file changes again
+        fs::write(&cfg_path, "download_dir: [unterminated").unwrap();
+        assert!(reloader.poll().unwrap().is_err());
+        assert!(reloader.poll().is_none());
+    }
+
+    #[test]
+    fn test_validate_downloads_config() {
+        let mut cfg = default_config();
+        assert!(validate_downloads_config(&cfg).is_ok());
+
+        cfg.rules[0].pattern = Some("(".into());
+        assert!(validate_downloads_config(&cfg).is_err());
+
+        let mut cfg = default_config();
+        cfg.rules[1].name = cfg.rules[0].name.clone();
+        let err = validate_downloads_config(&cfg).unwrap_err();
+        assert!(err.to_string().contains("duplicate rule name"));
+
+        let mut cfg = default_config();
+        cfg.rules[1].id = cfg.rules[0].id.clone();
+        let err = validate_downloads_config(&cfg).unwrap_err();
+        assert!(err.to_string().contains("reuses the id"));
+
+        let mut cfg = default_config();
+        cfg.rules[0].min_size_bytes = Some(10);
+        cfg.rules[0].max_size_bytes = Some(1);
+        assert!(validate_downloads_config(&cfg).is_err());
+
+        let mut cfg = default_config();
+        cfg.notifications = Some(NotificationSettings {
+            quiet_hours: Some(crate::notifications::QuietHours {
+                start: "22:00".into(),
+                end: "7am".into(),
+            }),
+            ..Default::default()
+        });
+        let err = validate_downloads_config(&cfg).unwrap_err();
+        assert!(err.to_string().contains("quiet hours"));
+    }
+
+    #[test]
+    fn test_watch_config_polling_reloads() {
+        let root = TempDir::new().unwrap();
+        let cfg_path = root.path().join("harbor.downloads.yaml");
+        write_config(&cfg_path, root.path(), &["a"]);
+        let shared = RwLock::new(load_downloads_config(&cfg_path).unwrap());
+        let stop = StopSignal::new();
+        let mut events = Vec::new();
+        let mut last_health = None;
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                watch_config_polling(&shared, Some(&cfg_path), 0, &stop, |event| {
+                    if let WatchEvent::Scanned(health) = event {
+                        last_health = Some(health);
+                        return;
+                    }
+                    events.push(event);
+                    if events.len() == 2 {
+                        stop.stop();
+                    }
+                })
+                .unwrap();
+            });
+            thread::sleep(Duration::from_millis(50));
+            write_config(&cfg_path, root.path(), &["a", "b"]);
+            thread::sleep(Duration::from_millis(50));
+            fs::write(&cfg_path, "rules: not-a-list").unwrap();
+        });
+
+        assert!(matches!(events[0], WatchEvent::ConfigReloaded));
+        assert!(matches!(events[1], WatchEvent::ConfigError(_)));
+        // The invalid edit kept the last good config
+        assert_eq!(shared.read().unwrap().rules.len(), 2);
+        let health = last_health.unwrap();
+        assert!(health.is_healthy());
+        assert!(health.last_scan.is_some());
+    }
+
+    #[test]
+    fn test_watcher_restarts_never_overlap() {
+        let root = TempDir::new().unwrap();
+        let mut cfg = default_config();
+        cfg.download_dir = root.path().to_str().unwrap().into();
+        let config = Arc::new(RwLock::new(cfg));
+        // Each watcher logs its start and its last pass; a restart must never
+        // see an event from the watcher before it.
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        for id in 0..3 {
+            let events = log.clone();
+            let watcher = spawn_watcher(config.clone(), None, 60, move |event| {
+                if let WatchEvent::Scanned(_) = event {
+                    events.lock().unwrap().push(id);
+                }
+            });
+            while !log.lock().unwrap().contains(&id) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            let started = Instant::now();
+            assert!(watcher.stop(Some(Duration::from_secs(5))));
+            // Stopping interrupts the 60 second wait
+            assert!(started.elapsed() < Duration::from_secs(1));
+        }
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(*log.lock().unwrap(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_wait_wakes_when_the_thread_ends() {
+        let root = TempDir::new().unwrap();
+        let mut cfg = default_config();
+        cfg.download_dir = root.path().to_str().unwrap().into();
+        let watcher = spawn_watcher(Arc::new(RwLock::new(cfg)), None, 60, |event| {
+            if let WatchEvent::Scanned(_) = event {
+                panic!("watcher callback failed");
+            }
+        });
+        // Not stopped, but the panic ends the thread after its first pass
+        let started = Instant::now();
+        assert!(watcher.wait(Duration::from_secs(30)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(watcher.is_finished());
+        assert!(!watcher.is_stopping());
+    }
+
+    #[test]
+    fn test_stop_signal_wakes_waiters() {
+        let stop = StopSignal::new();
+        assert!(!stop.wait(Duration::from_millis(1)));
+        let waiter = {
+            let stop = stop.clone();
+            thread::spawn(move || stop.wait(Duration::from_secs(60)))
+        };
+        stop.stop();
+        assert!(waiter.join().unwrap());
+        assert!(stop.is_stopped());
+    }
+
+    #[test]
+    fn test_load_downloads_config_accepts_either_separator() {
+        let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        writeln!(
+            file,
+            r#"
+download_dir: "base/Downloads"
+rules:
+  - name: test
+    target_dir: "base\\Downloads\\Target"
+"#
+        )
+        .unwrap();
+
+        let cfg = load_downloads_config(file.path()).unwrap();
+        let base = Path::new("base").join("Downloads");
+        assert_eq!(Path::new(&cfg.download_dir), base);
+        assert_eq!(Path::new(&cfg.rules[0].target_dir), base.join("Target"));
+    }
+
+    #[test]
+    fn test_load_downloads_config_missing_var_names_rule() {
+        let mut file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        writeln!(
+            file,
+            r#"
+download_dir: "/dl"
+rules:
+  - name: Pictures
+    target_dir: "%HARBOR_TYPO_VAR%/Images"
+"#
+        )
+        .unwrap();
+
+        let err = load_downloads_config(file.path()).unwrap_err();
+        let msg = format!("{:#}", err);
+        assert!(msg.contains("rule 'Pictures'"));
+        assert!(msg.contains("HARBOR_TYPO_VAR"));
     }
 
     #[test]
@@ -736,5 +2160,10 @@ rules:
         assert_eq!(cfg.service_enabled, Some(true));
         assert!(!cfg.rules.is_empty());
         assert!(cfg.rules.iter().any(|r| r.name == "Images"));
+        let images = cfg.rules.iter().find(|r| r.name == "Images").unwrap();
+        assert_eq!(
+            Path::new(&images.target_dir),
+            Path::new(&cfg.download_dir).join("Images")
+        );
     }
 }
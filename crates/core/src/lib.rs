@@ -1,6 +1,16 @@
+pub mod activity;
+pub mod activity_store;
 pub mod config;
+pub mod config_store;
 pub mod downloads;
 pub mod health;
+pub mod notifications;
 pub mod orchestrator;
+pub mod paths;
+pub mod rule_stats;
+pub mod rules;
+pub mod schema;
+pub mod service;
 pub mod state;
 pub mod types;
+pub mod watcher_health;
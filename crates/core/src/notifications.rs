@@ -0,0 +1,366 @@
+//! Desktop notifications about what the watcher did.
+//!
+//! [`Notifier`] turns [`ServiceEvent`]s into [`Notification`]s according to the
+//! config's [`NotificationSettings`]; showing them is up to the frontend.
+//! Moves are only mentioned for rules that opted in with `notify`.
+
+use crate::downloads::{unmatched_files, DownloadsConfig, OrganizeResult, RuleFailure};
+use crate::service::ServiceEvent;
+use anyhow::{Context, Result};
+use chrono::NaiveTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Which notifications are shown and when. Unset fields use the defaults below.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationSettings {
+    /// Master switch for every notification (default: true).
+    pub enabled: Option<bool>,
+    /// Notify when Harbor gives up moving a file (default: true).
+    pub failures: Option<bool>,
+    /// Notify about files no rule matches once they are this many hours old
+    /// (default: off).
+    pub unmatched_after_hours: Option<u64>,
+    /// Nothing is shown during this time of day.
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl NotificationSettings {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn failures(&self) -> bool {
+        self.failures.unwrap_or(true)
+    }
+
+    pub fn unmatched_after(&self) -> Option<Duration> {
+        self.unmatched_after_hours
+            .map(|h| Duration::from_secs(h * 60 * 60))
+    }
+
+    /// Whether `time` falls into the quiet hours.
+    pub fn is_quiet(&self, time: NaiveTime) -> bool {
+        self.quiet_hours.as_ref().is_some_and(|q| q.contains(time))
+    }
+}
+
+/// A daily window in local time, e.g. `22:00` to `07:00`. The end is exclusive
+/// and may be before the start to span midnight.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct QuietHours {
+    /// Start of the window, `HH:MM`.
+    pub start: String,
+    /// End of the window, `HH:MM`.
+    pub end: String,
+}
+
+impl QuietHours {
+    pub fn parse(&self) -> Result<(NaiveTime, NaiveTime)> {
+        let parse = |s: &str| {
+            NaiveTime::parse_from_str(s.trim(), "%H:%M")
+                .with_context(|| format!("invalid quiet hours time '{}' (expected HH:MM)", s))
+        };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let Ok((start, end)) = self.parse() else {
+            return false;
+        };
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+/// A notification ready to be shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    /// File the "Show in folder" action reveals.
+    pub reveal: Option<PathBuf>,
+}
+
+/// Decides which service events deserve a notification. Keep one per
+/// subscription: it remembers which unmatched files were already reported.
+#[derive(Debug, Default)]
+pub struct Notifier {
+    reported_unmatched: HashSet<PathBuf>,
+    last_unmatched_check: Option<Instant>,
+}
+
+impl Notifier {
+    /// How often the download folder is checked for unmatched files.
+    pub const UNMATCHED_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The notification for `event`, if any. `now` is the local time of day,
+    /// checked against the quiet hours.
+    pub fn on_event(
+        &mut self,
+        event: &ServiceEvent,
+        cfg: &DownloadsConfig,
+        now: NaiveTime,
+    ) -> Option<Notification> {
+        let settings = cfg.notifications.clone().unwrap_or_default();
+        if !settings.enabled() || settings.is_quiet(now) {
+            return None;
+        }
+        match event {
+            ServiceEvent::Organized(actions) => moves_notification(actions, cfg),
+            ServiceEvent::Failed(failures) if settings.failures() => {
+                failures_notification(failures)
+            }
+            ServiceEvent::Scanned(_) => self.check_unmatched(cfg, settings.unmatched_after()?),
+            _ => None,
+        }
+    }
+
+    /// Reports unmatched files not mentioned before. Files that left the
+    /// folder are forgotten, so they are reported again if they come back.
+    fn check_unmatched(
+        &mut self,
+        cfg: &DownloadsConfig,
+        older_than: Duration,
+    ) -> Option<Notification> {
+        if self
+            .last_unmatched_check
+            .is_some_and(|t| t.elapsed() < Self::UNMATCHED_CHECK_INTERVAL)
+        {
+            return None;
+        }
+        self.last_unmatched_check = Some(Instant::now());
+        let files = unmatched_files(cfg, older_than).ok()?;
+        self.reported_unmatched.retain(|p| files.contains(p));
+        let new: Vec<PathBuf> = files
+            .into_iter()
+            .filter(|p| self.reported_unmatched.insert(p.clone()))
+            .collect();
+        let first = new.first()?;
+        let title = match new.len() {
+            1 => "1 file matches no rule".to_string(),
+            n => format!("{} files match no rule", n),
+        };
+        Some(Notification {
+            title,
+            body: name_list(&new),
+            reveal: Some(first.clone()),
+        })
+    }
+}
+
+/// "5 files organized: 3 Images, 2 Documents", counting only rules with `notify`.
+fn moves_notification(actions: &[OrganizeResult], cfg: &DownloadsConfig) -> Option<Notification> {
+    let notify = |rule: &str| {
+        cfg.rules
+            .iter()
+            .any(|r| r.name == rule && r.notify.unwrap_or(false))
+    };
+    let moved: Vec<&OrganizeResult> = actions.iter().filter(|a| notify(&a.2)).collect();
+    let (_, dest, rule, _, _) = moved.last()?;
+    if moved.len() == 1 {
+        return Some(Notification {
+            title: "File organized".to_string(),
+            body: format!("{} moved to {}", file_name(dest), rule),
+            reveal: Some(dest.clone()),
+        });
+    }
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for (_, _, rule, _, _) in &moved {
+        match counts.iter_mut().find(|(r, _)| r == rule) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((rule, 1)),
+        }
+    }
+    let per_rule: Vec<String> = counts
+        .iter()
+        .map(|(rule, n)| format!("{} {}", n, rule))
+        .collect();
+    Some(Notification {
+        title: "Files organized".to_string(),
+        body: format!("{} files organized: {}", moved.len(), per_rule.join(", ")),
+        reveal: Some(dest.clone()),
+    })
+}
+
+/// Only files Harbor gave up on; the ones still being retried may yet move.
+fn failures_notification(failures: &[RuleFailure]) -> Option<Notification> {
+    let failed: Vec<&RuleFailure> = failures.iter().filter(|f| f.gave_up()).collect();
+    let first = failed.first()?;
+    let (title, body) = match failed.len() {
+        1 => (
+            format!("Could not move {}", file_name(&first.path)),
+            first.to_string(),
+        ),
+        n => {
+            let paths: Vec<PathBuf> = failed.iter().map(|f| f.path.clone()).collect();
+            (format!("Could not move {} files", n), name_list(&paths))
+        }
+    };
+    Some(Notification {
+        title,
+        body,
+        reveal: Some(first.path.clone()),
+    })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// The first few file names, then how many more there are.
+fn name_list(paths: &[PathBuf]) -> String {
+    const SHOWN: usize = 3;
+    let mut names: Vec<String> = paths.iter().take(SHOWN).map(|p| file_name(p)).collect();
+    if paths.len() > SHOWN {
+        names.push(format!("and {} more", paths.len() - SHOWN));
+    }
+    names.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Rule;
+    use std::fs;
+
+    fn rule(name: &str, ext: &str, notify: bool) -> Rule {
+        Rule {
+            id: String::new(),
+            name: name.to_string(),
+            extensions: Some(vec![ext.to_string()]),
+            pattern: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            target_dir: name.to_string(),
+            create_symlink: None,
+            enabled: Some(true),
+            notify: Some(notify),
+        }
+    }
+
+    fn config(dir: &Path) -> DownloadsConfig {
+        let mut cfg = crate::downloads::default_config();
+        cfg.download_dir = dir.display().to_string();
+        cfg.rules = vec![
+            rule("Images", "png", true),
+            rule("Documents", "pdf", true),
+            rule("Archives", "zip", false),
+        ];
+        cfg
+    }
+
+    fn moved(file: &str, rule: &str) -> OrganizeResult {
+        (
+            PathBuf::from(file),
+            PathBuf::from(rule).join(file),
+            rule.to_string(),
+            None,
+            rule.to_lowercase(),
+        )
+    }
+
+    fn noon() -> NaiveTime {
+        NaiveTime::from_hms_opt(12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_moves_are_batched_per_rule() {
+        let cfg = config(Path::new("."));
+        let event = ServiceEvent::Organized(vec![
+            moved("a.png", "Images"),
+            moved("b.pdf", "Documents"),
+            moved("c.png", "Images"),
+            moved("d.zip", "Archives"),
+            moved("e.png", "Images"),
+            moved("f.pdf", "Documents"),
+        ]);
+        let n = Notifier::new().on_event(&event, &cfg, noon()).unwrap();
+        assert_eq!(n.body, "5 files organized: 3 Images, 2 Documents");
+        assert_eq!(n.reveal, Some(PathBuf::from("Documents/f.pdf")));
+
+        let single = ServiceEvent::Organized(vec![moved("a.png", "Images")]);
+        let n = Notifier::new().on_event(&single, &cfg, noon()).unwrap();
+        assert_eq!(n.body, "a.png moved to Images");
+
+        let silent = ServiceEvent::Organized(vec![moved("d.zip", "Archives")]);
+        assert!(Notifier::new().on_event(&silent, &cfg, noon()).is_none());
+    }
+
+    #[test]
+    fn test_settings_and_quiet_hours() {
+        let mut cfg = config(Path::new("."));
+        let event = ServiceEvent::Organized(vec![moved("a.png", "Images")]);
+        let failure = |retry_in| RuleFailure {
+            path: PathBuf::from("x.pdf"),
+            rule: "Documents".into(),
+            rule_id: "documents".into(),
+            message: "denied".into(),
+            attempts: 5,
+            retry_in,
+        };
+        let mut notifier = Notifier::new();
+        let retrying = ServiceEvent::Failed(vec![failure(Some(Duration::from_secs(5)))]);
+        assert!(notifier.on_event(&retrying, &cfg, noon()).is_none());
+        let gave_up = ServiceEvent::Failed(vec![failure(None)]);
+        let n = notifier.on_event(&gave_up, &cfg, noon()).unwrap();
+        assert_eq!(n.title, "Could not move x.pdf");
+
+        cfg.notifications = Some(NotificationSettings {
+            failures: Some(false),
+            quiet_hours: Some(QuietHours {
+                start: "22:00".into(),
+                end: "07:00".into(),
+            }),
+            ..Default::default()
+        });
+        assert!(notifier.on_event(&gave_up, &cfg, noon()).is_none());
+        assert!(notifier.on_event(&event, &cfg, noon()).is_some());
+        let night = NaiveTime::from_hms_opt(23, 30, 0).unwrap();
+        let morning = NaiveTime::from_hms_opt(6, 59, 0).unwrap();
+        assert!(notifier.on_event(&event, &cfg, night).is_none());
+        assert!(notifier.on_event(&event, &cfg, morning).is_none());
+
+        cfg.notifications = Some(NotificationSettings {
+            enabled: Some(false),
+            ..Default::default()
+        });
+        assert!(notifier.on_event(&event, &cfg, noon()).is_none());
+    }
+
+    #[test]
+    fn test_unmatched_files_reported_once() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("notes.txt"), "x").unwrap();
+        fs::write(tmp.path().join("photo.png"), "x").unwrap();
+        let mut cfg = config(tmp.path());
+        cfg.min_age_secs = Some(0);
+        let scanned = ServiceEvent::Scanned(Default::default());
+
+        let mut notifier = Notifier::new();
+        assert!(notifier.on_event(&scanned, &cfg, noon()).is_none());
+
+        cfg.notifications = Some(NotificationSettings {
+            unmatched_after_hours: Some(0),
+            ..Default::default()
+        });
+        let n = notifier.on_event(&scanned, &cfg, noon()).unwrap();
+        assert_eq!(n.title, "1 file matches no rule");
+        assert_eq!(n.body, "notes.txt");
+
+        notifier.last_unmatched_check = None;
+        assert!(notifier.on_event(&scanned, &cfg, noon()).is_none());
+    }
+}
@@ -0,0 +1,337 @@
+//! Platform folder lookup shared by the CLI, tray and desktop app.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+
+/// The current user's home directory.
+pub fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The user's Downloads folder.
+///
+/// Uses the Known Folder API on Windows and `XDG_DOWNLOAD_DIR` (from the
+/// environment or `user-dirs.dirs`) on Linux, falling back to `~/Downloads`.
+pub fn download_dir() -> PathBuf {
+    platform_download_dir().unwrap_or_else(|| fallback_home().join("Downloads"))
+}
+
+/// Where Harbor keeps its config and logs: `%LOCALAPPDATA%\Harbor` on Windows,
+/// `$XDG_DATA_HOME/harbor` (or `~/.local/share/harbor`) elsewhere.
+pub fn harbor_data_dir() -> PathBuf {
+    if cfg!(windows) {
+        return std::env::var_os("LOCALAPPDATA")
+            .map(|p| PathBuf::from(p).join("Harbor"))
+            .unwrap_or_else(|| PathBuf::from("C:\\Harbor"));
+    }
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| fallback_home().join(".local").join("share"))
+        .join("harbor")
+}
+
+fn fallback_home() -> PathBuf {
+    home_dir().unwrap_or_else(|| {
+        if cfg!(windows) {
+            PathBuf::from("C:\\Users\\Public")
+        } else {
+            PathBuf::from("/tmp")
+        }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ExpandError {
+    #[error("environment variable {0} is not set")]
+    MissingVar(String),
+    #[error("unterminated ${{...}} in {0:?}")]
+    Unterminated(String),
+    #[error("cannot expand ~: home directory is unknown")]
+    NoHome,
+}
+
+/// Expands environment references in a config value.
+///
+/// Supported forms are `%VAR%`, `$VAR`, `${VAR}`, `${VAR:-default}` and a
+/// leading `~` for the home directory. `%%` and `$$` produce a literal `%`
+/// or `$`. Referencing a variable that is not set is an error rather than an
+/// empty string, so a typo cannot silently turn into a different path.
+pub fn expand_env(input: &str) -> Result<String, ExpandError> {
+    expand_with(
+        input,
+        &|name| std::env::var(name).ok(),
+        home_dir().as_deref(),
+    )
+}
+
+/// [`expand_env`] with variables read through `var` and `~` replaced by
+/// `home`, so tests never touch the process environment.
+fn expand_with(
+    input: &str,
+    var: &dyn Fn(&str) -> Option<String>,
+    home: Option<&Path>,
+) -> Result<String, ExpandError> {
+    let lookup = |name: &str| var(name).ok_or_else(|| ExpandError::MissingVar(name.to_string()));
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    if let Some(after) = rest.strip_prefix('~') {
+        if after.is_empty() || after.starts_with(['/', '\\']) {
+            let home = home.ok_or(ExpandError::NoHome)?;
+            out.push_str(&home.to_string_lossy());
+            rest = after;
+        }
+    }
+
+    while let Some(pos) = rest.find(['%', '$']) {
+        out.push_str(&rest[..pos]);
+        let sigil = rest.as_bytes()[pos] as char;
+        let tail = &rest[pos + 1..];
+
+        if tail.starts_with(sigil) {
+            out.push(sigil);
+            rest = &tail[1..];
+            continue;
+        }
+
+        if sigil == '%' {
+            // Only `%NAME%` with a plausible variable name is a reference; a lone
+            // percent sign (e.g. "50% off") is kept as-is.
+            match tail.find('%') {
+                Some(end) if is_windows_var_name(&tail[..end]) => {
+                    out.push_str(&lookup(&tail[..end])?);
+                    rest = &tail[end + 1..];
+                }
+                _ => {
+                    out.push('%');
+                    rest = tail;
+                }
+            }
+        } else if let Some(braced) = tail.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| ExpandError::Unterminated(input.to_string()))?;
+            let expr = &braced[..end];
+            match expr.split_once(":-") {
+                Some((name, default)) => match var(name) {
+                    Some(val) if !val.is_empty() => out.push_str(&val),
+                    _ => out.push_str(&expand_with(default, var, home)?),
+                },
+                None => out.push_str(&lookup(expr)?),
+            }
+            rest = &braced[end + 1..];
+        } else {
+            let len = tail
+                .char_indices()
+                .find(|(i, c)| {
+                    !(c.is_ascii_alphanumeric() || *c == '_') || (*i == 0 && c.is_ascii_digit())
+                })
+                .map(|(i, _)| i)
+                .unwrap_or(tail.len());
+            if len == 0 {
+                out.push('$');
+            } else {
+                out.push_str(&lookup(&tail[..len])?);
+            }
+            rest = &tail[len..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn is_windows_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '(' | ')'))
+}
+
+/// Rewrites `/` and `\` to the platform separator so configs written on one
+/// OS keep working on another.
+pub fn normalize_separators(path: &str) -> String {
+    path.chars()
+        .map(|c| {
+            if c == '/' || c == '\\' {
+                MAIN_SEPARATOR
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn platform_download_dir() -> Option<PathBuf> {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Com::CoTaskMemFree;
+    use windows::Win32::UI::Shell::{FOLDERID_Downloads, SHGetKnownFolderPath, KF_FLAG_DEFAULT};
+
+    // SAFETY: FOLDERID_Downloads is a valid GUID, and the returned string is
+    // owned by us and released with CoTaskMemFree once copied.
+    unsafe {
+        let raw =
+            SHGetKnownFolderPath(&FOLDERID_Downloads, KF_FLAG_DEFAULT, HANDLE::default()).ok()?;
+        let path = raw.to_string().ok();
+        CoTaskMemFree(Some(raw.0 as *const _));
+        path.map(PathBuf::from)
+    }
+}
+
+#[cfg(not(windows))]
+fn platform_download_dir() -> Option<PathBuf> {
+    xdg_download_dir(|name| std::env::var_os(name))
+}
+
+/// The XDG download folder, reading environment variables through `var`.
+/// An explicit `XDG_DOWNLOAD_DIR` is used even when `HOME` is unset.
+#[cfg_attr(windows, allow(dead_code))]
+fn xdg_download_dir(var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let var = |name: &str| var(name).filter(|v| !v.is_empty());
+    if let Some(dir) = var("XDG_DOWNLOAD_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let home = PathBuf::from(var("HOME")?);
+    let config_home = var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".config"));
+    let content = std::fs::read_to_string(config_home.join("user-dirs.dirs")).ok()?;
+    parse_user_dirs(&content, "XDG_DOWNLOAD_DIR", &home)
+}
+
+/// Reads one entry of an XDG `user-dirs.dirs` file, e.g.
+/// `XDG_DOWNLOAD_DIR="$HOME/Downloads"`.
+#[cfg_attr(windows, allow(dead_code))]
+fn parse_user_dirs(content: &str, key: &str, home: &Path) -> Option<PathBuf> {
+    content.lines().find_map(|line| {
+        let line = line.trim();
+        if line.starts_with('#') {
+            return None;
+        }
+        let value = line.strip_prefix(key)?.trim_start().strip_prefix('=')?;
+        let value = value.trim().trim_matches('"');
+        if let Some(rest) = value.strip_prefix("$HOME") {
+            let rest = rest.trim_start_matches('/');
+            // A bare "$HOME" means the directory is disabled
+            if rest.is_empty() {
+                return None;
+            }
+            Some(home.join(rest))
+        } else if value.starts_with('/') {
+            Some(PathBuf::from(value))
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user_dirs() {
+        let home = Path::new("/home/dev");
+        let content = r#"
+# This file is written by xdg-user-dirs-update
+XDG_DESKTOP_DIR="$HOME/Desktop"
+XDG_DOWNLOAD_DIR="$HOME/Téléchargements"
+"#;
+        assert_eq!(
+            parse_user_dirs(content, "XDG_DOWNLOAD_DIR", home),
+            Some(home.join("Téléchargements"))
+        );
+        assert_eq!(
+            parse_user_dirs("XDG_DOWNLOAD_DIR=\"/data/dl\"", "XDG_DOWNLOAD_DIR", home),
+            Some(PathBuf::from("/data/dl"))
+        );
+        assert_eq!(
+            parse_user_dirs("XDG_DOWNLOAD_DIR=\"$HOME/\"", "XDG_DOWNLOAD_DIR", home),
+            None
+        );
+        assert_eq!(parse_user_dirs("", "XDG_DOWNLOAD_DIR", home), None);
+    }
+
+    fn expand(input: &str) -> Result<String, ExpandError> {
+        let vars = |name: &str| match name {
+            "HARBOR_TEST_VAR" => Some("world".to_string()),
+            "HARBOR_TEST_DIR" => Some("dl".to_string()),
+            _ => None,
+        };
+        expand_with(input, &vars, Some(Path::new("/home/dev")))
+    }
+
+    #[test]
+    fn test_expand_env() {
+        assert_eq!(expand("Hello %HARBOR_TEST_VAR%").unwrap(), "Hello world");
+        assert_eq!(expand("%HARBOR_TEST_VAR%").unwrap(), "world");
+        assert_eq!(expand("$HARBOR_TEST_VAR/x").unwrap(), "world/x");
+        assert_eq!(expand("${HARBOR_TEST_VAR}s").unwrap(), "worlds");
+        assert_eq!(expand("No vars").unwrap(), "No vars");
+        assert_eq!(
+            expand("Unknown %HARBOR_MISSING_VAR%"),
+            Err(ExpandError::MissingVar("HARBOR_MISSING_VAR".into()))
+        );
+        assert!(expand("$HARBOR_MISSING_VAR").is_err());
+        assert_eq!(expand_env("No vars").unwrap(), "No vars");
+    }
+
+    #[test]
+    fn test_expand_env_defaults_and_escapes() {
+        assert_eq!(
+            expand("${HARBOR_MISSING_VAR:-fallback}/a").unwrap(),
+            "fallback/a"
+        );
+        assert_eq!(
+            expand("${HARBOR_MISSING_VAR:-$HARBOR_TEST_DIR}").unwrap(),
+            "dl"
+        );
+        assert_eq!(expand("${HARBOR_TEST_DIR:-x}").unwrap(), "dl");
+        assert_eq!(expand("100%% done").unwrap(), "100% done");
+        assert_eq!(expand("50% off").unwrap(), "50% off");
+        assert_eq!(expand("cost $$5").unwrap(), "cost $5");
+        assert_eq!(expand("a $ b").unwrap(), "a $ b");
+        assert!(matches!(
+            expand("${HARBOR_TEST_DIR"),
+            Err(ExpandError::Unterminated(_))
+        ));
+    }
+
+    #[test]
+    fn test_expand_env_home() {
+        assert_eq!(expand("~").unwrap(), "/home/dev");
+        assert_eq!(expand("~/Downloads").unwrap(), "/home/dev/Downloads");
+        assert_eq!(
+            expand_with("~/Downloads", &|_| None, None),
+            Err(ExpandError::NoHome)
+        );
+        // Only a leading tilde is special
+        assert_eq!(expand("a~b").unwrap(), "a~b");
+        assert_eq!(expand("~user").unwrap(), "~user");
+    }
+
+    #[test]
+    fn test_normalize_separators() {
+        let expected = ["a", "b", "c"].join(std::path::MAIN_SEPARATOR_STR);
+        assert_eq!(normalize_separators("a/b\\c"), expected);
+        assert_eq!(normalize_separators("abc"), "abc");
+    }
+
+    #[test]
+    fn test_xdg_download_dir() {
+        let only_xdg = |name: &str| (name == "XDG_DOWNLOAD_DIR").then(|| OsString::from("/dl"));
+        assert_eq!(xdg_download_dir(only_xdg), Some(PathBuf::from("/dl")));
+        assert_eq!(xdg_download_dir(|_| None), None);
+    }
+
+    #[test]
+    fn test_download_dir_is_absolute() {
+        if home_dir().is_some() {
+            assert!(download_dir().is_absolute());
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Per-rule usage counters kept in a JSON file next to the downloads config.
+//!
+//! The activity log answers "what moved"; these counters answer "which rules still
+//! fire" without scanning history, and they survive activity retention.
+
+use crate::downloads::{OrganizeResult, RuleFailure};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Usage of a single rule.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleStats {
+    #[serde(default)]
+    pub matches: u64,
+    #[serde(default)]
+    pub bytes_moved: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_matched: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+/// Stats for every rule that has fired, keyed by rule id.
+pub type RuleStatsMap = BTreeMap<String, RuleStats>;
+
+/// Reads and updates the stats file. Updates are read-modify-write under a
+/// sibling `.lock` file, so the app, tray and CLI can all record into it.
+#[derive(Debug, Clone)]
+pub struct RuleStatsStore {
+    path: PathBuf,
+}
+
+impl RuleStatsStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `harbor.downloads.stats.json` for `harbor.downloads.yaml`.
+    pub fn for_config(config_path: impl AsRef<Path>) -> Self {
+        Self::new(config_path.as_ref().with_extension("stats.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the stats; a missing file means no rule has fired yet.
+    pub fn load(&self) -> Result<RuleStatsMap> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("parsing {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RuleStatsMap::new()),
+            Err(e) => Err(e).with_context(|| format!("reading {}", self.path.display())),
+        }
+    }
+
+    /// Counts each of `actions` against the rule that made it.
+    pub fn record_moves(&self, actions: &[OrganizeResult]) -> Result<()> {
+        if actions.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        self.update(|stats| {
+            for (_, to, _, _, id) in actions.iter().filter(|a| !a.4.is_empty()) {
+                let entry = stats.entry(id.clone()).or_default();
+                entry.matches += 1;
+                entry.bytes_moved += fs::metadata(to).map(|m| m.len()).unwrap_or(0);
+                entry.last_matched = Some(now);
+            }
+        })
+    }
+
+    /// Stores each of `failures` as the last error of the rule it names.
+    pub fn record_failures(&self, failures: &[RuleFailure]) -> Result<()> {
+        if failures.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        self.update(|stats| {
+            for failure in failures.iter().filter(|f| !f.rule_id.is_empty()) {
+                let entry = stats.entry(failure.rule_id.clone()).or_default();
+                entry.last_error = Some(failure.message.clone());
+                entry.last_error_at = Some(now);
+            }
+        })
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut RuleStatsMap)) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let lock_path = sibling(&self.path, "lock");
+        let lock = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("opening {}", lock_path.display()))?;
+        lock.lock()
+            .with_context(|| format!("locking {}", lock_path.display()))?;
+
+        // A damaged file is started over rather than blocking every move.
+        let mut stats = self.load().unwrap_or_default();
+        apply(&mut stats);
+
+        let tmp = sibling(&self.path, "tmp");
+        let mut file =
+            fs::File::create(&tmp).with_context(|| format!("writing {}", tmp.display()))?;
+        file.write_all(serde_json::to_string_pretty(&stats)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("replacing {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_moves_and_failures() {
+        let temp = TempDir::new().unwrap();
+        let store = RuleStatsStore::for_config(temp.path().join("harbor.downloads.yaml"));
+        assert_eq!(
+            store.path(),
+            temp.path().join("harbor.downloads.stats.json")
+        );
+        assert!(store.load().unwrap().is_empty());
+
+        let moved = temp.path().join("a.pdf");
+        fs::write(&moved, b"12345").unwrap();
+        let action = (
+            PathBuf::from("/dl/a.pdf"),
+            moved.clone(),
+            "Docs".to_string(),
+            None,
+            "r1".to_string(),
+        );
+        // A rule renamed after the move is still counted by its id
+        let renamed = (
+            PathBuf::from("/dl/c.pdf"),
+            moved.clone(),
+            "Papers".to_string(),
+            None,
+            "r1".to_string(),
+        );
+        store
+            .record_moves(&[action.clone(), action, renamed])
+            .unwrap();
+
+        let failure = RuleFailure {
+            path: PathBuf::from("/dl/b.png"),
+            rule: "Images".into(),
+            rule_id: "r2".into(),
+            message: "move failed".into(),
+            attempts: 1,
+            retry_in: None,
+        };
+        store.record_failures(&[failure]).unwrap();
+
+        let stats = store.load().unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats["r1"].matches, 3);
+        assert_eq!(stats["r1"].bytes_moved, 15);
+        assert!(stats["r1"].last_matched.is_some());
+        assert_eq!(stats["r2"].matches, 0);
+        assert_eq!(stats["r2"].last_error.as_deref(), Some("move failed"));
+        assert!(stats["r2"].last_error_at.is_some());
+    }
+}
@@ -0,0 +1,680 @@
+//! Static checks on a rule list, and rule packs for sharing rules.
+//!
+//! `organize_once` applies the first matching rule, so a broad rule placed
+//! early can keep later rules from ever firing. [`analyze_rules`] compares
+//! rule definitions (not files) and reports such problems as warnings.
+//!
+//! A [`RulePack`] carries rules without the per-user settings of
+//! `harbor.downloads.yaml`, so one rule set can be rolled out to many machines.
+
+use crate::downloads::{validate_downloads_config, DownloadsConfig};
+use crate::paths::{expand_env, normalize_separators};
+use crate::types::Rule;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A fresh id for a newly created rule.
+pub fn new_rule_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let seed = format!(
+        "{}:{}:{}",
+        nanos,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    format!("{:016x}", fnv1a(seed.as_bytes()))
+}
+
+/// Gives every rule without an id, or with an id an earlier rule already
+/// uses, one derived from its name. Deriving it (rather than generating a
+/// random one) means the tray, CLI and app agree on the ids of an old config
+/// until [`ConfigStore::persist_rule_ids`](crate::config_store::ConfigStore::persist_rule_ids)
+/// writes them to the file. Returns whether any id changed.
+pub fn assign_rule_ids<'a>(rules: impl IntoIterator<Item = &'a mut Rule>) -> bool {
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut changed = false;
+    for rule in rules {
+        if rule.id.is_empty() || taken.contains(&rule.id) {
+            rule.id = (0..)
+                .map(|n| match n {
+                    0 => fnv1a(rule.name.as_bytes()),
+                    n => fnv1a(format!("{}\0{}", rule.name, n).as_bytes()),
+                })
+                .map(|hash| format!("{:016x}", hash))
+                .find(|id| !taken.contains(id))
+                .unwrap_or_default();
+            changed = true;
+        }
+        taken.insert(rule.id.clone());
+    }
+    changed
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleWarning {
+    /// Every file `rule` matches is already taken by the earlier rule `by`.
+    Shadowed { rule: String, by: String },
+    /// Both rules accept these extensions; files that match both go to `first`.
+    OverlappingExtensions {
+        first: String,
+        second: String,
+        extensions: Vec<String>,
+    },
+    /// Both rules move files into the same folder.
+    DuplicateDestination {
+        first: String,
+        second: String,
+        target_dir: String,
+    },
+}
+
+impl std::fmt::Display for RuleWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleWarning::Shadowed { rule, by } => write!(
+                f,
+                "rule '{}' never fires: every file it matches is taken by '{}' first",
+                rule, by
+            ),
+            RuleWarning::OverlappingExtensions {
+                first,
+                second,
+                extensions,
+            } => write!(
+                f,
+                "rules '{}' and '{}' both accept {}; '{}' wins",
+                first,
+                second,
+                extensions
+                    .iter()
+                    .map(|e| format!(".{}", e))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                first
+            ),
+            RuleWarning::DuplicateDestination {
+                first,
+                second,
+                target_dir,
+            } => write!(
+                f,
+                "rules '{}' and '{}' both move files to {}",
+                first, second, target_dir
+            ),
+        }
+    }
+}
+
+/// Lower-cased extensions, or `None` when the rule accepts any extension.
+fn extension_set(rule: &Rule) -> Option<BTreeSet<String>> {
+    rule.extensions
+        .as_ref()
+        .map(|exts| exts.iter().map(|e| e.to_ascii_lowercase()).collect())
+}
+
+/// A pattern that matches every name is the same as no pattern.
+fn effective_pattern(rule: &Rule) -> Option<&str> {
+    match rule.pattern.as_deref() {
+        None | Some("") | Some(".*") | Some("^.*$") | Some("^.*") | Some(".*$") => None,
+        Some(p) => Some(p),
+    }
+}
+
+/// Whether every file matching `later` also matches `earlier`. Patterns are
+/// only compared literally, so this errs on the side of "no".
+fn covers(earlier: &Rule, later: &Rule) -> bool {
+    let extensions_covered = match (extension_set(earlier), extension_set(later)) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(a), Some(b)) => b.is_subset(&a),
+    };
+    let pattern_covered = match (effective_pattern(earlier), effective_pattern(later)) {
+        (None, _) => true,
+        (Some(a), Some(b)) => a == b,
+        (Some(_), None) => false,
+    };
+    let min_covered = match (earlier.min_size_bytes, later.min_size_bytes) {
+        (None, _) => true,
+        (Some(a), Some(b)) => a <= b,
+        (Some(a), None) => a == 0,
+    };
+    let max_covered = match (earlier.max_size_bytes, later.max_size_bytes) {
+        (None, _) => true,
+        (Some(a), Some(b)) => a >= b,
+        (Some(_), None) => false,
+    };
+    extensions_covered && pattern_covered && min_covered && max_covered
+}
+
+fn normalize_dir(dir: &str) -> String {
+    let trimmed = dir.trim_end_matches(['/', '\\']).replace('\\', "/");
+    if cfg!(windows) {
+        trimmed.to_lowercase()
+    } else {
+        trimmed
+    }
+}
+
+/// Finds shadowed rules, overlapping extension sets and duplicate
+/// destinations among the enabled rules, in rule order.
+pub fn analyze_rules(rules: &[Rule]) -> Vec<RuleWarning> {
+    let enabled: Vec<&Rule> = rules.iter().filter(|r| r.enabled.unwrap_or(true)).collect();
+    let mut warnings = Vec::new();
+
+    for (i, later) in enabled.iter().enumerate() {
+        let earlier = &enabled[..i];
+        if let Some(by) = earlier.iter().find(|e| covers(e, later)) {
+            warnings.push(RuleWarning::Shadowed {
+                rule: later.name.clone(),
+                by: by.name.clone(),
+            });
+            // Overlaps with a dead rule add nothing
+            continue;
+        }
+        for first in earlier {
+            if let (Some(a), Some(b)) = (extension_set(first), extension_set(later)) {
+                let shared: Vec<String> = a.intersection(&b).cloned().collect();
+                if !shared.is_empty() {
+                    warnings.push(RuleWarning::OverlappingExtensions {
+                        first: first.name.clone(),
+                        second: later.name.clone(),
+                        extensions: shared,
+                    });
+                }
+            }
+        }
+    }
+
+    for (i, second) in enabled.iter().enumerate() {
+        let dir = normalize_dir(&second.target_dir);
+        if dir.is_empty() {
+            continue;
+        }
+        if let Some(first) = enabled[..i]
+            .iter()
+            .find(|f| normalize_dir(&f.target_dir) == dir)
+        {
+            warnings.push(RuleWarning::DuplicateDestination {
+                first: first.name.clone(),
+                second: second.name.clone(),
+                target_dir: second.target_dir.clone(),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Current [`RulePack::version`]; packs from newer versions are rejected.
+pub const PACK_VERSION: u32 = 1;
+
+/// Rules exported for import on another machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePack {
+    pub version: u32,
+    /// Download folder of the exporting machine, used to rebase `target_dir`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_dir: Option<String>,
+    pub rules: Vec<Rule>,
+}
+
+impl RulePack {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let p = path.as_ref();
+        let content =
+            std::fs::read_to_string(p).with_context(|| format!("read {}", p.display()))?;
+        let pack: RulePack = serde_yaml::from_str(&content).context("parse rule pack")?;
+        if pack.version > PACK_VERSION {
+            bail!(
+                "rule pack version {} is newer than supported ({})",
+                pack.version,
+                PACK_VERSION
+            );
+        }
+        Ok(pack)
+    }
+
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self).context("serialize rule pack")
+    }
+}
+
+/// Packs the named rules in config order, or every rule if `names` is empty.
+pub fn export_pack(cfg: &DownloadsConfig, names: &[String]) -> Result<RulePack> {
+    if let Some(missing) = names
+        .iter()
+        .find(|n| !cfg.rules.iter().any(|r| &r.name == *n))
+    {
+        bail!("no rule named '{}'", missing);
+    }
+    let rules = cfg
+        .rules
+        .iter()
+        .filter(|r| names.is_empty() || names.contains(&r.name))
+        // Ids are local; the importing side assigns its own
+        .map(|r| Rule {
+            id: String::new(),
+            ..r.clone()
+        })
+        .collect();
+    Ok(RulePack {
+        version: PACK_VERSION,
+        download_dir: Some(cfg.download_dir.clone()),
+        rules,
+    })
+}
+
+/// What to do when an imported rule has the name of an existing one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Collision {
+    /// Keep the local rule.
+    #[default]
+    Skip,
+    /// Add the imported rule as "Name (2)".
+    Rename,
+    /// Overwrite the local rule in place.
+    Replace,
+}
+
+impl std::str::FromStr for Collision {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "skip" => Ok(Collision::Skip),
+            "rename" => Ok(Collision::Rename),
+            "replace" => Ok(Collision::Replace),
+            other => bail!("unknown collision mode '{}'", other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    pub on_collision: Collision,
+    /// Move targets under the pack's download folder to the same place under
+    /// ours; relative targets are taken relative to our download folder.
+    pub rebase: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportReport {
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    /// `(name in the pack, name it was added as)`
+    pub renamed: Vec<(String, String)>,
+    pub skipped: Vec<String>,
+}
+
+fn rebase_target(target: &str, from: Option<&str>, to: &str) -> String {
+    let target_path = Path::new(target);
+    if let Some(rest) = from.and_then(|f| target_path.strip_prefix(f).ok()) {
+        return Path::new(to).join(rest).to_string_lossy().into_owned();
+    }
+    if target_path.is_relative() {
+        return Path::new(to)
+            .join(target_path)
+            .to_string_lossy()
+            .into_owned();
+    }
+    target.to_string()
+}
+
+fn unique_name(base: &str, taken: &HashSet<String>) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_default()
+}
+
+/// Merges `pack` into `cfg`. New rules are appended with fresh ids and
+/// replaced rules keep theirs; `cfg` is left untouched if the merged rule
+/// list does not validate.
+pub fn import_pack(
+    cfg: &mut DownloadsConfig,
+    pack: RulePack,
+    opts: ImportOptions,
+) -> Result<ImportReport> {
+    let mut merged = cfg.clone();
+    let mut report = ImportReport::default();
+    let mut taken: HashSet<String> = merged.rules.iter().map(|r| r.name.clone()).collect();
+    let pack_dir = pack.download_dir.as_deref().map(normalize_separators);
+
+    for mut rule in pack.rules {
+        let target = expand_env(&rule.target_dir)
+            .with_context(|| format!("rule '{}': target_dir", rule.name))?;
+        rule.target_dir = normalize_separators(&target);
+        if opts.rebase {
+            rule.target_dir =
+                rebase_target(&rule.target_dir, pack_dir.as_deref(), &merged.download_dir);
+        }
+
+        match merged.rules.iter().position(|r| r.name == rule.name) {
+            None => {
+                rule.id = new_rule_id();
+                taken.insert(rule.name.clone());
+                report.added.push(rule.name.clone());
+                merged.rules.push(rule);
+            }
+            Some(_) if opts.on_collision == Collision::Skip => report.skipped.push(rule.name),
+            Some(i) if opts.on_collision == Collision::Replace => {
+                report.replaced.push(rule.name.clone());
+                rule.id = std::mem::take(&mut merged.rules[i].id);
+                merged.rules[i] = rule;
+            }
+            Some(_) => {
+                let name = unique_name(&rule.name, &taken);
+                taken.insert(name.clone());
+                report.renamed.push((rule.name, name.clone()));
+                rule.id = new_rule_id();
+                rule.name = name;
+                merged.rules.push(rule);
+            }
+        }
+    }
+
+    validate_downloads_config(&merged)?;
+    *cfg = merged;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, exts: Option<&[&str]>, target: &str) -> Rule {
+        Rule {
+            id: String::new(),
+            name: name.into(),
+            extensions: exts.map(|e| e.iter().map(|s| s.to_string()).collect()),
+            pattern: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            target_dir: target.into(),
+            create_symlink: None,
+            enabled: None,
+            notify: None,
+        }
+    }
+
+    #[test]
+    fn test_shadowed_rules() {
+        let rules = vec![
+            Rule {
+                pattern: Some("^invoice".into()),
+                ..rule("Invoices", None, "/inv")
+            },
+            rule("Docs", Some(&["PDF", "docx"]), "/docs"),
+            rule("Pdf", Some(&["pdf"]), "/pdf"),
+            Rule {
+                min_size_bytes: Some(10),
+                ..rule("BigPdf", Some(&["pdf"]), "/big")
+            },
+            Rule {
+                pattern: Some("^invoice".into()),
+                ..rule("InvoicePdf", Some(&["pdf"]), "/inv2")
+            },
+            rule("Everything", None, "/misc"),
+            rule("Zip", Some(&["zip"]), "/zip"),
+            Rule {
+                enabled: Some(false),
+                notify: None,
+                ..rule("Off", Some(&["pdf"]), "/off")
+            },
+        ];
+        let shadowed: Vec<(String, String)> = analyze_rules(&rules)
+            .into_iter()
+            .filter_map(|w| match w {
+                RuleWarning::Shadowed { rule, by } => Some((rule, by)),
+                _ => None,
+            })
+            .collect();
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(
+            shadowed,
+            [
+                pair("Pdf", "Docs"),
+                pair("BigPdf", "Docs"),
+                pair("InvoicePdf", "Invoices"),
+                pair("Zip", "Everything"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_size_ranges() {
+        let sized = |name: &str, min, max| Rule {
+            min_size_bytes: min,
+            max_size_bytes: max,
+            ..rule(name, Some(&["iso"]), name)
+        };
+        // A narrower range is shadowed by a wider one, not the other way round
+        let rules = vec![
+            sized("Wide", Some(0), Some(100)),
+            sized("Narrow", Some(10), Some(50)),
+        ];
+        assert_eq!(analyze_rules(&rules).len(), 1);
+        let rules = vec![
+            sized("Narrow", Some(10), Some(50)),
+            sized("Wide", Some(0), Some(100)),
+        ];
+        assert!(matches!(
+            analyze_rules(&rules)[..],
+            [RuleWarning::OverlappingExtensions { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_overlaps_and_destinations() {
+        let rules = vec![
+            Rule {
+                pattern: Some("^scan".into()),
+                ..rule("Scans", Some(&["pdf", "png"]), "/docs/")
+            },
+            rule("Images", Some(&["png", "jpg"]), "/img"),
+            rule("Docs", Some(&["pdf"]), "/docs"),
+        ];
+        let warnings = analyze_rules(&rules);
+        assert_eq!(
+            warnings,
+            [
+                RuleWarning::OverlappingExtensions {
+                    first: "Scans".into(),
+                    second: "Images".into(),
+                    extensions: vec!["png".into()],
+                },
+                RuleWarning::OverlappingExtensions {
+                    first: "Scans".into(),
+                    second: "Docs".into(),
+                    extensions: vec!["pdf".into()],
+                },
+                RuleWarning::DuplicateDestination {
+                    first: "Scans".into(),
+                    second: "Docs".into(),
+                    target_dir: "/docs".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "rules 'Scans' and 'Images' both accept .png; 'Scans' wins"
+        );
+    }
+
+    fn config(download_dir: &str, rules: Vec<Rule>) -> DownloadsConfig {
+        DownloadsConfig {
+            download_dir: download_dir.into(),
+            rules,
+            min_age_secs: None,
+            tutorial_completed: Some(true),
+            service_enabled: None,
+            check_updates: None,
+            last_notified_version: None,
+            activity_retention: None,
+            notifications: None,
+            paused_until: None,
+            active_profile: None,
+            profiles: None,
+        }
+    }
+
+    #[test]
+    fn test_assign_rule_ids() {
+        let mut rules = vec![
+            rule("A", None, "/a"),
+            Rule {
+                id: "fixed".into(),
+                ..rule("B", None, "/b")
+            },
+            Rule {
+                id: "fixed".into(),
+                ..rule("C", None, "/c")
+            },
+        ];
+        assert!(assign_rule_ids(&mut rules));
+        assert_eq!(rules[1].id, "fixed");
+        let ids: HashSet<&str> = rules.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids.len(), 3);
+
+        // Another process migrating the same file gets the same ids
+        let mut again = vec![rule("A", None, "/a")];
+        assign_rule_ids(&mut again);
+        assert_eq!(again[0].id, rules[0].id);
+        assert!(!assign_rule_ids(&mut rules));
+        assert_ne!(new_rule_id(), new_rule_id());
+    }
+
+    #[test]
+    fn test_export_pack() {
+        let cfg = config(
+            "/home/a/Downloads",
+            vec![
+                rule("Images", Some(&["png"]), "/home/a/Downloads/Images"),
+                rule("Docs", Some(&["pdf"]), "/home/a/Docs"),
+            ],
+        );
+        let pack = export_pack(&cfg, &["Docs".into()]).unwrap();
+        assert_eq!(pack.rules.len(), 1);
+        assert_eq!(pack.download_dir.as_deref(), Some("/home/a/Downloads"));
+        assert_eq!(export_pack(&cfg, &[]).unwrap().rules.len(), 2);
+        assert!(export_pack(&cfg, &["Nope".into()]).is_err());
+
+        let yaml = pack.to_yaml().unwrap();
+        assert!(!yaml.contains("tutorial_completed"));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pack.yaml");
+        std::fs::write(&path, yaml).unwrap();
+        assert_eq!(RulePack::load(&path).unwrap().rules[0].name, "Docs");
+        std::fs::write(&path, "version: 99\nrules: []\n").unwrap();
+        assert!(RulePack::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_import_collisions() {
+        let pack = RulePack {
+            version: PACK_VERSION,
+            download_dir: None,
+            rules: vec![
+                rule("Images", Some(&["jpg"]), "/shared/img"),
+                rule("Music", Some(&["mp3"]), "/shared/music"),
+            ],
+        };
+        let local = || {
+            config(
+                "/dl",
+                vec![
+                    Rule {
+                        id: "local-images".into(),
+                        ..rule("Images", Some(&["png"]), "/img")
+                    },
+                    rule("Images (2)", Some(&["gif"]), "/gif"),
+                ],
+            )
+        };
+        let import = |on_collision| {
+            let mut cfg = local();
+            let opts = ImportOptions {
+                on_collision,
+                rebase: false,
+            };
+            let report = import_pack(&mut cfg, pack.clone(), opts).unwrap();
+            (cfg, report)
+        };
+
+        let (cfg, report) = import(Collision::Skip);
+        assert_eq!(report.skipped, ["Images"]);
+        assert_eq!(report.added, ["Music"]);
+        assert_eq!(cfg.rules[0].target_dir, "/img");
+
+        let (cfg, report) = import(Collision::Replace);
+        assert_eq!(report.replaced, ["Images"]);
+        assert_eq!(cfg.rules[0].target_dir, "/shared/img");
+        assert_eq!(cfg.rules[0].id, "local-images");
+        assert_eq!(cfg.rules.len(), 3);
+
+        let (cfg, report) = import(Collision::Rename);
+        assert_eq!(
+            report.renamed,
+            [("Images".to_string(), "Images (3)".to_string())]
+        );
+        assert_eq!(cfg.rules[2].name, "Images (3)");
+        assert!(!cfg.rules[2].id.is_empty());
+        assert_ne!(cfg.rules[2].id, cfg.rules[3].id);
+        assert!(cfg.tutorial_completed.unwrap());
+    }
+
+    #[test]
+    fn test_import_rebase_and_validation() {
+        let pack = RulePack {
+            version: PACK_VERSION,
+            download_dir: Some("/home/a/Downloads".into()),
+            rules: vec![
+                rule("Images", Some(&["png"]), "/home/a/Downloads/Images"),
+                rule("Archives", Some(&["zip"]), "Archives"),
+                rule("Shared", Some(&["iso"]), "/mnt/share"),
+            ],
+        };
+        let mut cfg = config("/home/b/Downloads", vec![]);
+        let opts = ImportOptions {
+            on_collision: Collision::Skip,
+            rebase: true,
+        };
+        import_pack(&mut cfg, pack, opts).unwrap();
+        let targets: Vec<&str> = cfg.rules.iter().map(|r| r.target_dir.as_str()).collect();
+        assert_eq!(
+            targets,
+            [
+                "/home/b/Downloads/Images",
+                "/home/b/Downloads/Archives",
+                "/mnt/share"
+            ]
+        );
+
+        let bad = RulePack {
+            version: PACK_VERSION,
+            download_dir: None,
+            rules: vec![Rule {
+                pattern: Some("(".into()),
+                ..rule("Bad", None, "/bad")
+            }],
+        };
+        assert!(import_pack(&mut cfg, bad, ImportOptions::default()).is_err());
+        assert_eq!(cfg.rules.len(), 3);
+    }
+}
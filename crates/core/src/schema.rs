@@ -0,0 +1,57 @@
+use crate::downloads::DownloadsConfig;
+use crate::types::WorkspaceConfig;
+use anyhow::{Context, Result};
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+/// The configuration files Harbor can describe with a JSON Schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// `harbor.downloads.yaml`
+    Downloads,
+    /// `harbor.config.yaml`
+    Workspace,
+}
+
+pub fn downloads_schema() -> RootSchema {
+    schema_for!(DownloadsConfig)
+}
+
+pub fn workspace_schema() -> RootSchema {
+    schema_for!(WorkspaceConfig)
+}
+
+/// Renders the schema for `kind` as pretty-printed JSON.
+///
+/// The output can be referenced from an editor, e.g. with a
+/// `# yaml-language-server: $schema=downloads.schema.json` header.
+pub fn schema_json(kind: SchemaKind) -> Result<String> {
+    let schema = match kind {
+        SchemaKind::Downloads => downloads_schema(),
+        SchemaKind::Workspace => workspace_schema(),
+    };
+    serde_json::to_string_pretty(&schema).context("serialize schema")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downloads_schema_has_rules() {
+        let json: serde_json::Value =
+            serde_json::from_str(&schema_json(SchemaKind::Downloads).unwrap()).unwrap();
+        assert_eq!(json["title"], "DownloadsConfig");
+        assert!(json["properties"]["rules"].is_object());
+        assert!(json["definitions"]["Rule"].is_object());
+    }
+
+    #[test]
+    fn test_workspace_schema_has_health_check() {
+        let json: serde_json::Value =
+            serde_json::from_str(&schema_json(SchemaKind::Workspace).unwrap()).unwrap();
+        assert_eq!(json["title"], "WorkspaceConfig");
+        assert!(json["definitions"]["Service"].is_object());
+        assert!(json["definitions"]["HealthCheck"].is_object());
+    }
+}
@@ -0,0 +1,877 @@
+//! The downloads watcher as a long-lived service.
+//!
+//! The tray, the desktop app and `harbor downloads-watch` all run the same
+//! [`WatcherService`]: it owns the shared config, the watcher thread, the activity
+//! log, rule stats and health report, and tells subscribers what happened. The
+//! frontends only map their buttons and output onto it.
+
+use crate::activity::ActivityLog;
+use crate::config_store::ConfigStore;
+use crate::downloads::{
+    scan_once, spawn_watcher, validate_downloads_config, DownloadsConfig, OrganizeResult,
+    RuleFailure, ScanReport, WatchEvent, WatcherThread,
+};
+use crate::rule_stats::RuleStatsStore;
+use crate::watcher_health::WatcherHealth;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Days, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{Duration, Instant};
+
+/// Whether the watcher is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceState {
+    Stopped,
+    Running,
+    /// The watcher thread is alive but skips its passes.
+    Paused,
+}
+
+/// A snapshot of the service for status displays.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub state: ServiceState,
+    /// Seconds since the watcher was started; `None` while stopped
+    pub uptime_seconds: Option<u64>,
+    /// Set when the config file was edited into an invalid state
+    pub config_error: Option<String>,
+    /// The watcher's latest pass; `None` while stopped
+    pub health: Option<WatcherHealth>,
+    /// When a timed pause ends; `None` unless snoozed
+    pub paused_until: Option<DateTime<Utc>>,
+    /// Seconds left until then
+    pub resumes_in_seconds: Option<u64>,
+}
+
+impl ServiceStatus {
+    pub fn is_running(&self) -> bool {
+        self.state != ServiceState::Stopped
+    }
+
+    /// One line for menus and tooltips, e.g. "Paused, resumes in 14 min".
+    pub fn summary(&self) -> String {
+        match (self.state, self.resumes_in_seconds) {
+            (ServiceState::Stopped, _) => "Stopped".to_string(),
+            (ServiceState::Running, _) => "Watching".to_string(),
+            (ServiceState::Paused, Some(secs)) => {
+                format!("Paused, resumes in {}", format_countdown(secs))
+            }
+            (ServiceState::Paused, None) => "Paused".to_string(),
+        }
+    }
+}
+
+/// Whole minutes, rounded up so the countdown never shows "0 min" early.
+fn format_countdown(secs: u64) -> String {
+    let minutes = secs.div_ceil(60);
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} min", h, m),
+    }
+}
+
+/// How long [`WatcherService::snooze`] pauses the watcher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnoozeFor {
+    Minutes(u32),
+    /// Until [`SnoozeFor::MORNING_HOUR`] o'clock the next day.
+    Tomorrow,
+}
+
+impl SnoozeFor {
+    /// Local hour "until tomorrow" ends at.
+    pub const MORNING_HOUR: u32 = 8;
+
+    /// When a snooze started at `now` ends.
+    pub fn until<Tz: TimeZone>(self, now: DateTime<Tz>) -> DateTime<Utc> {
+        match self {
+            SnoozeFor::Minutes(m) => (now + chrono::Duration::minutes(m.into())).to_utc(),
+            SnoozeFor::Tomorrow => now
+                .date_naive()
+                .checked_add_days(Days::new(1))
+                .and_then(|day| day.and_hms_opt(Self::MORNING_HOUR, 0, 0))
+                .and_then(|morning| morning.and_local_timezone(now.timezone()).earliest())
+                .map(|t| t.to_utc())
+                // Only a DST gap at exactly that hour gets here
+                .unwrap_or_else(|| (now + chrono::Duration::days(1)).to_utc()),
+        }
+    }
+
+    /// When a snooze started now ends, in local time.
+    pub fn until_now(self) -> DateTime<Utc> {
+        self.until(Local::now())
+    }
+}
+
+/// `15m`, `1h` or `tomorrow`.
+impl FromStr for SnoozeFor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        if s == "tomorrow" {
+            return Ok(SnoozeFor::Tomorrow);
+        }
+        let (number, per_unit) = if let Some(n) = s.strip_suffix('m') {
+            (n, 1)
+        } else if let Some(n) = s.strip_suffix('h') {
+            (n, 60)
+        } else {
+            bail!(
+                "expected a duration like 15m or 1h, or 'tomorrow', got '{}'",
+                s
+            );
+        };
+        let n: u32 = number
+            .parse()
+            .with_context(|| format!("invalid duration '{}'", s))?;
+        if n == 0 {
+            bail!("duration must be positive");
+        }
+        Ok(SnoozeFor::Minutes(n.saturating_mul(per_unit)))
+    }
+}
+
+/// Something subscribers may want to show, sent in the order it happened.
+#[derive(Debug, Clone)]
+pub enum ServiceEvent {
+    /// Files were moved, by the watcher or an on-demand run.
+    Organized(Vec<OrganizeResult>),
+    /// Files matched a rule but could not be moved.
+    Failed(Vec<RuleFailure>),
+    /// The watcher finished a pass.
+    Scanned(WatcherHealth),
+    /// A new version of the config file is now active.
+    ConfigReloaded,
+    /// The config file changed but could not be loaded; the previous config stays active.
+    ConfigError(String),
+    /// The watcher was started, stopped, paused or resumed.
+    StateChanged(ServiceState),
+    /// The activity log, rule stats, health report or rule ids could not be written.
+    RecordError(String),
+}
+
+impl From<WatchEvent> for ServiceEvent {
+    fn from(event: WatchEvent) -> Self {
+        match event {
+            WatchEvent::Organized(actions) => Self::Organized(actions),
+            WatchEvent::Failed(failures) => Self::Failed(failures),
+            WatchEvent::Scanned(health) => Self::Scanned(health),
+            WatchEvent::ConfigReloaded => Self::ConfigReloaded,
+            WatchEvent::ConfigError(msg) => Self::ConfigError(msg),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    watcher: Mutex<Option<WatcherThread>>,
+    started_at: Mutex<Option<Instant>>,
+    config_error: Mutex<Option<String>>,
+    health: Mutex<Option<WatcherHealth>>,
+    /// Why the last expired snooze could not be cleared from the config file
+    snooze_error: Mutex<Option<String>>,
+    subscribers: Mutex<Vec<Sender<ServiceEvent>>>,
+}
+
+/// Runs the downloads watcher and records what it does.
+///
+/// Clones share the same watcher. Configure it with the `with_*` builders
+/// before cloning or starting it.
+#[derive(Clone)]
+pub struct WatcherService {
+    config: Arc<RwLock<DownloadsConfig>>,
+    store: Option<Arc<ConfigStore>>,
+    log_path: PathBuf,
+    interval_secs: u64,
+    shared: Arc<Shared>,
+}
+
+impl WatcherService {
+    /// Seconds between passes unless [`WatcherService::with_interval`] says otherwise.
+    pub const DEFAULT_INTERVAL_SECS: u64 = 5;
+
+    pub fn new(config: DownloadsConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            store: None,
+            log_path: ActivityLog::default_path(),
+            interval_secs: Self::DEFAULT_INTERVAL_SECS,
+            shared: Arc::default(),
+        }
+    }
+
+    /// Follows edits to the config file at `path`, and keeps rule stats and the
+    /// health report next to it.
+    pub fn with_config_path(self, path: impl AsRef<Path>) -> Self {
+        self.with_store(Arc::new(ConfigStore::new(path)))
+    }
+
+    /// Like [`WatcherService::with_config_path`], sharing a store the frontend also saves through.
+    pub fn with_store(mut self, store: Arc<ConfigStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn with_log_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.log_path = path.into();
+        self
+    }
+
+    pub fn with_interval(mut self, interval_secs: u64) -> Self {
+        self.interval_secs = interval_secs;
+        self
+    }
+
+    /// The config the watcher reads on every pass; edits show up on the next one.
+    pub fn config(&self) -> &Arc<RwLock<DownloadsConfig>> {
+        &self.config
+    }
+
+    pub fn config_snapshot(&self) -> DownloadsConfig {
+        self.config
+            .read()
+            .map(|c| c.clone())
+            .unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    pub fn config_path(&self) -> Option<&Path> {
+        self.store.as_deref().map(ConfigStore::path)
+    }
+
+    pub fn store(&self) -> Option<&Arc<ConfigStore>> {
+        self.store.as_ref()
+    }
+
+    pub fn log_path(&self) -> &Path {
+        &self.log_path
+    }
+
+    /// The activity log, rotated according to the current config.
+    pub fn activity_log(&self) -> ActivityLog {
+        let retention = self.config_snapshot().activity_retention;
+        ActivityLog::new(&self.log_path).with_retention(retention.unwrap_or_default())
+    }
+
+    /// Rule stats live next to the config file, so there are none without one.
+    pub fn rule_stats(&self) -> Option<RuleStatsStore> {
+        self.config_path().map(RuleStatsStore::for_config)
+    }
+
+    /// Receives every event from now on; dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> Receiver<ServiceEvent> {
+        let (tx, rx) = mpsc::channel();
+        lock(&self.shared.subscribers).push(tx);
+        rx
+    }
+
+    /// Starts the watcher, or resumes it when paused. Does nothing if it is
+    /// already running. Fails while a stopped watcher is still finishing its
+    /// last pass, so two watchers never run at once.
+    pub fn start(&self) -> Result<()> {
+        let mut watcher = lock(&self.shared.watcher);
+        if let Some(thread) = watcher.as_ref().filter(|w| !w.is_finished()) {
+            if thread.is_stopping() {
+                bail!("the watcher is still finishing its last pass; try again shortly");
+            }
+            if thread.is_paused() {
+                thread.resume();
+                self.broadcast(ServiceEvent::StateChanged(self.running_state()));
+            }
+            return Ok(());
+        }
+
+        // Announced first so subscribers never see a pass before the start.
+        // A snooze saved before a restart still holds.
+        *lock(&self.shared.started_at) = Some(Instant::now());
+        self.broadcast(ServiceEvent::StateChanged(self.running_state()));
+        self.persist_rule_ids();
+        let service = self.clone();
+        *watcher = Some(spawn_watcher(
+            self.config.clone(),
+            self.config_path().map(Path::to_path_buf),
+            self.interval_secs,
+            move |event| service.on_watch_event(event),
+        ));
+        Ok(())
+    }
+
+    /// Stops the watcher and waits up to [`WatcherThread::STOP_TIMEOUT`] for a
+    /// pass in progress. Returns whether the thread exited; if not, it stays
+    /// registered and [`WatcherService::start`] refuses to run a second
+    /// watcher until it has.
+    pub fn stop(&self) -> bool {
+        self.stop_within(WatcherThread::STOP_TIMEOUT)
+    }
+
+    /// [`WatcherService::stop`] with a custom wait, for callers that would
+    /// rather block than leave a move half done.
+    pub fn stop_within(&self, timeout: Duration) -> bool {
+        let mut watcher = lock(&self.shared.watcher);
+        let Some(thread) = watcher.as_ref() else {
+            return true;
+        };
+        // Stopped already announced by an earlier call that timed out
+        let announced = thread.is_stopping();
+        thread.signal_stop();
+        let exited = thread.wait(timeout);
+        if exited {
+            if let Some(thread) = watcher.take() {
+                thread.stop(None);
+            }
+        }
+        drop(watcher);
+        if !announced {
+            *lock(&self.shared.started_at) = None;
+            *lock(&self.shared.health) = None;
+            self.broadcast(ServiceEvent::StateChanged(ServiceState::Stopped));
+        }
+        exited
+    }
+
+    /// Keeps the watcher alive but skips its passes until [`WatcherService::start`]
+    /// or [`WatcherService::resume`].
+    pub fn pause(&self) -> Result<()> {
+        let watcher = lock(&self.shared.watcher);
+        let Some(thread) = watcher
+            .as_ref()
+            .filter(|w| !w.is_finished() && !w.is_stopping())
+        else {
+            bail!("the watcher is not running");
+        };
+        if !thread.is_paused() {
+            thread.pause();
+            self.broadcast(ServiceEvent::StateChanged(ServiceState::Paused));
+        }
+        Ok(())
+    }
+
+    /// Pauses the watcher until `until`, after which it resumes on its own.
+    /// The time is saved in the config, so the pause outlasts a restart.
+    pub fn snooze(&self, until: DateTime<Utc>) -> Result<()> {
+        if self.state() == ServiceState::Stopped {
+            bail!("the watcher is not running");
+        }
+        self.set_paused_until(Some(until))?;
+        self.broadcast(ServiceEvent::StateChanged(ServiceState::Paused));
+        Ok(())
+    }
+
+    /// Undoes [`WatcherService::pause`] and [`WatcherService::snooze`]; does
+    /// nothing unless paused.
+    pub fn resume(&self) -> Result<()> {
+        let snoozed = self.snoozed_until().is_some();
+        if snoozed {
+            self.set_paused_until(None)?;
+        }
+        let watcher = lock(&self.shared.watcher);
+        let Some(thread) = watcher
+            .as_ref()
+            .filter(|w| !w.is_finished() && !w.is_stopping())
+        else {
+            return Ok(());
+        };
+        if thread.is_paused() || snoozed {
+            thread.resume();
+            self.broadcast(ServiceEvent::StateChanged(ServiceState::Running));
+        }
+        Ok(())
+    }
+
+    pub fn state(&self) -> ServiceState {
+        match lock(&self.shared.watcher).as_ref() {
+            Some(thread) if thread.is_finished() || thread.is_stopping() => ServiceState::Stopped,
+            Some(thread) if thread.is_paused() => ServiceState::Paused,
+            Some(_) => self.running_state(),
+            None => ServiceState::Stopped,
+        }
+    }
+
+    pub fn status(&self) -> ServiceStatus {
+        let state = self.state();
+        let running = state != ServiceState::Stopped;
+        let paused_until = self.snoozed_until().filter(|_| running);
+        ServiceStatus {
+            state,
+            uptime_seconds: lock(&self.shared.started_at)
+                .filter(|_| running)
+                .map(|t| t.elapsed().as_secs()),
+            config_error: lock(&self.shared.config_error).clone(),
+            // A stopped watcher may still report its final pass; ignore it
+            health: lock(&self.shared.health).clone().filter(|_| running),
+            paused_until,
+            resumes_in_seconds: paused_until
+                .map(|t| (t - Utc::now()).num_seconds().max(0).unsigned_abs()),
+        }
+    }
+
+    /// Organizes the download folder once, whether or not the watcher runs.
+    /// Files that could not be moved are in the report, not retried.
+    pub fn organize_now(&self) -> Result<ScanReport> {
+        let scan = scan_once(&self.config_snapshot(), None)?;
+        self.record_scan(&scan);
+        Ok(scan)
+    }
+
+    /// Organizes the given files and folders (one level deep) with the current rules.
+    pub fn organize_paths(&self, paths: &[PathBuf]) -> Result<ScanReport> {
+        let scan = crate::downloads::organize_paths(&self.config_snapshot(), paths)?;
+        self.record_scan(&scan);
+        Ok(scan)
+    }
+
+    /// Loads and validates the config file and makes it active right away.
+    pub fn reload(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            bail!("no config file to reload");
+        };
+        let new_config = store.load().and_then(|cfg| {
+            validate_downloads_config(&cfg)?;
+            Ok(cfg)
+        })?;
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = new_config;
+        *lock(&self.shared.config_error) = None;
+        self.broadcast(ServiceEvent::ConfigReloaded);
+        Ok(())
+    }
+
+    fn on_watch_event(&self, event: WatchEvent) {
+        match &event {
+            WatchEvent::Organized(actions) => self.record_moves(actions),
+            WatchEvent::Failed(failures) => self.record_failures(failures),
+            WatchEvent::ConfigReloaded => {
+                if let Some(store) = &self.store {
+                    store.resync();
+                }
+                self.persist_rule_ids();
+                *lock(&self.shared.config_error) = None;
+            }
+            WatchEvent::ConfigError(msg) => {
+                *lock(&self.shared.config_error) = Some(msg.clone());
+            }
+            WatchEvent::Scanned(health) => {
+                let snooze_over = self
+                    .config
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .paused_until
+                    .is_some_and(|until| until <= Utc::now());
+                if snooze_over {
+                    self.end_snooze();
+                }
+                if let Some(path) = self.config_path() {
+                    if let Err(e) = health.save(WatcherHealth::path_for_config(path)) {
+                        self.broadcast(ServiceEvent::RecordError(format!("{:#}", e)));
+                    }
+                }
+                *lock(&self.shared.health) = Some(health.clone());
+            }
+        }
+        self.broadcast(event.into());
+    }
+
+    fn snoozed_until(&self) -> Option<DateTime<Utc>> {
+        self.config
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .snoozed_until(Utc::now())
+    }
+
+    /// Running or, while snoozed, paused.
+    fn running_state(&self) -> ServiceState {
+        match self.snoozed_until() {
+            Some(_) => ServiceState::Paused,
+            None => ServiceState::Running,
+        }
+    }
+
+    /// Saves the pause time when there is a config file, then updates the
+    /// shared config; a failed save leaves it alone.
+    fn set_paused_until(&self, until: Option<DateTime<Utc>>) -> Result<()> {
+        let mut config = self.config.write().unwrap_or_else(|e| e.into_inner());
+        let mut edited = config.clone();
+        edited.paused_until = until;
+        if let Some(store) = &self.store {
+            store.save(&edited)?;
+        }
+        *config = edited;
+        Ok(())
+    }
+
+    /// Clears an expired snooze. Runs after every pass, so an error is only
+    /// reported when it differs from the last one.
+    fn end_snooze(&self) {
+        match self.set_paused_until(None) {
+            Ok(()) => {
+                *lock(&self.shared.snooze_error) = None;
+                self.broadcast(ServiceEvent::StateChanged(ServiceState::Running));
+            }
+            Err(e) => {
+                let msg = format!("ending the snooze: {:#}", e);
+                let last = lock(&self.shared.snooze_error).replace(msg.clone());
+                if last.as_ref() != Some(&msg) {
+                    self.broadcast(ServiceEvent::RecordError(msg));
+                }
+            }
+        }
+    }
+
+    fn record_scan(&self, scan: &ScanReport) {
+        if !scan.actions.is_empty() {
+            self.record_moves(&scan.actions);
+            self.broadcast(ServiceEvent::Organized(scan.actions.clone()));
+        }
+        if !scan.failures.is_empty() {
+            self.record_failures(&scan.failures);
+            self.broadcast(ServiceEvent::Failed(scan.failures.clone()));
+        }
+    }
+
+    /// Saves the ids of rules that have none in the config file yet, so they
+    /// survive the rule being renamed there.
+    fn persist_rule_ids(&self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        if let Err(e) = store.persist_rule_ids() {
+            self.broadcast(ServiceEvent::RecordError(format!(
+                "saving rule ids: {:#}",
+                e
+            )));
+        }
+    }
+
+    fn record_moves(&self, actions: &[OrganizeResult]) {
+        let mut result = self.activity_log().record_moves(actions);
+        if let Some(stats) = self.rule_stats() {
+            result = result.and(stats.record_moves(actions));
+        }
+        if let Err(e) = result {
+            self.broadcast(ServiceEvent::RecordError(format!("{:#}", e)));
+        }
+    }
+
+    fn record_failures(&self, failures: &[RuleFailure]) {
+        let mut result = self.activity_log().record_failures(failures);
+        if let Some(stats) = self.rule_stats() {
+            result = result.and(stats.record_failures(failures));
+        }
+        if let Err(e) = result {
+            self.broadcast(ServiceEvent::RecordError(format!("{:#}", e)));
+        }
+    }
+
+    fn broadcast(&self, event: ServiceEvent) {
+        lock(&self.shared.subscribers).retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+/// A panicked watcher callback must not take the whole service down with it.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Rule;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn service_in(temp: &TempDir) -> WatcherService {
+        let downloads = temp.path().join("Downloads");
+        fs::create_dir(&downloads).unwrap();
+        let mut cfg = crate::downloads::default_config();
+        cfg.download_dir = downloads.display().to_string();
+        cfg.min_age_secs = Some(0);
+        cfg.rules = vec![Rule {
+            id: "docs".into(),
+            name: "Docs".into(),
+            extensions: Some(vec!["pdf".into()]),
+            pattern: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            target_dir: temp.path().join("Docs").display().to_string(),
+            create_symlink: None,
+            enabled: None,
+            notify: None,
+        }];
+        let cfg_path = temp.path().join("harbor.downloads.yaml");
+        fs::write(&cfg_path, serde_yaml::to_string(&cfg).unwrap()).unwrap();
+        WatcherService::new(cfg)
+            .with_config_path(&cfg_path)
+            .with_log_path(temp.path().join("activity.log"))
+    }
+
+    fn next_event(
+        rx: &Receiver<ServiceEvent>,
+        wanted: impl Fn(&ServiceEvent) -> bool,
+    ) -> ServiceEvent {
+        loop {
+            let event = rx
+                .recv_timeout(Duration::from_secs(5))
+                .expect("no matching event");
+            if wanted(&event) {
+                return event;
+            }
+        }
+    }
+
+    #[test]
+    fn test_start_pause_stop() {
+        let temp = TempDir::new().unwrap();
+        let service = service_in(&temp);
+        let events = service.subscribe();
+        assert_eq!(service.state(), ServiceState::Stopped);
+        assert!(service.pause().is_err());
+
+        fs::write(temp.path().join("Downloads/a.pdf"), b"pdf").unwrap();
+        service.start().unwrap();
+        service.start().unwrap();
+        assert_eq!(service.state(), ServiceState::Running);
+        let ServiceEvent::Organized(actions) =
+            next_event(&events, |e| matches!(e, ServiceEvent::Organized(_)))
+        else {
+            unreachable!()
+        };
+        assert_eq!(actions[0].2, "Docs");
+        next_event(&events, |e| matches!(e, ServiceEvent::Scanned(_)));
+        let status = service.status();
+        assert!(status.is_running());
+        assert!(status.health.is_some());
+
+        service.pause().unwrap();
+        assert_eq!(service.status().state, ServiceState::Paused);
+        service.start().unwrap();
+        assert_eq!(service.state(), ServiceState::Running);
+
+        let started = Instant::now();
+        assert!(service.stop());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        let status = service.status();
+        assert_eq!(status.state, ServiceState::Stopped);
+        assert!(status.uptime_seconds.is_none());
+        assert!(status.health.is_none());
+
+        let changes: Vec<ServiceState> = events
+            .try_iter()
+            .filter_map(|e| match e {
+                ServiceEvent::StateChanged(state) => Some(state),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ServiceState::Paused,
+                ServiceState::Running,
+                ServiceState::Stopped
+            ]
+        );
+
+        // The move was recorded once, by the service
+        let records = service.activity_log().read_all().unwrap();
+        assert_eq!(records.len(), 1);
+        let stats = service.rule_stats().unwrap().load().unwrap();
+        assert_eq!(stats["docs"].matches, 1);
+        let health_path = WatcherHealth::path_for_config(service.config_path().unwrap());
+        assert!(health_path.exists());
+    }
+
+    #[test]
+    fn test_start_refused_until_stopped_pass_exits() {
+        let temp = TempDir::new().unwrap();
+        let service = service_in(&temp);
+        let events = service.subscribe();
+
+        // Holding the activity log's lock blocks the pass while it records the move
+        let log_lock = fs::File::create(temp.path().join("activity.log.lock")).unwrap();
+        log_lock.lock().unwrap();
+        fs::write(temp.path().join("Downloads/a.pdf"), b"pdf").unwrap();
+        service.start().unwrap();
+        let moved = temp.path().join("Docs/a.pdf");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !moved.exists() {
+            assert!(Instant::now() < deadline, "the pass never ran");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert!(!service.stop_within(Duration::from_millis(50)));
+        assert_eq!(service.state(), ServiceState::Stopped);
+        assert!(service.start().is_err());
+        assert!(service.pause().is_err());
+        // A second stop does not announce Stopped again
+        assert!(!service.stop_within(Duration::from_millis(10)));
+
+        drop(log_lock);
+        assert!(service.stop());
+        service.start().unwrap();
+        assert_eq!(service.state(), ServiceState::Running);
+        assert!(service.stop());
+
+        let changes: Vec<ServiceState> = events
+            .try_iter()
+            .filter_map(|e| match e {
+                ServiceEvent::StateChanged(state) => Some(state),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ServiceState::Running,
+                ServiceState::Stopped,
+                ServiceState::Running,
+                ServiceState::Stopped
+            ]
+        );
+        assert_eq!(service.activity_log().read_all().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_snooze_survives_restart_and_expires() {
+        let temp = TempDir::new().unwrap();
+        let service = service_in(&temp).with_interval(0);
+        let in_15_min = || SnoozeFor::Minutes(15).until_now();
+        assert!(service.snooze(in_15_min()).is_err());
+
+        service.start().unwrap();
+        service.snooze(in_15_min()).unwrap();
+        let status = service.status();
+        assert_eq!(status.state, ServiceState::Paused);
+        assert_eq!(status.summary(), "Paused, resumes in 15 min");
+        fs::write(temp.path().join("Downloads/a.pdf"), b"pdf").unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!temp.path().join("Docs/a.pdf").exists());
+        service.stop();
+
+        // A new service reads the snooze back from the config file
+        let cfg_path = service.config_path().unwrap().to_path_buf();
+        let cfg = crate::downloads::load_downloads_config(&cfg_path).unwrap();
+        let restarted = WatcherService::new(cfg)
+            .with_config_path(&cfg_path)
+            .with_log_path(temp.path().join("activity.log"))
+            .with_interval(0);
+        let events = restarted.subscribe();
+        restarted.start().unwrap();
+        assert_eq!(restarted.state(), ServiceState::Paused);
+
+        restarted.config().write().unwrap().paused_until =
+            Some(Utc::now() - chrono::Duration::seconds(1));
+        next_event(&events, |e| matches!(e, ServiceEvent::Organized(_)));
+        next_event(&events, |e| {
+            matches!(e, ServiceEvent::StateChanged(ServiceState::Running))
+        });
+        assert!(temp.path().join("Docs/a.pdf").exists());
+        let saved = crate::downloads::load_downloads_config(&cfg_path).unwrap();
+        assert!(saved.paused_until.is_none());
+
+        restarted.snooze(in_15_min()).unwrap();
+        restarted.resume().unwrap();
+        assert_eq!(restarted.state(), ServiceState::Running);
+        assert!(restarted.status().resumes_in_seconds.is_none());
+        restarted.stop();
+    }
+
+    #[test]
+    fn test_rejected_snooze_save_leaves_config_alone() {
+        let temp = TempDir::new().unwrap();
+        let service = service_in(&temp);
+        let events = service.subscribe();
+        let cfg_path = service.config_path().unwrap().to_path_buf();
+        let expired = Utc::now() - chrono::Duration::seconds(1);
+        service.set_paused_until(Some(expired)).unwrap();
+
+        // Someone else edits the file; saves are refused until it is reloaded
+        let edited = fs::read_to_string(&cfg_path).unwrap() + "\n# edited\n";
+        fs::write(&cfg_path, edited).unwrap();
+        assert!(service
+            .set_paused_until(Some(SnoozeFor::Minutes(15).until_now()))
+            .is_err());
+        assert_eq!(service.config_snapshot().paused_until, Some(expired));
+
+        // The expired snooze stays in place and the error is reported once
+        for _ in 0..3 {
+            service.on_watch_event(WatchEvent::Scanned(WatcherHealth::default()));
+        }
+        assert_eq!(service.config_snapshot().paused_until, Some(expired));
+        let errors = events
+            .try_iter()
+            .filter(|e| matches!(e, ServiceEvent::RecordError(_)))
+            .count();
+        assert_eq!(errors, 1);
+
+        service.store.as_ref().unwrap().resync();
+        service.on_watch_event(WatchEvent::Scanned(WatcherHealth::default()));
+        assert!(service.config_snapshot().paused_until.is_none());
+    }
+
+    #[test]
+    fn test_snooze_for() {
+        assert_eq!("15m".parse::<SnoozeFor>().unwrap(), SnoozeFor::Minutes(15));
+        assert_eq!("2h".parse::<SnoozeFor>().unwrap(), SnoozeFor::Minutes(120));
+        assert_eq!(
+            "Tomorrow".parse::<SnoozeFor>().unwrap(),
+            SnoozeFor::Tomorrow
+        );
+        assert!("0m".parse::<SnoozeFor>().is_err());
+        assert!("soon".parse::<SnoozeFor>().is_err());
+
+        let evening = Utc.with_ymd_and_hms(2024, 3, 1, 22, 30, 0).unwrap();
+        assert_eq!(
+            SnoozeFor::Tomorrow.until(evening),
+            Utc.with_ymd_and_hms(2024, 3, 2, 8, 0, 0).unwrap()
+        );
+        assert_eq!(
+            SnoozeFor::Minutes(60).until(evening),
+            Utc.with_ymd_and_hms(2024, 3, 1, 23, 30, 0).unwrap()
+        );
+
+        assert_eq!(format_countdown(1), "1 min");
+        assert_eq!(format_countdown(3600), "1 h");
+        assert_eq!(format_countdown(3601), "1 h 1 min");
+    }
+
+    #[test]
+    fn test_organize_now_and_reload() {
+        let temp = TempDir::new().unwrap();
+        let service = service_in(&temp);
+        let events = service.subscribe();
+
+        fs::write(temp.path().join("Downloads/b.pdf"), b"pdf").unwrap();
+        let scan = service.organize_now().unwrap();
+        assert_eq!(scan.actions.len(), 1);
+        assert!(temp.path().join("Docs/b.pdf").exists());
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            ServiceEvent::Organized(_)
+        ));
+        assert_eq!(service.activity_log().read_all().unwrap().len(), 1);
+
+        let cfg_path = service.config_path().unwrap().to_path_buf();
+        let mut cfg = service.config_snapshot();
+        cfg.rules.clear();
+        fs::write(&cfg_path, serde_yaml::to_string(&cfg).unwrap()).unwrap();
+        service.reload().unwrap();
+        assert!(service.config_snapshot().rules.is_empty());
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            ServiceEvent::ConfigReloaded
+        ));
+
+        fs::write(&cfg_path, "download_dir: [").unwrap();
+        assert!(service.reload().is_err());
+        assert!(service.config_snapshot().rules.is_empty());
+
+        drop(events);
+        service.broadcast(ServiceEvent::ConfigReloaded);
+        assert!(service.shared.subscribers.lock().unwrap().is_empty());
+    }
+}
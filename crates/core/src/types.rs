@@ -1,32 +1,43 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceConfig {
+    /// Services to launch, ordered by their `depends_on` graph.
     pub services: Vec<Service>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Service {
+    /// Unique service name.
     pub name: String,
+    /// Shell command used to start the service.
     pub command: String,
+    /// Working directory, relative to the base directory.
     pub cwd: Option<String>,
+    /// Extra environment variables for the process.
     pub env: Option<HashMap<String, String>>,
+    /// Names of services that must be started first.
     pub depends_on: Option<Vec<String>>,
+    /// Readiness probe run after the service is spawned.
     pub health_check: Option<HealthCheck>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HealthCheck {
     pub kind: HealthCheckKind,
+    /// Command to run for `kind: command`.
     pub command: Option<String>,
+    /// URL to probe for `kind: http`.
     pub url: Option<String>,
+    /// Local port to connect to for `kind: tcp`.
     pub tcp_port: Option<u16>,
     pub timeout_ms: Option<u64>,
     pub retries: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum HealthCheckKind {
     Command,
@@ -35,17 +46,31 @@ pub enum HealthCheckKind {
     None,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Rule {
+    /// Stable identifier that survives renames. Assigned when the config is
+    /// loaded if missing.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    /// Display name of the rule.
     pub name: String,
+    /// File extensions (without the leading dot) this rule applies to.
     pub extensions: Option<Vec<String>>,
+    /// Regex matched against the file name.
     pub pattern: Option<String>,
+    /// Minimum file size in bytes.
     pub min_size_bytes: Option<u64>,
+    /// Maximum file size in bytes.
     pub max_size_bytes: Option<u64>,
+    /// Directory matching files are moved to.
     pub target_dir: String,
+    /// Leave a symlink at the original location after moving.
     pub create_symlink: Option<bool>,
+    /// Disabled rules are skipped during organization.
     #[serde(default = "default_enabled")]
     pub enabled: Option<bool>,
+    /// Include moves made by this rule in desktop notifications (default: false).
+    pub notify: Option<bool>,
 }
 
 fn default_enabled() -> Option<bool> {
@@ -74,6 +99,7 @@ mod tests {
     #[test]
     fn test_rule_serde() {
         let r = Rule {
+            id: String::new(),
             name: "rule".to_string(),
             extensions: Some(vec!["txt".to_string()]),
             pattern: None,
@@ -82,6 +108,7 @@ mod tests {
             target_dir: "target".to_string(),
             create_symlink: None,
             enabled: Some(true),
+            notify: None,
         };
         let json = serde_json::to_string(&r).unwrap();
         let r2: Rule = serde_json::from_str(&json).unwrap();
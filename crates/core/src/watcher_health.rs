@@ -0,0 +1,186 @@
+//! How the downloads watcher is doing, published after every pass.
+//!
+//! The watcher runs inside the app, the tray or `harbor downloads-watch`, so the
+//! last report is also written next to the config for `harbor status --downloads`.
+
+use crate::downloads::ScanReport;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The watcher's state after its most recent pass.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatcherHealth {
+    /// Process running the watcher
+    pub pid: u32,
+    /// Seconds between passes
+    pub interval_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_scan: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_scan_ms: u64,
+    /// Entries seen by the last successful pass
+    #[serde(default)]
+    pub files_examined: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error_at: Option<DateTime<Utc>>,
+    /// Failed passes since the last one that worked
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// Failed passes since the watcher started
+    #[serde(default)]
+    pub total_failures: u64,
+    /// Files whose move failed and that will be tried again
+    #[serde(default)]
+    pub pending_retries: usize,
+    /// Why the watcher cannot do its job at all, e.g. the download folder is gone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub degraded: Option<String>,
+}
+
+impl WatcherHealth {
+    pub fn new(interval_secs: u64) -> Self {
+        Self {
+            pid: std::process::id(),
+            interval_secs,
+            ..Default::default()
+        }
+    }
+
+    /// Records a pass over `download_dir` that took `elapsed` and either
+    /// finished or failed as a whole. A file that could not be moved becomes
+    /// the last error without failing the pass.
+    pub fn record_pass(
+        &mut self,
+        download_dir: &Path,
+        elapsed: Duration,
+        result: std::result::Result<&ScanReport, &anyhow::Error>,
+    ) {
+        let now = Utc::now();
+        self.last_scan = Some(now);
+        self.last_scan_ms = elapsed.as_millis() as u64;
+        match result {
+            Ok(scan) => {
+                self.files_examined = scan.examined;
+                self.consecutive_failures = 0;
+                self.degraded = None;
+                if let Some(failure) = scan.failures.last() {
+                    self.last_error = Some(failure.to_string());
+                    self.last_error_at = Some(now);
+                }
+            }
+            Err(e) => {
+                self.last_error = Some(format!("{:#}", e));
+                self.last_error_at = Some(now);
+                self.consecutive_failures += 1;
+                self.total_failures += 1;
+                self.degraded = check_download_dir(download_dir);
+            }
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.degraded.is_none() && self.consecutive_failures == 0
+    }
+
+    /// True when no pass has been reported for three intervals, which usually
+    /// means the watcher that wrote this report has exited.
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        let Some(last) = self.last_scan else {
+            return true;
+        };
+        let grace = chrono::Duration::seconds(self.interval_secs.max(1) as i64 * 3 + 5);
+        now - last > grace
+    }
+
+    /// `harbor.downloads.health.json` for `harbor.downloads.yaml`.
+    pub fn path_for_config(config_path: impl AsRef<Path>) -> PathBuf {
+        config_path.as_ref().with_extension("health.json")
+    }
+
+    /// Reads a saved report; `None` when no watcher has written one yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .with_context(|| format!("parsing {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+
+    /// Writes the report through a temporary file so readers never see half of it.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_os_string();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("replacing {}", path.display()))?;
+        Ok(())
+    }
+}
+
+fn check_download_dir(dir: &Path) -> Option<String> {
+    match fs::read_dir(dir) {
+        Ok(_) => None,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Some(format!("download folder {} is missing", dir.display()))
+        }
+        Err(e) => Some(format!(
+            "cannot read download folder {}: {}",
+            dir.display(),
+            e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_pass() {
+        let temp = TempDir::new().unwrap();
+        let missing = temp.path().join("Downloads");
+        let mut health = WatcherHealth::new(5);
+        assert!(health.is_stale(Utc::now()));
+
+        let err = anyhow::anyhow!("list {}", missing.display());
+        health.record_pass(&missing, Duration::from_millis(3), Err(&err));
+        health.record_pass(&missing, Duration::from_millis(3), Err(&err));
+        assert!(!health.is_healthy());
+        assert_eq!(health.consecutive_failures, 2);
+        assert!(health.degraded.as_deref().unwrap().contains("is missing"));
+        assert!(health.last_error_at.is_some());
+
+        fs::create_dir(&missing).unwrap();
+        let scan = ScanReport {
+            examined: 4,
+            ..Default::default()
+        };
+        health.record_pass(&missing, Duration::from_millis(7), Ok(&scan));
+        assert!(health.is_healthy());
+        assert_eq!(health.files_examined, 4);
+        assert_eq!(health.last_scan_ms, 7);
+        assert_eq!(health.total_failures, 2);
+        // The last error stays visible after recovering
+        assert!(health.last_error.is_some());
+        assert!(!health.is_stale(Utc::now()));
+        assert!(health.is_stale(Utc::now() + chrono::Duration::seconds(60)));
+
+        let path = WatcherHealth::path_for_config(temp.path().join("harbor.downloads.yaml"));
+        assert_eq!(path, temp.path().join("harbor.downloads.health.json"));
+        assert_eq!(WatcherHealth::load(&path).unwrap(), None);
+        health.save(&path).unwrap();
+        assert_eq!(WatcherHealth::load(&path).unwrap(), Some(health));
+    }
+}
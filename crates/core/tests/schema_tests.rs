@@ -0,0 +1,39 @@
+//! Keeps the checked-in JSON Schemas in sync with the config types.
+//!
+//! Run with `HARBOR_UPDATE_SNAPSHOTS=1` to regenerate the snapshots after
+//! an intentional change to `DownloadsConfig` or `WorkspaceConfig`.
+
+use harbor_core::schema::{schema_json, SchemaKind};
+use std::path::PathBuf;
+
+fn check_snapshot(kind: SchemaKind, file: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(file);
+    let actual = schema_json(kind).unwrap() + "\n";
+
+    if std::env::var_os("HARBOR_UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_default()
+        .replace("\r\n", "\n");
+    assert!(
+        expected == actual,
+        "{} is out of date; rerun with HARBOR_UPDATE_SNAPSHOTS=1",
+        path.display()
+    );
+}
+
+#[test]
+fn downloads_schema_matches_snapshot() {
+    check_snapshot(SchemaKind::Downloads, "downloads.schema.json");
+}
+
+#[test]
+fn workspace_schema_matches_snapshot() {
+    check_snapshot(SchemaKind::Workspace, "workspace.schema.json");
+}
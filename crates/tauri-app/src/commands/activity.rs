@@ -1,8 +1,9 @@
 use crate::state::AppState;
-use regex::Regex;
+use harbor_core::activity::ActivityRecord;
+use harbor_core::activity_store::{
+    parse_since, parse_until, ActivityQuery, ActivityStats, ActivityStore, ExportFormat,
+};
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::io::{BufRead, BufReader};
 use tauri::State;
 
 /// Activity log entry for the frontend
@@ -16,19 +17,12 @@ pub struct ActivityLogDto {
     pub source_path: String,
     pub dest_path: String,
     pub rule_name: String,
+    /// Id of the rule at the time of the move; empty for old entries
+    pub rule_id: String,
     pub status: String,
     pub symlink_info: Option<String>,
 }
 
-/// Activity statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActivityStats {
-    pub total_files_moved: usize,
-    pub files_moved_today: usize,
-    pub files_moved_this_week: usize,
-    pub most_active_rule: Option<String>,
-}
-
 /// Paginated response for activity logs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActivityLogsResponse {
@@ -37,30 +31,8 @@ pub struct ActivityLogsResponse {
     pub has_more: bool,
 }
 
-fn parse_log_line(line: &str, id: usize) -> Option<ActivityLogDto> {
-    // Log format: "source_path -> dest_path (rule_name) symlink_info"
-    // Example: "C:\Downloads\file.jpg -> C:\Images\file.jpg (Images) Symlink created"
-
-    let arrow_pattern = Regex::new(r"^(.+?) -> (.+) \(([^)]+)\)\s*(.*)$").ok()?;
-
-    let caps = arrow_pattern.captures(line)?;
-
-    let source_path = caps.get(1)?.as_str().to_string();
-    let dest_path = caps.get(2)?.as_str().to_string();
-    let rule_name = caps.get(3)?.as_str().to_string();
-    let symlink_info = caps.get(4).map(|m| m.as_str().trim().to_string());
-    let symlink_info = if symlink_info.as_ref().map(|s| s.is_empty()).unwrap_or(true) {
-        None
-    } else {
-        symlink_info
-    };
-
-    // Extract filename from dest_path
-    let filename = std::path::Path::new(&dest_path)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+fn to_dto(record: ActivityRecord, id: usize) -> ActivityLogDto {
+    let filename = record.file_name();
 
     // Derive icon from extension
     let ext = std::path::Path::new(&filename)
@@ -71,18 +43,19 @@ fn parse_log_line(line: &str, id: usize) -> Option<ActivityLogDto> {
 
     let (icon, icon_color) = derive_icon_and_color(&ext);
 
-    Some(ActivityLogDto {
+    ActivityLogDto {
         id: id.to_string(),
-        timestamp: "".to_string(), // We don't have timestamps in current log format
+        timestamp: record.timestamp.map(|t| t.to_rfc3339()).unwrap_or_default(),
         filename,
         icon,
         icon_color,
-        source_path,
-        dest_path,
-        rule_name,
-        status: "success".to_string(),
-        symlink_info,
-    })
+        source_path: record.source,
+        dest_path: record.dest.unwrap_or_default(),
+        rule_name: record.rule.unwrap_or_default(),
+        rule_id: record.rule_id.unwrap_or_default(),
+        status: record.outcome.as_str().to_string(),
+        symlink_info: record.symlink_info,
+    }
 }
 
 fn derive_icon_and_color(ext: &str) -> (String, String) {
@@ -113,53 +86,62 @@ fn derive_icon_and_color(ext: &str) -> (String, String) {
     }
 }
 
-fn read_log_entries(reader: impl BufRead) -> Vec<ActivityLogDto> {
-    reader
-        .lines()
-        .map_while(Result::ok)
-        .filter(|line| {
-            !line.trim().is_empty()
-                && !line.starts_with("Recent Moves")
-                && !line.starts_with("---")
-                && !line.starts_with("Startup:")
-        })
-        .enumerate()
-        .filter_map(|(idx, line)| parse_log_line(&line, idx))
-        .collect()
-}
-
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_activity_logs(
     state: State<'_, AppState>,
     limit: Option<usize>,
     offset: Option<usize>,
+    rule: Option<String>,
+    rule_id: Option<String>,
+    extension: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    outcome: Option<String>,
+    search: Option<String>,
 ) -> Result<ActivityLogsResponse, String> {
-    let log_path = state.recent_log_path();
     let limit = limit.unwrap_or(50);
     let offset = offset.unwrap_or(0);
 
-    if !log_path.exists() {
-        return Ok(ActivityLogsResponse {
-            logs: vec![],
-            total: 0,
-            has_more: false,
-        });
-    }
-
-    let file = fs::File::open(&log_path).map_err(|e| format!("Failed to open log file: {}", e))?;
-    let reader = BufReader::new(file);
-
-    // Read all lines and parse them
-    let mut all_logs = read_log_entries(reader);
+    let query = ActivityQuery {
+        rule,
+        rule_id,
+        extension,
+        since: since
+            .as_deref()
+            .map(parse_since)
+            .transpose()
+            .map_err(|e| e.to_string())?,
+        until: until
+            .as_deref()
+            .map(parse_until)
+            .transpose()
+            .map_err(|e| e.to_string())?,
+        outcome: outcome
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|e: anyhow::Error| e.to_string())?,
+        text: search,
+        offset,
+        limit: Some(limit),
+    };
 
-    // Reverse to show most recent first
-    all_logs.reverse();
+    // Most recent first, filtered and counted by the index
+    let page = ActivityStore::open(state.activity_log())
+        .and_then(|store| store.query(&query))
+        .map_err(|e| format!("Failed to read activity log: {}", e))?;
 
-    let total = all_logs.len();
+    let total = page.total;
     let has_more = offset + limit < total;
 
-    // Apply pagination
-    let logs: Vec<ActivityLogDto> = all_logs.into_iter().skip(offset).take(limit).collect();
+    // Ids count from the oldest match so they stay stable as new moves arrive
+    let logs: Vec<ActivityLogDto> = page
+        .records
+        .into_iter()
+        .enumerate()
+        .map(|(i, record)| to_dto(record, total - offset - i - 1))
+        .collect();
 
     Ok(ActivityLogsResponse {
         logs,
@@ -168,54 +150,57 @@ pub async fn get_activity_logs(
     })
 }
 
+/// Statistics with a per-day series over the last `days` days (default 30)
 #[tauri::command]
-pub async fn get_activity_stats(state: State<'_, AppState>) -> Result<ActivityStats, String> {
-    let log_path = state.recent_log_path();
-
-    if !log_path.exists() {
-        return Ok(ActivityStats {
-            total_files_moved: 0,
-            files_moved_today: 0,
-            files_moved_this_week: 0,
-            most_active_rule: None,
-        });
-    }
-
-    let file = fs::File::open(&log_path).map_err(|e| format!("Failed to open log file: {}", e))?;
-    let reader = BufReader::new(file);
-
-    let logs = read_log_entries(reader);
-    let total = logs.len();
-    let mut rule_counts: std::collections::HashMap<String, usize> =
-        std::collections::HashMap::new();
-
-    for log in logs {
-        *rule_counts.entry(log.rule_name).or_insert(0) += 1;
-    }
+pub async fn get_activity_stats(
+    state: State<'_, AppState>,
+    days: Option<u32>,
+) -> Result<ActivityStats, String> {
+    ActivityStore::open(state.activity_log())
+        .and_then(|store| store.stats(days.unwrap_or(30)))
+        .map_err(|e| format!("Failed to read activity log: {}", e))
+}
 
-    let most_active_rule = rule_counts
-        .into_iter()
-        .max_by_key(|(_, count)| *count)
-        .map(|(name, _)| name);
+/// Write the activity history matching the filters to `path` as CSV or
+/// JSON, oldest first. Returns the number of records written.
+#[tauri::command]
+pub async fn export_activity(
+    state: State<'_, AppState>,
+    path: String,
+    format: String,
+    rule: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+) -> Result<usize, String> {
+    let format: ExportFormat = format.parse().map_err(|e: anyhow::Error| e.to_string())?;
+    let query = ActivityQuery {
+        rule,
+        since: since
+            .as_deref()
+            .map(parse_since)
+            .transpose()
+            .map_err(|e| e.to_string())?,
+        until: until
+            .as_deref()
+            .map(parse_until)
+            .transpose()
+            .map_err(|e| e.to_string())?,
+        ..Default::default()
+    };
 
-    Ok(ActivityStats {
-        total_files_moved: total,
-        files_moved_today: total, // Simplified - we don't have timestamps in current format
-        files_moved_this_week: total,
-        most_active_rule,
-    })
+    let file =
+        std::fs::File::create(&path).map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    ActivityStore::open(state.activity_log())
+        .and_then(|store| store.export(&query, format, std::io::BufWriter::new(file)))
+        .map_err(|e| format!("Failed to export activity: {}", e))
 }
 
 #[tauri::command]
 pub async fn clear_activity_logs(state: State<'_, AppState>) -> Result<(), String> {
-    let log_path = state.recent_log_path();
-
-    if log_path.exists() {
-        fs::write(&log_path, "Recent Moves Log\n----------------\n")
-            .map_err(|e| format!("Failed to clear log file: {}", e))?;
-    }
-
-    Ok(())
+    state
+        .activity_log()
+        .clear()
+        .map_err(|e| format!("Failed to clear log file: {}", e))
 }
 
 #[cfg(test)]
@@ -243,31 +228,26 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_log_line() {
+    fn test_to_dto() {
         let line = r#"C:\Source\file.txt -> C:\Dest\file.txt (Docs) Symlink created"#;
-        let dto = parse_log_line(line, 1).unwrap();
+        let record = harbor_core::activity::parse_line(line).unwrap();
+        let dto = to_dto(record, 1);
         assert_eq!(dto.id, "1");
         assert_eq!(dto.source_path, r"C:\Source\file.txt");
         assert_eq!(dto.dest_path, r"C:\Dest\file.txt");
         assert_eq!(dto.rule_name, "Docs");
+        assert_eq!(dto.rule_id, "");
         assert_eq!(dto.symlink_info.as_deref(), Some("Symlink created"));
         assert_eq!(dto.filename, "file.txt");
         assert_eq!(dto.icon, "description");
         assert_eq!(dto.icon_color, "blue");
-
-        // Test without symlink info
-        let line2 = r#"C:\src\img.png -> C:\dst\img.png (Images) "#;
-        let dto2 = parse_log_line(line2, 2).unwrap();
-        assert_eq!(dto2.symlink_info, None);
-    }
-
-    #[test]
-    fn test_read_log_entries() {
-        let data = "Recent Moves Log\n----------------\n\nC:\\src\\a.txt -> C:\\dst\\a.txt (RuleA)\nC:\\src\\b.txt -> C:\\dst\\b.txt (RuleB)";
-        let reader = std::io::Cursor::new(data);
-        let logs = read_log_entries(reader);
-        assert_eq!(logs.len(), 2);
-        assert_eq!(logs[0].rule_name, "RuleA");
-        assert_eq!(logs[1].rule_name, "RuleB");
+        assert_eq!(dto.status, "success");
+        assert_eq!(dto.timestamp, "");
+
+        // Tray lines carry a timestamp
+        let line2 = "[2024-01-01 12:00:00] Moved img.png -> /dst/img.png (Rule: Images)";
+        let dto2 = to_dto(harbor_core::activity::parse_line(line2).unwrap(), 2);
+        assert_eq!(dto2.rule_name, "Images");
+        assert!(!dto2.timestamp.is_empty());
     }
 }
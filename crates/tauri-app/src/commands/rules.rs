@@ -1,10 +1,14 @@
-use crate::commands::settings::{internal_start_service, internal_stop_service};
 use crate::state::AppState;
-use harbor_core::downloads::DownloadsConfig;
+use harbor_core::downloads::{explain, DownloadsConfig, RuleVerdict};
+use harbor_core::rule_stats::{RuleStats, RuleStatsMap};
+use harbor_core::rules::{
+    analyze_rules, export_pack, import_pack, new_rule_id, Collision, ImportOptions, ImportReport,
+    RulePack, RuleWarning,
+};
 use harbor_core::types::Rule;
+use std::path::Path;
 
 use serde::{Deserialize, Serialize};
-use std::fs;
 use tauri::State;
 
 /// Frontend-facing rule representation
@@ -28,10 +32,21 @@ pub struct RuleDto {
     pub create_symlink: bool,
     /// Whether the rule is enabled
     pub enabled: bool,
+    /// Whether moves by this rule show a desktop notification
+    pub notify: bool,
     /// Icon name (derived from first extension)
     pub icon: String,
     /// Icon color
     pub icon_color: String,
+    /// How often the rule matched and when it last fired or failed
+    pub stats: RuleStats,
+}
+
+impl RuleDto {
+    fn with_stats(mut self, stats: &RuleStatsMap) -> Self {
+        self.stats = stats.get(&self.id).cloned().unwrap_or_default();
+        self
+    }
 }
 
 impl From<&Rule> for RuleDto {
@@ -40,7 +55,7 @@ impl From<&Rule> for RuleDto {
         let icon_color = derive_icon_color(rule.extensions.as_ref());
 
         RuleDto {
-            id: rule.name.clone(),
+            id: rule.id.clone(),
             name: rule.name.clone(),
             extensions: rule
                 .extensions
@@ -55,8 +70,10 @@ impl From<&Rule> for RuleDto {
             destination: rule.target_dir.clone(),
             create_symlink: rule.create_symlink.unwrap_or(false),
             enabled: rule.enabled.unwrap_or(true),
+            notify: rule.notify.unwrap_or(false),
             icon,
             icon_color,
+            stats: RuleStats::default(),
         }
     }
 }
@@ -108,33 +125,19 @@ fn derive_icon_color(extensions: Option<&Vec<String>>) -> String {
     }
 }
 
-fn save_config(state: &AppState, config: &DownloadsConfig) -> Result<(), String> {
-    let yaml =
-        serde_yaml::to_string(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
-    fs::write(&state.config_path, yaml).map_err(|e| format!("Failed to write config: {}", e))?;
-    Ok(())
-}
-
-fn restart_service_if_running(state: &AppState) -> Result<(), String> {
-    let flag_guard = state.watcher_flag.lock().map_err(|e| e.to_string())?;
-    let is_running = flag_guard.is_some();
-    drop(flag_guard);
-
-    if is_running {
-        internal_stop_service(state)?;
-        internal_start_service(state)?;
-    }
-    Ok(())
-}
-
 #[tauri::command]
 pub async fn get_rules(state: State<'_, AppState>) -> Result<Vec<RuleDto>, String> {
     impl_get_rules(&state).await
 }
 
 pub async fn impl_get_rules(state: &AppState) -> Result<Vec<RuleDto>, String> {
+    let stats = state.rule_stats().load().unwrap_or_default();
     let config = state.config.read().map_err(|e| e.to_string())?;
-    Ok(config.rules.iter().map(RuleDto::from).collect())
+    Ok(config
+        .rules
+        .iter()
+        .map(|rule| RuleDto::from(rule).with_stats(&stats))
+        .collect())
 }
 
 #[tauri::command]
@@ -178,9 +181,10 @@ pub async fn impl_create_rule(
 ) -> Result<RuleDto, String> {
     let new_rule = {
         let mut config = state.config.write().map_err(|e| e.to_string())?;
+        let mut edited = config.clone();
 
         // Check if rule with this name already exists
-        if config.rules.iter().any(|r| r.name == name) {
+        if edited.rules.iter().any(|r| r.name == name) {
             return Err(format!("Rule with name '{}' already exists", name));
         }
 
@@ -192,6 +196,7 @@ pub async fn impl_create_rule(
             .collect();
 
         let rule = Rule {
+            id: new_rule_id(),
             name: name.clone(),
             extensions: if extensions.is_empty() {
                 None
@@ -204,15 +209,15 @@ pub async fn impl_create_rule(
             target_dir: destination,
             create_symlink,
             enabled,
+            notify: None,
         };
 
-        config.rules.push(rule.clone());
-        save_config(state, &config)?;
+        edited.rules.push(rule.clone());
+        state.save_config(&edited)?;
+        *config = edited;
         rule
     };
 
-    restart_service_if_running(state)?;
-
     Ok(RuleDto::from(&new_rule))
 }
 
@@ -260,11 +265,22 @@ pub async fn impl_update_rule(
 ) -> Result<RuleDto, String> {
     let updated = {
         let mut config = state.config.write().map_err(|e| e.to_string())?;
+        let mut edited = config.clone();
+
+        if let Some(new_name) = &name {
+            if edited
+                .rules
+                .iter()
+                .any(|r| &r.name == new_name && r.id != id)
+            {
+                return Err(format!("Rule with name '{}' already exists", new_name));
+            }
+        }
 
-        let rule = config
+        let rule = edited
             .rules
             .iter_mut()
-            .find(|r| r.name == id)
+            .find(|r| r.id == id)
             .ok_or_else(|| format!("Rule '{}' not found", id))?;
 
         if let Some(new_name) = name {
@@ -297,103 +313,254 @@ pub async fn impl_update_rule(
             rule.enabled = Some(en);
         }
 
-        let updated = RuleDto::from(&*rule);
-        save_config(state, &config)?;
+        let stats = state.rule_stats().load().unwrap_or_default();
+        let updated = RuleDto::from(&*rule).with_stats(&stats);
+        state.save_config(&edited)?;
+        *config = edited;
         updated
     };
 
-    restart_service_if_running(state)?;
-
     Ok(updated)
 }
 
 #[tauri::command]
-pub async fn delete_rule(state: State<'_, AppState>, rule_name: String) -> Result<(), String> {
-    impl_delete_rule(&state, rule_name).await
+pub async fn delete_rule(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    impl_delete_rule(&state, id).await
 }
 
-pub async fn impl_delete_rule(state: &AppState, rule_name: String) -> Result<(), String> {
+pub async fn impl_delete_rule(state: &AppState, id: String) -> Result<(), String> {
     {
         let mut config = state.config.write().map_err(|e| e.to_string())?;
+        let mut edited = config.clone();
 
-        let original_len = config.rules.len();
-        config.rules.retain(|r| r.name != rule_name);
+        let original_len = edited.rules.len();
+        edited.rules.retain(|r| r.id != id);
 
-        if config.rules.len() == original_len {
-            return Err(format!("Rule '{}' not found", rule_name));
+        if edited.rules.len() == original_len {
+            return Err(format!("Rule '{}' not found", id));
         }
 
-        save_config(state, &config)?;
+        state.save_config(&edited)?;
+        *config = edited;
     }
-    restart_service_if_running(state)?;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn toggle_rule(
     state: State<'_, AppState>,
-    rule_name: String,
+    id: String,
     enabled: bool,
 ) -> Result<(), String> {
-    impl_toggle_rule(&state, rule_name, enabled).await
+    impl_toggle_rule(&state, id, enabled).await
 }
 
-pub async fn impl_toggle_rule(
-    state: &AppState,
-    rule_name: String,
-    enabled: bool,
-) -> Result<(), String> {
+pub async fn impl_toggle_rule(state: &AppState, id: String, enabled: bool) -> Result<(), String> {
     {
         let mut config = state.config.write().map_err(|e| e.to_string())?;
+        let mut edited = config.clone();
 
-        let rule = config
+        let rule = edited
             .rules
             .iter_mut()
-            .find(|r| r.name == rule_name)
-            .ok_or_else(|| format!("Rule '{}' not found", rule_name))?;
+            .find(|r| r.id == id)
+            .ok_or_else(|| format!("Rule '{}' not found", id))?;
 
         rule.enabled = Some(enabled);
-        save_config(state, &config)?;
+        state.save_config(&edited)?;
+        *config = edited;
     }
-    restart_service_if_running(state)?;
+    Ok(())
+}
+
+/// Opts a rule in or out of desktop notifications
+#[tauri::command]
+pub async fn set_rule_notify(
+    state: State<'_, AppState>,
+    id: String,
+    notify: bool,
+) -> Result<(), String> {
+    impl_set_rule_notify(&state, id, notify).await
+}
 
+pub async fn impl_set_rule_notify(
+    state: &AppState,
+    id: String,
+    notify: bool,
+) -> Result<(), String> {
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    let mut edited = config.clone();
+    let rule = edited
+        .rules
+        .iter_mut()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("Rule '{}' not found", id))?;
+    rule.notify = Some(notify);
+    state.save_config(&edited)?;
+    *config = edited;
     Ok(())
 }
 
+/// A problem with the rule order, e.g. a rule that can never fire
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleWarningDto {
+    pub message: String,
+    pub warning: RuleWarning,
+}
+
+/// Saves the new order and returns warnings about it
 #[tauri::command]
 pub async fn reorder_rules(
     state: State<'_, AppState>,
-    rule_names: Vec<String>,
-) -> Result<(), String> {
-    impl_reorder_rules(&state, rule_names).await
+    ids: Vec<String>,
+) -> Result<Vec<RuleWarningDto>, String> {
+    impl_reorder_rules(&state, ids).await
 }
 
-pub async fn impl_reorder_rules(state: &AppState, rule_names: Vec<String>) -> Result<(), String> {
-    {
-        let mut config = state.config.write().map_err(|e| e.to_string())?;
+pub async fn impl_reorder_rules(
+    state: &AppState,
+    ids: Vec<String>,
+) -> Result<Vec<RuleWarningDto>, String> {
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
 
-        // Reorder rules based on the provided order
-        let mut new_rules: Vec<Rule> = Vec::with_capacity(rule_names.len());
+    // Reorder rules based on the provided order
+    let mut new_rules: Vec<Rule> = Vec::with_capacity(ids.len());
 
-        for name in &rule_names {
-            if let Some(rule) = config.rules.iter().find(|r| &r.name == name).cloned() {
-                new_rules.push(rule);
-            }
+    for id in &ids {
+        if let Some(rule) = config.rules.iter().find(|r| &r.id == id).cloned() {
+            new_rules.push(rule);
         }
+    }
 
-        // Add any rules that weren't in the provided list (shouldn't happen, but safety first)
-        for rule in &config.rules {
-            if !rule_names.contains(&rule.name) {
-                new_rules.push(rule.clone());
-            }
+    // Add any rules that weren't in the provided list (shouldn't happen, but safety first)
+    for rule in &config.rules {
+        if !ids.contains(&rule.id) {
+            new_rules.push(rule.clone());
         }
+    }
+
+    let mut edited = config.clone();
+    edited.rules = new_rules;
+    state.save_config(&edited)?;
+    *config = edited;
+    Ok(rule_warnings(&config.rules))
+}
+
+fn rule_warnings(rules: &[Rule]) -> Vec<RuleWarningDto> {
+    analyze_rules(rules)
+        .into_iter()
+        .map(|w| RuleWarningDto {
+            message: w.to_string(),
+            warning: w,
+        })
+        .collect()
+}
+
+/// Outcome of importing a rule pack
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRulesDto {
+    #[serde(flatten)]
+    pub report: ImportReport,
+    /// Problems with the merged rule list
+    pub warnings: Vec<RuleWarningDto>,
+}
+
+/// Writes the named rules (all if empty) to a pack file
+#[tauri::command]
+pub async fn export_rules(
+    state: State<'_, AppState>,
+    names: Vec<String>,
+    path: String,
+) -> Result<(), String> {
+    impl_export_rules(&state, names, path).await
+}
 
-        config.rules = new_rules;
-        save_config(state, &config)?;
+pub async fn impl_export_rules(
+    state: &AppState,
+    names: Vec<String>,
+    path: String,
+) -> Result<(), String> {
+    let config = state.config.read().map_err(|e| e.to_string())?.clone();
+    let yaml = export_pack(&config, &names)
+        .and_then(|pack| pack.to_yaml())
+        .map_err(|e| format!("{:#}", e))?;
+    std::fs::write(&path, yaml).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+#[tauri::command]
+pub async fn import_rules(
+    state: State<'_, AppState>,
+    path: String,
+    on_collision: Collision,
+    rebase: bool,
+) -> Result<ImportRulesDto, String> {
+    impl_import_rules(&state, path, on_collision, rebase).await
+}
+
+pub async fn impl_import_rules(
+    state: &AppState,
+    path: String,
+    on_collision: Collision,
+    rebase: bool,
+) -> Result<ImportRulesDto, String> {
+    let pack = RulePack::load(&path).map_err(|e| format!("{:#}", e))?;
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    let mut merged = config.clone();
+    let opts = ImportOptions {
+        on_collision,
+        rebase,
+    };
+    let report = import_pack(&mut merged, pack, opts).map_err(|e| format!("{:#}", e))?;
+    state.save_config(&merged)?;
+    *config = merged;
+    Ok(ImportRulesDto {
+        report,
+        warnings: rule_warnings(&config.rules),
+    })
+}
+
+/// Rule profiles and which one is active
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfilesDto {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+impl From<&DownloadsConfig> for ProfilesDto {
+    fn from(config: &DownloadsConfig) -> Self {
+        ProfilesDto {
+            active: config.active_profile().to_string(),
+            profiles: config.profile_names(),
+        }
     }
-    restart_service_if_running(state)?;
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn get_profiles(state: State<'_, AppState>) -> Result<ProfilesDto, String> {
+    let config = state.config.read().map_err(|e| e.to_string())?;
+    Ok(ProfilesDto::from(&*config))
+}
+
+/// Swaps in another profile's rules; the running watcher uses them on its
+/// next pass
+#[tauri::command]
+pub async fn set_active_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<ProfilesDto, String> {
+    impl_set_active_profile(&state, name).await
+}
+
+pub async fn impl_set_active_profile(
+    state: &AppState,
+    name: String,
+) -> Result<ProfilesDto, String> {
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    let mut switched = config.clone();
+    switched.use_profile(&name).map_err(|e| e.to_string())?;
+    state.save_config(&switched)?;
+    *config = switched;
+    Ok(ProfilesDto::from(&*config))
 }
 
 #[tauri::command]
@@ -402,6 +569,54 @@ pub async fn get_download_dir(state: State<'_, AppState>) -> Result<String, Stri
     Ok(config.download_dir.clone())
 }
 
+/// One rule's verdict on a file
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleTraceDto {
+    pub rule_name: String,
+    pub matched: bool,
+    /// Human-readable reason, e.g. "'.pdf' is not one of png, jpg"
+    pub reason: String,
+    pub verdict: RuleVerdict,
+}
+
+/// Why a file would or would not be organized
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainDto {
+    pub path: String,
+    /// File-level reason the watcher skips it (partial, too young, ...)
+    pub skip: Option<String>,
+    /// First matching enabled rule
+    pub winner: Option<String>,
+    pub would_move: bool,
+    pub rules: Vec<RuleTraceDto>,
+}
+
+#[tauri::command]
+pub async fn explain_file(state: State<'_, AppState>, path: String) -> Result<ExplainDto, String> {
+    impl_explain_file(&state, path).await
+}
+
+pub async fn impl_explain_file(state: &AppState, path: String) -> Result<ExplainDto, String> {
+    let config = state.config.read().map_err(|e| e.to_string())?.clone();
+    let ex = explain(&config, Path::new(&path)).map_err(|e| format!("{:#}", e))?;
+    Ok(ExplainDto {
+        path,
+        skip: ex.skip.as_ref().map(|s| s.to_string()),
+        winner: ex.winner.clone(),
+        would_move: ex.would_move(),
+        rules: ex
+            .rules
+            .into_iter()
+            .map(|t| RuleTraceDto {
+                rule_name: t.rule,
+                matched: t.verdict == RuleVerdict::Matched,
+                reason: t.verdict.to_string(),
+                verdict: t.verdict,
+            })
+            .collect(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -440,6 +655,7 @@ mod tests {
         assert_eq!(derive_icon_color(None), "slate");
     }
 
+    use harbor_core::downloads::DownloadsConfig;
     use tempfile::tempdir;
 
     fn create_test_state() -> (AppState, tempfile::TempDir) {
@@ -453,6 +669,11 @@ mod tests {
             service_enabled: Some(true),
             check_updates: Some(true),
             last_notified_version: None,
+            activity_retention: None,
+            notifications: None,
+            paused_until: None,
+            active_profile: None,
+            profiles: None,
         };
         let yaml = serde_yaml::to_string(&config).unwrap();
         std::fs::write(&cfg_path, yaml).unwrap();
@@ -506,7 +727,7 @@ mod tests {
     async fn test_update_rule() {
         let (state, _tmp) = create_test_state();
 
-        let _ = impl_create_rule(
+        let created = impl_create_rule(
             &state,
             "Rule1".to_string(),
             vec!["txt".to_string()],
@@ -522,7 +743,7 @@ mod tests {
 
         let updated = impl_update_rule(
             &state,
-            "Rule1".to_string(),
+            created.id.clone(),
             Some("Rule1_Updated".to_string()),
             Some(vec!["md".to_string()]),
             Some("NewTarget".to_string()),
@@ -537,6 +758,8 @@ mod tests {
         assert!(updated.is_ok());
         let u = updated.unwrap();
         assert_eq!(u.name, "Rule1_Updated");
+        // Renaming keeps the id
+        assert_eq!(u.id, created.id);
         assert_eq!(u.destination, "NewTarget");
         assert!(u.extensions.contains(&".md".to_string()));
 
@@ -544,13 +767,41 @@ mod tests {
         let rules = impl_get_rules(&state).await.unwrap();
         assert_eq!(rules.len(), 1);
         assert_eq!(rules[0].name, "Rule1_Updated");
+        let other = impl_create_rule(
+            &state,
+            "Other".to_string(),
+            vec![],
+            "Elsewhere".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_ne!(other.id, created.id);
+        let clash = impl_update_rule(
+            &state,
+            other.id,
+            Some("Rule1_Updated".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+        assert!(clash.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_rule() {
         let (state, _tmp) = create_test_state();
 
-        impl_create_rule(
+        let created = impl_create_rule(
             &state,
             "To Delete".to_string(),
             vec![],
@@ -564,7 +815,11 @@ mod tests {
         .await
         .unwrap();
 
-        let res = impl_delete_rule(&state, "To Delete".to_string()).await;
+        // Rules are addressed by id, not name
+        assert!(impl_delete_rule(&state, "To Delete".to_string())
+            .await
+            .is_err());
+        let res = impl_delete_rule(&state, created.id).await;
         assert!(res.is_ok());
 
         let rules = impl_get_rules(&state).await.unwrap();
@@ -579,7 +834,7 @@ mod tests {
     async fn test_toggle_rule() {
         let (state, _tmp) = create_test_state();
 
-        impl_create_rule(
+        let created = impl_create_rule(
             &state,
             "ToggleMe".to_string(),
             vec![],
@@ -593,12 +848,49 @@ mod tests {
         .await
         .unwrap();
 
-        impl_toggle_rule(&state, "ToggleMe".to_string(), false)
+        impl_toggle_rule(&state, created.id.clone(), false)
+            .await
+            .unwrap();
+        impl_set_rule_notify(&state, created.id, true)
             .await
             .unwrap();
 
         let rules = impl_get_rules(&state).await.unwrap();
         assert!(!rules[0].enabled);
+        assert!(rules[0].notify);
+        assert!(impl_set_rule_notify(&state, "missing".into(), true)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_save_leaves_config_alone() {
+        let (state, _tmp) = create_test_state();
+        let created = impl_create_rule(
+            &state,
+            "Keep".to_string(),
+            vec!["pdf".to_string()],
+            "".to_string(),
+            None,
+            None,
+            None,
+            None,
+            Some(true),
+        )
+        .await
+        .unwrap();
+
+        // Someone else edits the file; the store refuses to overwrite it
+        let yaml = std::fs::read_to_string(&state.config_path).unwrap();
+        std::fs::write(&state.config_path, format!("{}# edited\n", yaml)).unwrap();
+
+        assert!(impl_toggle_rule(&state, created.id.clone(), false)
+            .await
+            .is_err());
+        assert!(impl_delete_rule(&state, created.id.clone()).await.is_err());
+        let rules = impl_get_rules(&state).await.unwrap();
+        assert_eq!(rules.len(), 1);
+        assert!(rules[0].enabled);
     }
 
     #[tokio::test]
@@ -645,12 +937,143 @@ mod tests {
         .await
         .unwrap();
 
-        let order = vec!["C".to_string(), "A".to_string(), "B".to_string()];
-        impl_reorder_rules(&state, order).await.unwrap();
+        let ids: Vec<String> = impl_get_rules(&state)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        let order = vec![ids[2].clone(), ids[0].clone(), ids[1].clone()];
+        let warnings = impl_reorder_rules(&state, order).await.unwrap();
 
         let rules = impl_get_rules(&state).await.unwrap();
         assert_eq!(rules[0].name, "C");
         assert_eq!(rules[1].name, "A");
         assert_eq!(rules[2].name, "B");
+        // None of the rules has criteria, so C now takes every file
+        let shadowed: Vec<&RuleWarning> = warnings.iter().map(|w| &w.warning).collect();
+        assert!(shadowed.contains(&&RuleWarning::Shadowed {
+            rule: "A".into(),
+            by: "C".into()
+        }));
+        assert!(warnings[0].message.contains("never fires"));
+    }
+
+    #[tokio::test]
+    async fn test_export_import_rules() {
+        let (state, tmp) = create_test_state();
+        for (name, ext) in [("Images", "png"), ("Docs", "pdf")] {
+            impl_create_rule(
+                &state,
+                name.into(),
+                vec![ext.into()],
+                format!("/target/{}", name),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        let pack = tmp.path().join("pack.yaml").display().to_string();
+        impl_export_rules(&state, vec!["Docs".into()], pack.clone())
+            .await
+            .unwrap();
+
+        let result = impl_import_rules(&state, pack.clone(), Collision::Rename, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.report.renamed,
+            [("Docs".to_string(), "Docs (2)".to_string())]
+        );
+        // The copy takes the same extensions as the original, so it never fires
+        assert_eq!(result.warnings.len(), 2);
+        let rules = impl_get_rules(&state).await.unwrap();
+        assert_eq!(rules.len(), 3);
+
+        let result = impl_import_rules(&state, pack, Collision::Skip, false)
+            .await
+            .unwrap();
+        assert_eq!(result.report.skipped, ["Docs"]);
+        assert!(impl_export_rules(&state, vec!["Nope".into()], "x".into())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_active_profile() {
+        let (state, _tmp) = create_test_state();
+        impl_create_rule(
+            &state,
+            "Home".into(),
+            vec![],
+            "/home".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        state.config.write().unwrap().profiles =
+            Some([("work".to_string(), Vec::new())].into_iter().collect());
+
+        let profiles = impl_set_active_profile(&state, "work".into())
+            .await
+            .unwrap();
+        assert_eq!(profiles.active, "work");
+        assert_eq!(profiles.profiles, ["default", "work"]);
+        assert!(impl_get_rules(&state).await.unwrap().is_empty());
+        let saved = harbor_core::downloads::load_downloads_config(&state.config_path).unwrap();
+        assert_eq!(saved.active_profile(), "work");
+
+        assert!(impl_set_active_profile(&state, "nope".into())
+            .await
+            .is_err());
+        impl_set_active_profile(&state, "default".into())
+            .await
+            .unwrap();
+        assert_eq!(impl_get_rules(&state).await.unwrap()[0].name, "Home");
+    }
+
+    #[tokio::test]
+    async fn test_explain_file() {
+        let (state, tmp) = create_test_state();
+        let _ = impl_create_rule(
+            &state,
+            "Images".to_string(),
+            vec!["png".to_string()],
+            "Target".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let download_dir = tmp.path().join("DL");
+        state.config.write().unwrap().download_dir = download_dir.display().to_string();
+        let file = download_dir.join("a.txt");
+        std::fs::create_dir_all(&download_dir).unwrap();
+        std::fs::write(&file, "x").unwrap();
+
+        let ex = impl_explain_file(&state, file.display().to_string())
+            .await
+            .unwrap();
+        assert!(!ex.would_move);
+        assert_eq!(ex.winner, None);
+        assert_eq!(ex.rules.len(), 1);
+        assert!(!ex.rules[0].matched);
+        assert_eq!(ex.rules[0].reason, "'.txt' is not one of png");
+
+        let missing = tmp.path().join("missing.txt");
+        assert!(impl_explain_file(&state, missing.display().to_string())
+            .await
+            .is_err());
     }
 }
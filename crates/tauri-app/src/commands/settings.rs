@@ -1,12 +1,11 @@
 use crate::state::AppState;
-use harbor_core::downloads::{load_downloads_config, organize_once, watch_polling};
+use chrono::{DateTime, Utc};
+use harbor_core::downloads::{ScanReport, WatcherThread};
+use harbor_core::notifications::NotificationSettings;
+use harbor_core::service::{ServiceState, SnoozeFor};
+use harbor_core::watcher_health::WatcherHealth;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::io::Write;
 use std::path::PathBuf;
-use std::sync::atomic::Ordering;
-use std::sync::Arc;
-use std::thread;
 use tauri::State;
 
 #[cfg(windows)]
@@ -18,132 +17,93 @@ use winreg::RegKey;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceStatus {
     pub running: bool,
+    /// Running, but skipping passes until resumed
+    pub paused: bool,
     pub uptime_seconds: Option<u64>,
+    /// Set when the config file was edited into an invalid state
+    pub config_error: Option<String>,
+    /// Last scan, last error and failure counts from the watcher's latest pass
+    pub health: Option<WatcherHealth>,
+    /// When a timed pause ends
+    pub paused_until: Option<DateTime<Utc>>,
+    /// Seconds until then, for the countdown
+    pub resumes_in_seconds: Option<u64>,
+    /// One-line description, e.g. "Paused, resumes in 14 min"
+    pub summary: String,
 }
 
-fn append_to_log(log_path: &PathBuf, actions: &[(PathBuf, PathBuf, String, Option<String>)]) {
-    if actions.is_empty() {
-        return;
-    }
-
-    if let Some(parent) = log_path.parent() {
-        let _ = fs::create_dir_all(parent);
-    }
-
-    let mut buf = String::new();
-    for (from, to, rule, symlink_info) in actions {
-        let symlink_msg = symlink_info.as_deref().unwrap_or("");
-        buf.push_str(&format!(
-            "{} -> {} ({}) {}\n",
-            from.display(),
-            to.display(),
-            rule,
-            symlink_msg
-        ));
+impl From<harbor_core::service::ServiceStatus> for ServiceStatus {
+    fn from(status: harbor_core::service::ServiceStatus) -> Self {
+        Self {
+            running: status.is_running(),
+            paused: status.state == ServiceState::Paused,
+            summary: status.summary(),
+            uptime_seconds: status.uptime_seconds,
+            config_error: status.config_error,
+            health: status.health,
+            paused_until: status.paused_until,
+            resumes_in_seconds: status.resumes_in_seconds,
+        }
     }
+}
 
-    if let Ok(mut file) = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(log_path)
-    {
-        let _ = file.write_all(buf.as_bytes());
+/// Reports files a one-off organize run could not move as an error.
+fn finish_scan(scan: ScanReport) -> Result<usize, String> {
+    match scan.failures.first() {
+        None => Ok(scan.actions.len()),
+        Some(first) => Err(format!(
+            "{} file(s) could not be moved: {}",
+            scan.failures.len(),
+            first
+        )),
     }
 }
 
 #[tauri::command]
 pub async fn get_service_status(state: State<'_, AppState>) -> Result<ServiceStatus, String> {
-    let flag_guard = state.watcher_flag.lock().map_err(|e| e.to_string())?;
-    let running = flag_guard.is_some();
-    drop(flag_guard); // Release lock early
-
-    let uptime_seconds = if running {
-        let start_time = state.service_start_time.lock().map_err(|e| e.to_string())?;
-        start_time.map(|t| t.elapsed().as_secs())
-    } else {
-        None
-    };
+    impl_get_service_status(&state).await
+}
 
-    Ok(ServiceStatus {
-        running,
-        uptime_seconds,
-    })
+pub async fn impl_get_service_status(state: &AppState) -> Result<ServiceStatus, String> {
+    Ok(state.service.status().into())
 }
 
 pub fn internal_start_service(state: &AppState) -> Result<(), String> {
-    let mut flag_guard = state.watcher_flag.lock().map_err(|e| e.to_string())?;
-
-    // If already running, do nothing
-    if let Some(flag) = flag_guard.as_ref() {
-        if flag.load(Ordering::SeqCst) {
-            return Ok(());
-        }
-    }
-
-    // Create a new flag for this new thread
-    let new_flag = Arc::new(std::sync::atomic::AtomicBool::new(true));
-    *flag_guard = Some(new_flag.clone());
-
-    let config = state.config.read().map_err(|e| e.to_string())?.clone();
-    let log_path = state.recent_log_path();
-
-    // Use the *new* flag for the thread
-    let thread_flag = new_flag.clone();
-    let handle = thread::spawn(move || {
-        let _ = watch_polling(&config, 5, &thread_flag, |actions| {
-            append_to_log(&log_path, actions);
-        });
-    });
-
-    let mut guard = state.watcher_handle.lock().map_err(|e| e.to_string())?;
-    *guard = Some(handle);
-
-    // Set start time
-    let mut time_guard = state.service_start_time.lock().map_err(|e| e.to_string())?;
-    *time_guard = Some(std::time::Instant::now());
-
-    Ok(())
+    state.service.start().map_err(|e| e.to_string())
 }
 
+/// Fails when the pass in progress outlasts [`WatcherThread::STOP_TIMEOUT`];
+/// the watcher still stops once that pass is done.
 pub fn internal_stop_service(state: &AppState) -> Result<(), String> {
-    let mut flag_guard = state.watcher_flag.lock().map_err(|e| e.to_string())?;
-
-    if let Some(flag) = flag_guard.take() {
-        // Signal the thread to stop
-        flag.store(false, Ordering::SeqCst);
+    if state.service.stop() {
+        Ok(())
+    } else {
+        Err(format!(
+            "The watcher is still finishing a move after {}s; it stops once that is done",
+            WatcherThread::STOP_TIMEOUT.as_secs()
+        ))
     }
-
-    // We don't join the thread here to avoid blocking the UI,
-    // but since we've set its specific flag to false, it WILL exit
-    // on its next loop iteration (within 5 seconds).
-
-    let mut guard = state.watcher_handle.lock().map_err(|e| e.to_string())?;
-    *guard = None;
-
-    let mut time_guard = state.service_start_time.lock().map_err(|e| e.to_string())?;
-    *time_guard = None;
-
-    Ok(())
 }
 
 pub fn persist_service_state(state: &AppState, enabled: bool) -> Result<(), String> {
-    {
-        let mut config = state.config.write().map_err(|e| e.to_string())?;
-        config.service_enabled = Some(enabled);
-    }
-    // Save to disk
-    let config = state.config.read().map_err(|e| e.to_string())?;
-    if let Ok(yaml) = serde_yaml::to_string(&*config) {
-        std::fs::write(&state.config_path, yaml)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
-    }
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    let mut edited = config.clone();
+    edited.service_enabled = Some(enabled);
+    state.save_config(&edited)?;
+    *config = edited;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn start_service(state: State<'_, AppState>) -> Result<(), String> {
-    persist_service_state(&state, true)?;
-    internal_start_service(&state)
+    impl_start_service(&state)
+}
+
+/// Turning the service on also ends a timed pause
+pub fn impl_start_service(state: &AppState) -> Result<(), String> {
+    persist_service_state(state, true)?;
+    internal_start_service(state)?;
+    impl_resume_service(state)
 }
 
 #[tauri::command]
@@ -152,16 +112,56 @@ pub async fn stop_service(state: State<'_, AppState>) -> Result<(), String> {
     internal_stop_service(&state)
 }
 
+/// Pauses the watcher for a while; it resumes on its own, even across restarts
 #[tauri::command]
-pub async fn trigger_organize_now(state: State<'_, AppState>) -> Result<usize, String> {
-    let config = state.config.read().map_err(|e| e.to_string())?.clone();
-    let log_path = state.recent_log_path();
+pub async fn snooze_service(state: State<'_, AppState>, duration: SnoozeFor) -> Result<(), String> {
+    impl_snooze_service(&state, duration)
+}
 
-    let actions = organize_once(&config).map_err(|e| format!("Organize failed: {}", e))?;
+pub fn impl_snooze_service(state: &AppState, duration: SnoozeFor) -> Result<(), String> {
+    state
+        .service
+        .snooze(duration.until_now())
+        .map_err(|e| format!("Failed to pause: {:#}", e))
+}
 
-    append_to_log(&log_path, &actions);
+#[tauri::command]
+pub async fn resume_service(state: State<'_, AppState>) -> Result<(), String> {
+    impl_resume_service(&state)
+}
 
-    Ok(actions.len())
+pub fn impl_resume_service(state: &AppState) -> Result<(), String> {
+    state
+        .service
+        .resume()
+        .map_err(|e| format!("Failed to resume: {:#}", e))
+}
+
+#[tauri::command]
+pub async fn trigger_organize_now(state: State<'_, AppState>) -> Result<usize, String> {
+    let scan = state
+        .service
+        .organize_now()
+        .map_err(|e| format!("Organize failed: {:#}", e))?;
+    finish_scan(scan)
+}
+
+/// Organizes files and folders dropped onto the window with the current rules
+#[tauri::command]
+pub async fn organize_paths(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+) -> Result<usize, String> {
+    impl_organize_paths(&state, paths).await
+}
+
+pub async fn impl_organize_paths(state: &AppState, paths: Vec<String>) -> Result<usize, String> {
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    let scan = state
+        .service
+        .organize_paths(&paths)
+        .map_err(|e| format!("Organize failed: {:#}", e))?;
+    finish_scan(scan)
 }
 
 #[tauri::command]
@@ -212,19 +212,17 @@ pub async fn set_startup_enabled(enabled: bool) -> Result<(), String> {
 
     #[cfg(not(windows))]
     {
+        let _ = enabled;
         Err("Startup configuration not supported on this platform".to_string())
     }
 }
 
 #[tauri::command]
 pub async fn reload_config(state: State<'_, AppState>) -> Result<(), String> {
-    let new_config = load_downloads_config(&state.config_path)
-        .map_err(|e| format!("Failed to reload config: {}", e))?;
-
-    let mut config = state.config.write().map_err(|e| e.to_string())?;
-    *config = new_config;
-
-    Ok(())
+    state
+        .service
+        .reload()
+        .map_err(|e| format!("Failed to reload config: {:#}", e))
 }
 
 #[tauri::command]
@@ -283,37 +281,16 @@ pub async fn get_config_path(state: State<'_, AppState>) -> Result<String, Strin
 pub async fn reset_to_defaults(state: State<'_, AppState>) -> Result<(), String> {
     let config = harbor_core::downloads::default_config();
 
-    // Save to disk
-    if let Ok(yaml) = serde_yaml::to_string(&config) {
-        std::fs::write(&state.config_path, yaml)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
-    } else {
-        return Err("Failed to serialize default config".to_string());
-    }
+    // Save to disk; resetting deliberately discards any external edits
+    state
+        .store
+        .save_force(&config)
+        .map_err(|e| format!("Failed to write config: {}", e))?;
 
-    // Update state
+    // Update state; a running watcher reads it on its next pass
     let mut state_config = state.config.write().map_err(|e| e.to_string())?;
     *state_config = config;
 
-    // Restart service if running to pick up new config
-    // We can just rely on internal_start_service logic which re-reads config if we stop/start?
-    // Actually, internal_start_service reads from state.config via read lock.
-    // But verify if the running thread picks up changes?
-    // The running thread has a CLONE of the config at start.
-    // So if service is running, we MUST restart it.
-
-    // We can't access `internal_stop_service` easily if we are holding a write lock on config?
-    // No, locks are separate. global `watcher_flag` and `watcher_handle` vs `config` RwLock.
-
-    // But we are holding `state.config` write lock right now.
-    // `internal_start_service` needs `state.config` read lock.
-    // So we must drop our write lock before calling any service functions.
-    drop(state_config);
-
-    // Stop and start service to apply changes
-    let _ = internal_stop_service(&state);
-    let _ = internal_start_service(&state);
-
     Ok(())
 }
 
@@ -331,20 +308,11 @@ pub async fn set_tutorial_completed(
     state: State<'_, AppState>,
     completed: bool,
 ) -> Result<(), String> {
-    {
-        let mut config = state.config.write().map_err(|e| e.to_string())?;
-        config.tutorial_completed = Some(completed);
-    }
-
-    // Save to disk
-    let config = state.config.read().map_err(|e| e.to_string())?;
-    if let Ok(yaml) = serde_yaml::to_string(&*config) {
-        std::fs::write(&state.config_path, yaml)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
-    } else {
-        return Err("Failed to serialize config".to_string());
-    }
-
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    let mut edited = config.clone();
+    edited.tutorial_completed = Some(completed);
+    state.save_config(&edited)?;
+    *config = edited;
     Ok(())
 }
 
@@ -357,16 +325,42 @@ pub async fn get_check_updates(state: State<'_, AppState>) -> Result<bool, Strin
 
 #[tauri::command]
 pub async fn set_check_updates(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
-    {
-        let mut config = state.config.write().map_err(|e| e.to_string())?;
-        config.check_updates = Some(enabled);
-    }
-    // Save to disk
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    let mut edited = config.clone();
+    edited.check_updates = Some(enabled);
+    state.save_config(&edited)?;
+    *config = edited;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_notification_settings(
+    state: State<'_, AppState>,
+) -> Result<NotificationSettings, String> {
     let config = state.config.read().map_err(|e| e.to_string())?;
-    if let Ok(yaml) = serde_yaml::to_string(&*config) {
-        std::fs::write(&state.config_path, yaml)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(config.notifications.clone().unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn set_notification_settings(
+    state: State<'_, AppState>,
+    settings: NotificationSettings,
+) -> Result<(), String> {
+    impl_set_notification_settings(&state, settings).await
+}
+
+pub async fn impl_set_notification_settings(
+    state: &AppState,
+    settings: NotificationSettings,
+) -> Result<(), String> {
+    if let Some(quiet) = &settings.quiet_hours {
+        quiet.parse().map_err(|e| e.to_string())?;
     }
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    let mut edited = config.clone();
+    edited.notifications = Some(settings);
+    state.save_config(&edited)?;
+    *config = edited;
     Ok(())
 }
 
@@ -383,16 +377,11 @@ pub async fn set_last_notified_version(
     state: State<'_, AppState>,
     version: String,
 ) -> Result<(), String> {
-    {
-        let mut config = state.config.write().map_err(|e| e.to_string())?;
-        config.last_notified_version = Some(version);
-    }
-    // Save to disk
-    let config = state.config.read().map_err(|e| e.to_string())?;
-    if let Ok(yaml) = serde_yaml::to_string(&*config) {
-        std::fs::write(&state.config_path, yaml)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
-    }
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    let mut edited = config.clone();
+    edited.last_notified_version = Some(version);
+    state.save_config(&edited)?;
+    *config = edited;
     Ok(())
 }
 
@@ -402,43 +391,123 @@ mod tests {
     use harbor_core::downloads::DownloadsConfig;
     use tempfile::tempdir;
 
-    #[test]
-    fn test_append_to_log_creates_and_writes() {
+    #[tokio::test]
+    async fn test_set_notification_settings() {
         let tmp = tempdir().unwrap();
-        let log_path = tmp.path().join("logs").join("recent.log");
-
-        let actions = vec![
-            (
-                PathBuf::from("src/a.txt"),
-                PathBuf::from("dst/a.txt"),
-                "Images".to_string(),
-                None,
-            ),
-            (
-                PathBuf::from("src/b.txt"),
-                PathBuf::from("dst/b.txt"),
-                "Docs".to_string(),
-                Some("Symlinked".to_string()),
-            ),
-        ];
-
-        append_to_log(&log_path, &actions);
-
-        assert!(log_path.exists());
-        let content = std::fs::read_to_string(&log_path).unwrap();
-        assert!(content.contains("src/a.txt -> dst/a.txt (Images)"));
-        assert!(content.contains("src/b.txt -> dst/b.txt (Docs) Symlinked"));
+        let cfg_path = tmp.path().join("config.yaml");
+        let state = AppState::new(cfg_path.clone(), harbor_core::downloads::default_config());
+
+        let mut settings = NotificationSettings {
+            unmatched_after_hours: Some(24),
+            quiet_hours: Some(harbor_core::notifications::QuietHours {
+                start: "22:00".into(),
+                end: "25:00".into(),
+            }),
+            ..Default::default()
+        };
+        assert!(impl_set_notification_settings(&state, settings.clone())
+            .await
+            .is_err());
+
+        settings.quiet_hours.as_mut().unwrap().end = "07:00".into();
+        impl_set_notification_settings(&state, settings.clone())
+            .await
+            .unwrap();
+        let saved = harbor_core::downloads::load_downloads_config(&cfg_path).unwrap();
+        assert_eq!(saved.notifications, Some(settings));
     }
 
-    #[test]
-    fn test_append_to_log_empty() {
+    #[tokio::test]
+    async fn test_organize_paths() {
         let tmp = tempdir().unwrap();
-        let log_path = tmp.path().join("empty.log");
-        let actions = vec![];
+        let cfg_path = tmp.path().join("config.yaml");
+        let dropped = tmp.path().join("report.pdf");
+        std::fs::write(&dropped, "x").unwrap();
+        let target = tmp.path().join("Docs");
+
+        let mut cfg = harbor_core::downloads::default_config();
+        cfg.min_age_secs = Some(0);
+        cfg.rules.retain(|r| r.name == "Documents");
+        cfg.rules[0].target_dir = target.display().to_string();
+        let state = AppState::new(cfg_path, cfg);
+
+        let moved = impl_organize_paths(&state, vec![dropped.display().to_string()])
+            .await
+            .unwrap();
+        assert_eq!(moved, 1);
+        assert!(target.join("report.pdf").exists());
+        let records = state.activity_log().read_all().unwrap();
+        assert_eq!(records[0].rule.as_deref(), Some("Documents"));
+        let rules = crate::commands::rules::impl_get_rules(&state)
+            .await
+            .unwrap();
+        assert_eq!(rules[0].stats.matches, 1);
+        assert_eq!(rules[0].stats.bytes_moved, 1);
+        assert!(rules[0].stats.last_matched.is_some());
+
+        assert!(
+            impl_organize_paths(&state, vec![dropped.display().to_string()])
+                .await
+                .is_err()
+        );
+    }
 
-        append_to_log(&log_path, &actions);
+    #[tokio::test]
+    async fn test_service_status_reports_health() {
+        let tmp = tempdir().unwrap();
+        let cfg_path = tmp.path().join("config.yaml");
+        let mut cfg = harbor_core::downloads::default_config();
+        cfg.download_dir = tmp.path().join("missing").display().to_string();
+        let state = AppState::new(cfg_path.clone(), cfg);
+        assert!(impl_get_service_status(&state)
+            .await
+            .unwrap()
+            .health
+            .is_none());
+
+        internal_start_service(&state).unwrap();
+        let mut health = None;
+        for _ in 0..50 {
+            health = impl_get_service_status(&state).await.unwrap().health;
+            if health.is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        internal_stop_service(&state).unwrap();
 
-        assert!(!log_path.exists());
+        let health = health.expect("watcher reported no pass");
+        assert_eq!(health.consecutive_failures, 1);
+        assert!(health.degraded.unwrap().contains("is missing"));
+        assert!(WatcherHealth::path_for_config(&cfg_path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_stop_service_joins_watcher() {
+        let tmp = tempdir().unwrap();
+        let mut cfg = harbor_core::downloads::default_config();
+        cfg.download_dir = tmp.path().display().to_string();
+        let state = AppState::new(tmp.path().join("config.yaml"), cfg);
+
+        for _ in 0..2 {
+            internal_start_service(&state).unwrap();
+            assert!(impl_get_service_status(&state).await.unwrap().running);
+            state.service.pause().unwrap();
+            let status = impl_get_service_status(&state).await.unwrap();
+            assert!(status.running && status.paused);
+
+            impl_start_service(&state).unwrap();
+            assert!(!impl_get_service_status(&state).await.unwrap().paused);
+            impl_snooze_service(&state, SnoozeFor::Minutes(60)).unwrap();
+            let status = impl_get_service_status(&state).await.unwrap();
+            assert!(status.paused);
+            assert_eq!(status.summary, "Paused, resumes in 1 h");
+
+            let started = std::time::Instant::now();
+            internal_stop_service(&state).unwrap();
+            assert!(started.elapsed() < std::time::Duration::from_secs(1));
+            assert!(!impl_get_service_status(&state).await.unwrap().running);
+        }
     }
 
     #[test]
@@ -454,6 +523,11 @@ mod tests {
             service_enabled: Some(false),
             check_updates: None,
             last_notified_version: None,
+            activity_retention: None,
+            notifications: None,
+            paused_until: None,
+            active_profile: None,
+            profiles: None,
         };
         let yaml = serde_yaml::to_string(&initial_cfg).unwrap();
         std::fs::write(&cfg_path, yaml).unwrap();
@@ -475,6 +549,22 @@ mod tests {
         assert!(content.contains("service_enabled: false"));
     }
 
+    #[test]
+    fn test_persist_service_state_refuses_external_edit() {
+        let tmp = tempdir().unwrap();
+        let cfg_path = tmp.path().join("config.yaml");
+        let initial_cfg = harbor_core::downloads::default_config();
+        std::fs::write(&cfg_path, serde_yaml::to_string(&initial_cfg).unwrap()).unwrap();
+
+        let state = AppState::new(cfg_path.clone(), initial_cfg);
+        std::fs::write(&cfg_path, "download_dir: edited\nrules: []\n").unwrap();
+
+        let res = persist_service_state(&state, false);
+        assert!(res.unwrap_err().contains("changed by another program"));
+        let content = std::fs::read_to_string(&cfg_path).unwrap();
+        assert!(content.contains("edited"));
+    }
+
     #[tokio::test]
     async fn test_persist_update_settings() {
         let tmp = tempdir().unwrap();
@@ -488,6 +578,11 @@ mod tests {
             service_enabled: None,
             check_updates: Some(true),
             last_notified_version: None,
+            activity_retention: None,
+            notifications: None,
+            paused_until: None,
+            active_profile: None,
+            profiles: None,
         };
         let yaml = serde_yaml::to_string(&initial_cfg).unwrap();
         std::fs::write(&cfg_path, yaml).unwrap();
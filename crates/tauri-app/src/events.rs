@@ -0,0 +1,185 @@
+//! Pushes watcher events to the webview so the UI does not have to poll, and
+//! turns them into desktop notifications.
+
+use crate::commands::ServiceStatus;
+use crate::notify;
+use crate::state::AppState;
+use chrono::Local;
+use harbor_core::notifications::Notifier;
+use harbor_core::service::{ServiceEvent, ServiceState};
+use serde::Serialize;
+use std::sync::mpsc::Receiver;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Files were moved; payload [`MovesPayload`]
+pub const MOVE_COMPLETED: &str = "move-completed";
+/// Files matched a rule but could not be moved; payload [`FailuresPayload`]
+pub const MOVE_FAILED: &str = "move-failed";
+/// The watcher was started, stopped, paused or resumed; payload [`ServiceStatus`]
+pub const SERVICE_STATE_CHANGED: &str = "service-state-changed";
+/// The config file was reloaded; no payload
+pub const CONFIG_RELOADED: &str = "config-reloaded";
+/// The config file changed but is invalid; payload is the error message
+pub const CONFIG_ERROR: &str = "config-error";
+/// The watcher finished a pass; payload [`harbor_core::watcher_health::WatcherHealth`]
+pub const WATCHER_HEALTH: &str = "watcher-health";
+/// What the watcher did could not be recorded; payload is the error message
+pub const RECORD_ERROR: &str = "record-error";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MovedFile {
+    pub source: String,
+    pub dest: String,
+    pub rule: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MovesPayload {
+    pub moves: Vec<MovedFile>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedFile {
+    pub path: String,
+    pub rule: String,
+    pub message: String,
+    /// Seconds until the next try; absent once Harbor gave up
+    pub retry_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FailuresPayload {
+    pub failures: Vec<FailedFile>,
+}
+
+/// The webview event for `event`, if the UI cares about it. `status` is only
+/// called for state changes.
+fn to_ui_event(
+    event: &ServiceEvent,
+    status: impl FnOnce() -> ServiceStatus,
+) -> Option<(&'static str, serde_json::Value)> {
+    let payload = match event {
+        ServiceEvent::Organized(actions) => (
+            MOVE_COMPLETED,
+            serde_json::to_value(MovesPayload {
+                moves: actions
+                    .iter()
+                    .map(|(from, to, rule, _, _)| MovedFile {
+                        source: from.display().to_string(),
+                        dest: to.display().to_string(),
+                        rule: rule.clone(),
+                    })
+                    .collect(),
+            }),
+        ),
+        ServiceEvent::Failed(failures) => (
+            MOVE_FAILED,
+            serde_json::to_value(FailuresPayload {
+                failures: failures
+                    .iter()
+                    .map(|f| FailedFile {
+                        path: f.path.display().to_string(),
+                        rule: f.rule.clone(),
+                        message: f.message.clone(),
+                        retry_in_secs: f.retry_in.map(|d| d.as_secs()),
+                    })
+                    .collect(),
+            }),
+        ),
+        ServiceEvent::StateChanged(_) => (SERVICE_STATE_CHANGED, serde_json::to_value(status())),
+        ServiceEvent::ConfigReloaded => (CONFIG_RELOADED, Ok(serde_json::Value::Null)),
+        ServiceEvent::ConfigError(msg) => (CONFIG_ERROR, serde_json::to_value(msg)),
+        ServiceEvent::Scanned(health) => (WATCHER_HEALTH, serde_json::to_value(health)),
+        ServiceEvent::RecordError(msg) => (RECORD_ERROR, serde_json::to_value(msg)),
+    };
+    match payload {
+        (name, Ok(value)) => Some((name, value)),
+        (_, Err(_)) => None,
+    }
+}
+
+/// Forwards `events` to the webview and shows the desktop notifications they
+/// call for, until the service goes away. `on_state` runs on every start and
+/// stop, e.g. to update the tray's check items.
+pub fn forward(
+    app: AppHandle,
+    events: Receiver<ServiceEvent>,
+    on_state: impl Fn(ServiceState) + Send + 'static,
+) {
+    std::thread::spawn(move || {
+        let mut notifier = Notifier::new();
+        for event in events {
+            if let ServiceEvent::StateChanged(state) = &event {
+                on_state(*state);
+            }
+            let service = &app.state::<AppState>().service;
+            let status = || service.status().into();
+            if let Some((name, payload)) = to_ui_event(&event, status) {
+                let _ = app.emit(name, payload);
+            }
+            let config = service.config_snapshot();
+            if let Some(notification) = notifier.on_event(&event, &config, Local::now().time()) {
+                notify::show(&app, notification);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use harbor_core::downloads::RuleFailure;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn stopped() -> ServiceStatus {
+        harbor_core::service::ServiceStatus {
+            state: ServiceState::Stopped,
+            uptime_seconds: None,
+            config_error: None,
+            health: None,
+            paused_until: None,
+            resumes_in_seconds: None,
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_to_ui_event() {
+        let moved = ServiceEvent::Organized(vec![(
+            PathBuf::from("a.pdf"),
+            PathBuf::from("Docs/a.pdf"),
+            "Docs".into(),
+            None,
+            "docs".into(),
+        )]);
+        let (name, payload) = to_ui_event(&moved, stopped).unwrap();
+        assert_eq!(name, MOVE_COMPLETED);
+        assert_eq!(payload["moves"][0]["rule"], "Docs");
+        assert_eq!(payload["moves"][0]["dest"], "Docs/a.pdf");
+
+        let failed = ServiceEvent::Failed(vec![RuleFailure {
+            path: PathBuf::from("b.png"),
+            rule: "Images".into(),
+            rule_id: "images".into(),
+            message: "denied".into(),
+            attempts: 1,
+            retry_in: Some(Duration::from_secs(5)),
+        }]);
+        let (name, payload) = to_ui_event(&failed, stopped).unwrap();
+        assert_eq!(name, MOVE_FAILED);
+        assert_eq!(payload["failures"][0]["retry_in_secs"], 5);
+
+        let (name, payload) =
+            to_ui_event(&ServiceEvent::StateChanged(ServiceState::Stopped), stopped).unwrap();
+        assert_eq!(name, SERVICE_STATE_CHANGED);
+        assert_eq!(payload["running"], false);
+
+        let (name, _) = to_ui_event(&ServiceEvent::ConfigReloaded, stopped).unwrap();
+        assert_eq!(name, CONFIG_RELOADED);
+        let (name, payload) =
+            to_ui_event(&ServiceEvent::RecordError("disk full".into()), stopped).unwrap();
+        assert_eq!(name, RECORD_ERROR);
+        assert_eq!(payload, "disk full");
+    }
+}
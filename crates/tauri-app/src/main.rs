@@ -1,22 +1,18 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
+mod events;
+mod notify;
 mod state;
 
+use harbor_core::config_store::ConfigStore;
 use harbor_core::downloads::{default_config, load_downloads_config};
 
 use state::AppState;
-use std::path::PathBuf;
 use tauri::{Emitter, Manager};
 
-fn local_appdata_harbor() -> PathBuf {
-    std::env::var("LOCALAPPDATA")
-        .map(|p| PathBuf::from(p).join("Harbor"))
-        .unwrap_or(PathBuf::from("C:\\Harbor"))
-}
-
 fn main() {
-    let harbor_dir = local_appdata_harbor();
+    let harbor_dir = harbor_core::paths::harbor_data_dir();
     let _ = std::fs::create_dir_all(&harbor_dir);
 
     let cfg_path = harbor_dir.join("harbor.downloads.yaml");
@@ -28,10 +24,7 @@ fn main() {
             let _ = std::fs::copy(&default_config_path, &cfg_path);
         } else {
             // Create default config
-            let config = default_config();
-            if let Ok(yaml) = serde_yaml::to_string(&config) {
-                let _ = std::fs::write(&cfg_path, yaml);
-            }
+            let _ = ConfigStore::new(&cfg_path).save(&default_config());
         }
     }
 
@@ -72,17 +65,27 @@ fn main() {
             commands::update_rule,
             commands::delete_rule,
             commands::toggle_rule,
+            commands::set_rule_notify,
             commands::reorder_rules,
             commands::get_download_dir,
+            commands::explain_file,
+            commands::export_rules,
+            commands::import_rules,
+            commands::get_profiles,
+            commands::set_active_profile,
             // Activity commands
             commands::get_activity_logs,
             commands::get_activity_stats,
             commands::clear_activity_logs,
+            commands::export_activity,
             // Settings commands
             commands::get_service_status,
             commands::start_service,
             commands::stop_service,
+            commands::snooze_service,
+            commands::resume_service,
             commands::trigger_organize_now,
+            commands::organize_paths,
             commands::get_startup_enabled,
             commands::set_startup_enabled,
             commands::reload_config,
@@ -94,6 +97,8 @@ fn main() {
             commands::set_tutorial_completed,
             commands::get_check_updates,
             commands::set_check_updates,
+            commands::get_notification_settings,
+            commands::set_notification_settings,
             commands::get_last_notified_version,
             commands::set_last_notified_version,
         ])
@@ -105,7 +110,7 @@ fn main() {
         })
         .setup(move |app| {
             use tauri::image::Image;
-            use tauri::menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder};
+            use tauri::menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
             use tauri::tray::{MouseButton, TrayIconBuilder, TrayIconEvent};
             use tauri_plugin_autostart::ManagerExt;
 
@@ -142,10 +147,58 @@ fn main() {
                 .checked(!service_enabled)
                 .build(app)?;
 
+            // Timed pauses, plus a line showing the state and how long a pause has left
+            let service = app.state::<AppState>().service.clone();
+            let status_line = MenuItemBuilder::new(service.status().summary())
+                .id("status_line")
+                .enabled(false)
+                .build(app)?;
+            let pause_menu = SubmenuBuilder::new(app, "Pause")
+                .item(
+                    &MenuItemBuilder::new("For 15 Minutes")
+                        .id("snooze:15m")
+                        .build(app)?,
+                )
+                .item(
+                    &MenuItemBuilder::new("For 1 Hour")
+                        .id("snooze:1h")
+                        .build(app)?,
+                )
+                .item(
+                    &MenuItemBuilder::new("Until Tomorrow")
+                        .id("snooze:tomorrow")
+                        .build(app)?,
+                )
+                .separator()
+                .item(&MenuItemBuilder::new("Resume").id("resume").build(app)?)
+                .build()?;
+
             let organize_now = MenuItemBuilder::new("Organize Now")
                 .id("organize")
                 .build(app)?;
 
+            // One check item per rule profile, the active one checked
+            let (active_profile, profile_names) = {
+                let state: tauri::State<AppState> = app.state();
+                let config = state.config.read().map_err(|e| e.to_string())?;
+                (config.active_profile().to_string(), config.profile_names())
+            };
+            let profile_items = profile_names
+                .iter()
+                .map(|name| {
+                    CheckMenuItemBuilder::new(name)
+                        .id(format!("profile:{}", name))
+                        .checked(*name == active_profile)
+                        .build(app)
+                })
+                .collect::<tauri::Result<Vec<_>>>()?;
+            let profile_menu = profile_items
+                .iter()
+                .fold(SubmenuBuilder::new(app, "Profile"), |menu, item| {
+                    menu.item(item)
+                })
+                .build()?;
+
             let open_downloads = MenuItemBuilder::new("Open Downloads")
                 .id("open_downloads")
                 .build(app)?;
@@ -163,10 +216,13 @@ fn main() {
 
             let menu = MenuBuilder::new(app)
                 .items(&[
+                    &status_line,
                     &status_on,
                     &status_off,
+                    &pause_menu,
                     &tauri::menu::PredefinedMenuItem::separator(app)?,
                     &organize_now,
+                    &profile_menu,
                     &tauri::menu::PredefinedMenuItem::separator(app)?,
                     &open_downloads,
                     &open_rules,
@@ -177,6 +233,25 @@ fn main() {
                 ])
                 .build()?;
 
+            notify::init(app.handle())?;
+
+            // Keep the webview and the tray's check items in sync with the watcher,
+            // whichever side changed it
+            let (check_on, check_off) = (status_on.clone(), status_off.clone());
+            let (line, events_service) = (status_line.clone(), service.clone());
+            events::forward(app.handle().clone(), service.subscribe(), move |state| {
+                let running = state != harbor_core::service::ServiceState::Stopped;
+                let _ = check_on.set_checked(running);
+                let _ = check_off.set_checked(!running);
+                let _ = line.set_text(events_service.status().summary());
+            });
+            // Counts a timed pause down between state changes
+            let line = status_line.clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(std::time::Duration::from_secs(30));
+                let _ = line.set_text(service.status().summary());
+            });
+
             let _tray = TrayIconBuilder::with_id("tray")
                 .icon(tray_icon)
                 .menu(&menu)
@@ -198,21 +273,60 @@ fn main() {
                     "quit" => {
                         app.exit(0);
                     }
+                    // Clicking toggles the item itself, so set both even when
+                    // the service was already in the requested state
                     "service_on" => {
                         let state: tauri::State<AppState> = app.state();
-                        let _ = commands::settings::persist_service_state(&state, true);
-                        let _ = commands::settings::internal_start_service(&state);
+                        if let Err(e) = commands::settings::impl_start_service(&state) {
+                            notify::error(app, "Harbor could not start watching", &e);
+                        }
                         let _ = status_on.set_checked(true);
                         let _ = status_off.set_checked(false);
-                        // Force update UI if open? Not easy from here, UI polls status.
                     }
                     "service_off" => {
                         let state: tauri::State<AppState> = app.state();
-                        let _ = commands::settings::persist_service_state(&state, false);
-                        let _ = commands::settings::internal_stop_service(&state);
+                        let stopped = commands::settings::persist_service_state(&state, false)
+                            .and_then(|()| commands::settings::internal_stop_service(&state));
+                        if let Err(e) = stopped {
+                            notify::error(app, "Harbor could not stop watching", &e);
+                        }
                         let _ = status_on.set_checked(false);
                         let _ = status_off.set_checked(true);
                     }
+                    id if id.starts_with("snooze:") => {
+                        let state: tauri::State<AppState> = app.state();
+                        let paused = id["snooze:".len()..]
+                            .parse()
+                            .map_err(|e: anyhow::Error| e.to_string())
+                            .and_then(|duration| {
+                                commands::settings::impl_snooze_service(&state, duration)
+                            });
+                        if let Err(e) = paused {
+                            notify::error(app, "Harbor could not pause", &e);
+                        }
+                    }
+                    "resume" => {
+                        let state: tauri::State<AppState> = app.state();
+                        if let Err(e) = commands::settings::impl_resume_service(&state) {
+                            notify::error(app, "Harbor could not resume", &e);
+                        }
+                    }
+                    id if id.starts_with("profile:") => {
+                        let name = id["profile:".len()..].to_string();
+                        let items = profile_items.clone();
+                        let app_handle = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            let state: tauri::State<AppState> = app_handle.state();
+                            if let Ok(profiles) =
+                                commands::impl_set_active_profile(&state, name).await
+                            {
+                                let active = format!("profile:{}", profiles.active);
+                                for item in &items {
+                                    let _ = item.set_checked(item.id().as_ref() == active);
+                                }
+                            }
+                        });
+                    }
                     "organize" => {
                         let app_handle = app.clone();
                         tauri::async_runtime::spawn(async move {
@@ -0,0 +1,87 @@
+//! Shows the watcher's desktop notifications with a "Show in folder" button.
+
+use harbor_core::notifications::Notification;
+use std::path::Path;
+use tauri::AppHandle;
+use tauri_plugin_notification::{Action, ActionPerformed, ActionType, NotificationExt};
+
+/// Action type of notifications about a file
+const REVEAL_TYPE: &str = "reveal-file";
+const REVEAL_ACTION: &str = "reveal";
+/// Key of the revealed path in the notification's extra data
+const PATH_KEY: &str = "path";
+
+/// Registers the "Show in folder" button. Clicking it, or the notification
+/// itself, opens the folder containing the file.
+pub fn init(app: &AppHandle) -> tauri_plugin_notification::Result<()> {
+    let notifications = app.notification();
+    notifications.register_action_types(vec![ActionType::builder(REVEAL_TYPE)
+        .actions(vec![
+            Action::builder(REVEAL_ACTION, "Show in folder").build()
+        ])
+        .build()])?;
+    notifications.on_action(on_action)
+}
+
+pub fn show(app: &AppHandle, notification: Notification) {
+    let mut builder = app
+        .notification()
+        .builder()
+        .title(notification.title)
+        .body(notification.body);
+    if let Some(path) = notification.reveal {
+        builder = builder
+            .action_type_id(REVEAL_TYPE)
+            .extra(PATH_KEY, path.display().to_string());
+    }
+    if let Err(e) = builder.show() {
+        eprintln!("Failed to show notification: {}", e);
+    }
+}
+
+/// Reports a failed tray action, which has no window to show it in.
+pub fn error(app: &AppHandle, title: &str, message: &str) {
+    show(
+        app,
+        Notification {
+            title: title.to_string(),
+            body: message.to_string(),
+            reveal: None,
+        },
+    );
+}
+
+fn on_action(performed: &ActionPerformed) {
+    if !matches!(performed.action_id(), REVEAL_ACTION | "tap") {
+        return;
+    }
+    let path = performed
+        .notification()
+        .and_then(|n| n.extra().get(PATH_KEY))
+        .and_then(|v| v.as_str());
+    if let Some(path) = path {
+        if let Err(e) = reveal(Path::new(path)) {
+            eprintln!("Failed to show {} in its folder: {}", path, e);
+        }
+    }
+}
+
+/// Opens the folder containing `path`, selecting the file where the file
+/// manager supports it.
+fn reveal(path: &Path) -> std::io::Result<()> {
+    #[cfg(windows)]
+    {
+        std::process::Command::new("explorer")
+            .arg("/select,")
+            .arg(path)
+            .spawn()?;
+    }
+
+    #[cfg(not(windows))]
+    {
+        let dir = path.parent().unwrap_or(path);
+        std::process::Command::new("xdg-open").arg(dir).spawn()?;
+    }
+
+    Ok(())
+}
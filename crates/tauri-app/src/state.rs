@@ -1,41 +1,59 @@
+use harbor_core::activity::ActivityLog;
+use harbor_core::config_store::ConfigStore;
 use harbor_core::downloads::DownloadsConfig;
-use std::path::PathBuf;
-use std::sync::atomic::AtomicBool;
-use std::sync::{Arc, Mutex, RwLock};
-use std::thread::JoinHandle;
+use harbor_core::rule_stats::RuleStatsStore;
+use harbor_core::service::WatcherService;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
 /// Application state managed by Tauri
 pub struct AppState {
     /// Path to the configuration file
     pub config_path: PathBuf,
-    /// Valid flag for the *current* watcher thread.
-    /// When the service stops or restarts, we set the old flag to false
-    /// and create a new one for the new thread.
-    pub watcher_flag: Arc<Mutex<Option<Arc<AtomicBool>>>>,
-    /// Current configuration (cached)
+    /// Locked, backed-up writer for the configuration file
+    pub store: Arc<ConfigStore>,
+    /// Current configuration (cached), shared with the watcher
     pub config: Arc<RwLock<DownloadsConfig>>,
-    /// Handle to the watcher thread
-    pub watcher_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
-    /// Timestamp when the service was started
-    pub service_start_time: Arc<Mutex<Option<std::time::Instant>>>,
+    /// The downloads watcher, its status and the moves it records
+    pub service: WatcherService,
 }
 
 impl AppState {
     pub fn new(config_path: PathBuf, config: DownloadsConfig) -> Self {
+        let store = Arc::new(ConfigStore::new(&config_path));
+        let service = WatcherService::new(config)
+            .with_store(store.clone())
+            .with_log_path(recent_log_path(&config_path));
         Self {
+            config: service.config().clone(),
+            store,
             config_path,
-            watcher_flag: Arc::new(Mutex::new(None)),
-            config: Arc::new(RwLock::new(config)),
-            watcher_handle: Arc::new(Mutex::new(None)),
-            service_start_time: Arc::new(Mutex::new(None)),
+            service,
         }
     }
 
-    /// Get the path to the recent moves log
-    pub fn recent_log_path(&self) -> PathBuf {
-        self.config_path
-            .parent()
-            .unwrap_or(&self.config_path)
-            .join("recent_moves.log")
+    /// Persist `config` to disk, refusing to overwrite external edits
+    pub fn save_config(&self, config: &DownloadsConfig) -> Result<(), String> {
+        self.store
+            .save(config)
+            .map_err(|e| format!("Failed to write config: {}", e))
     }
+
+    /// The activity log, rotated according to the current config
+    pub fn activity_log(&self) -> ActivityLog {
+        self.service.activity_log()
+    }
+
+    /// Per-rule match counters, stored next to the config file
+    pub fn rule_stats(&self) -> RuleStatsStore {
+        RuleStatsStore::for_config(&self.config_path)
+    }
+}
+
+/// Where the app keeps its recent moves log
+fn recent_log_path(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or(config_path)
+        .join("recent_moves.log")
 }
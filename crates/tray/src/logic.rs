@@ -1,12 +1,10 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use harbor_core::activity::ActivityLog;
 use harbor_core::downloads::{
-    cleanup_old_symlinks, load_downloads_config, organize_once, watch_polling, DownloadsConfig,
-    OrganizeResult,
+    cleanup_old_symlinks, load_downloads_config, DownloadsConfig, OrganizeResult, WatcherThread,
 };
+use harbor_core::service::{ServiceState, SnoozeFor, WatcherService};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
-use std::thread;
 
 pub mod windows {
     pub mod utils {
@@ -53,131 +51,120 @@ pub mod windows {
     }
 }
 
+/// The tray's view of the shared [`WatcherService`].
 #[derive(Clone)]
 pub struct TrayLogic {
-    pub config: Arc<DownloadsConfig>,
-    watching: Arc<AtomicBool>,
-    handle: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
-    pub log_path: PathBuf,
+    service: WatcherService,
 }
 
 impl TrayLogic {
     pub fn new(config: DownloadsConfig) -> Self {
         Self {
-            config: Arc::new(config),
-            watching: Arc::new(AtomicBool::new(false)),
-            handle: Arc::new(Mutex::new(None)),
-            log_path: Self::default_log_path(),
+            service: WatcherService::new(config).with_log_path(Self::default_log_path()),
         }
     }
 
     #[allow(dead_code)]
     pub fn with_log_path(mut self, path: PathBuf) -> Self {
-        self.log_path = path;
+        self.service = self.service.with_log_path(path);
         self
     }
 
-    pub fn start_watching(&self) {
-        if self.watching.swap(true, Ordering::SeqCst) {
-            return;
-        }
+    /// The watcher reloads the config from this file when it changes.
+    pub fn with_config_path(mut self, path: PathBuf) -> Self {
+        self.service = self.service.with_config_path(path);
+        self
+    }
 
-        let logic = self.clone();
-        let logic_cb = logic.clone();
+    pub fn log_path(&self) -> &Path {
+        self.service.log_path()
+    }
 
-        let h = thread::spawn(move || {
-            let _ = watch_polling(&logic.config, 5, &logic.watching, move |actions| {
-                logic_cb.on_file_change(actions)
-            });
-        });
+    pub fn config_snapshot(&self) -> DownloadsConfig {
+        self.service.config_snapshot()
+    }
 
-        let mut guard = self.handle.lock().unwrap();
-        *guard = Some(h);
+    pub fn start_watching(&self) -> Result<()> {
+        self.service.start()
     }
 
-    pub fn on_file_change(&self, actions: &[OrganizeResult]) {
-        self.append_recent(actions);
+    #[allow(dead_code)]
+    pub fn is_watching(&self) -> bool {
+        self.service.state() != ServiceState::Stopped
     }
 
-    pub fn stop_watching(&self) {
-        self.watching.store(false, Ordering::SeqCst);
-        let mut guard = self.handle.lock().unwrap();
-        if let Some(h) = guard.take() {
-            // Unpark or wait? watch_polling checks atomic every 5s or on event.
-            // We just let it finish.
-            // On Windows we cannot easily interrupt the directory watcher.
-            // But verify thread usage:
-            #[allow(clippy::disallowed_methods)]
-            let _ = h.thread().id();
+    /// Fails when the pass in progress outlasts the stop timeout; the
+    /// watcher still stops once that pass is done.
+    pub fn stop_watching(&self) -> Result<()> {
+        if !self.service.stop() {
+            bail!(
+                "still finishing a move after {}s; it stops once that is done",
+                WatcherThread::STOP_TIMEOUT.as_secs()
+            );
         }
+        Ok(())
+    }
+
+    /// "Start Watching" from the menu; unlike startup it also ends a timed pause.
+    pub fn resume_watching(&self) -> Result<()> {
+        self.service.start()?;
+        self.service.resume()
+    }
+
+    pub fn snooze(&self, duration: SnoozeFor) -> Result<()> {
+        self.service.snooze(duration.until_now())
+    }
+
+    /// The disabled first menu item, e.g. "Paused, resumes in 14 min".
+    pub fn status_line(&self) -> String {
+        self.service.status().summary()
+    }
+
+    /// The tray tooltip, e.g. "Harbor: Paused, resumes in 14 min".
+    pub fn status_tip(&self) -> String {
+        format!("Harbor: {}", self.status_line())
     }
 
     pub fn organize_now(&self) -> Result<Vec<OrganizeResult>> {
-        let actions = organize_once(&self.config)?;
-        self.append_recent(&actions);
-        Ok(actions)
+        Ok(self.service.organize_now()?.actions)
     }
 
     pub fn cleanup_old_symlinks(&self) -> Result<usize> {
-        let count = cleanup_old_symlinks(&self.config)?;
+        let count = cleanup_old_symlinks(&self.config_snapshot())?;
         if count > 0 {
-            let _ = std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&self.log_path)
-                .and_then(|mut f| {
-                    use std::io::Write;
-                    writeln!(f, "Startup: Cleaned up {} old symlink(s)", count)
-                });
+            self.diagnostic(&format!("Startup: Cleaned up {} old symlink(s)", count));
         }
         Ok(count)
     }
 
+    /// Where the tray notes housekeeping that is not a move: `harbor-tray.log`
+    /// next to the activity log, which only holds JSON Lines records.
+    pub fn diagnostic_log_path(&self) -> PathBuf {
+        self.log_path().with_file_name("harbor-tray.log")
+    }
+
+    fn diagnostic(&self, message: &str) {
+        let _ = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.diagnostic_log_path())
+            .and_then(|mut f| {
+                use std::io::Write;
+                writeln!(f, "{}", message)
+            });
+    }
+
     fn default_log_path() -> PathBuf {
-        std::env::var("LOCALAPPDATA")
-            .map(|p| PathBuf::from(p).join("Harbor").join("recent_moves.log"))
-            .unwrap_or(PathBuf::from("C:\\Harbor\\recent_moves.log"))
+        ActivityLog::default_path()
     }
 
     pub fn local_appdata_harbor() -> PathBuf {
-        std::env::var("LOCALAPPDATA")
-            .map(|p| PathBuf::from(p).join("Harbor"))
-            .unwrap_or(PathBuf::from("C:\\Harbor"))
+        harbor_core::paths::harbor_data_dir()
     }
 
     pub fn recent_log_path() -> PathBuf {
         Self::default_log_path()
     }
-
-    fn append_recent(&self, actions: &[OrganizeResult]) {
-        if actions.is_empty() {
-            return;
-        }
-
-        // Ensure directory exists
-        if let Some(parent) = self.log_path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-
-        if let Ok(mut file) = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.log_path)
-        {
-            use std::io::Write;
-            for (from, to, rule, _) in actions {
-                let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-                let _ = writeln!(
-                    file,
-                    "[{}] Moved {} -> {} (Rule: {})",
-                    timestamp,
-                    from.file_name().unwrap_or_default().to_string_lossy(),
-                    to.display(),
-                    rule
-                );
-            }
-        }
-    }
 }
 
 pub fn load_initial_config(config_path: &Path) -> Result<DownloadsConfig> {
@@ -203,6 +190,7 @@ pub fn load_initial_config(config_path: &Path) -> Result<DownloadsConfig> {
 mod tests {
     use super::*;
     use crate::logic::windows::utils::SingleInstance;
+    use harbor_core::service::ServiceEvent;
     use tempfile::tempdir;
 
     fn create_test_config() -> (DownloadsConfig, tempfile::TempDir) {
@@ -218,21 +206,51 @@ mod tests {
             service_enabled: Some(true),
             check_updates: Some(true),
             last_notified_version: None,
+            activity_retention: None,
+            notifications: None,
+            paused_until: None,
+            active_profile: None,
+            profiles: None,
         };
         (config, tmp)
     }
 
+    fn with_pdf_rule(mut config: DownloadsConfig, tmp: &tempfile::TempDir) -> DownloadsConfig {
+        config.min_age_secs = Some(0);
+        config.rules.push(harbor_core::types::Rule {
+            id: "docs".to_string(),
+            name: "Docs".to_string(),
+            extensions: Some(vec!["pdf".to_string()]),
+            pattern: None,
+            min_size_bytes: None,
+            max_size_bytes: None,
+            target_dir: tmp.path().join("Docs").to_string_lossy().to_string(),
+            create_symlink: None,
+            enabled: None,
+            notify: None,
+        });
+        config
+    }
+
     #[test]
     fn test_tray_logic_struct() {
         let (config, _tmp) = create_test_config();
         let logic = TrayLogic::new(config);
 
-        logic.start_watching();
-        assert!(logic.handle.lock().unwrap().is_some());
+        logic.start_watching().unwrap();
+        assert!(logic.is_watching());
+
+        let started = std::time::Instant::now();
+        logic.stop_watching().unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(!logic.is_watching());
 
-        logic.stop_watching();
-        assert!(logic.handle.lock().unwrap().is_none());
-        assert!(!logic.watching.load(Ordering::SeqCst));
+        // Restarting right away runs a single fresh watcher
+        logic.start_watching().unwrap();
+        logic.start_watching().unwrap();
+        assert!(logic.is_watching());
+        logic.stop_watching().unwrap();
+        assert!(!logic.is_watching());
     }
 
     #[test]
@@ -311,44 +329,52 @@ mod tests {
     fn test_double_start_watching() {
         let (config, _tmp) = create_test_config();
         let logic = TrayLogic::new(config);
-        logic.start_watching();
-
-        let id1 = logic
-            .handle
-            .lock()
-            .unwrap()
-            .as_ref()
-            .map(|h| h.thread().id());
-        assert!(id1.is_some());
-
-        logic.start_watching();
-        let id2 = logic
-            .handle
-            .lock()
-            .unwrap()
-            .as_ref()
-            .map(|h| h.thread().id());
-        assert_eq!(id1, id2);
+        let events = logic.service.subscribe();
+        logic.start_watching().unwrap();
+        logic.start_watching().unwrap();
+        logic.stop_watching().unwrap();
+
+        let changes: Vec<_> = events
+            .try_iter()
+            .filter_map(|e| match e {
+                ServiceEvent::StateChanged(state) => Some(state),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(changes, [ServiceState::Running, ServiceState::Stopped]);
+    }
 
-        logic.stop_watching();
+    #[test]
+    fn test_snooze_and_resume() {
+        let (config, _tmp) = create_test_config();
+        let logic = TrayLogic::new(config);
+        assert!(logic.snooze(SnoozeFor::Minutes(15)).is_err());
+
+        logic.start_watching().unwrap();
+        logic.snooze(SnoozeFor::Minutes(15)).unwrap();
+        assert_eq!(logic.status_tip(), "Harbor: Paused, resumes in 15 min");
+        assert_eq!(logic.status_line(), "Paused, resumes in 15 min");
+        // Startup keeps a snooze, the menu item ends it
+        logic.start_watching().unwrap();
+        assert!(logic.status_tip().contains("Paused"));
+        logic.resume_watching().unwrap();
+        assert_eq!(logic.status_tip(), "Harbor: Watching");
+        logic.stop_watching().unwrap();
     }
 
     #[test]
-    fn test_on_file_change() {
+    fn test_organize_now_logs_moves() {
         let (config, tmp) = create_test_config();
-        let logic = TrayLogic::new(config).with_log_path(tmp.path().join("test.log"));
+        let logic =
+            TrayLogic::new(with_pdf_rule(config, &tmp)).with_log_path(tmp.path().join("test.log"));
+        std::fs::write(tmp.path().join("Downloads").join("a.pdf"), "x").unwrap();
 
-        let action = (
-            PathBuf::from("a.txt"),
-            PathBuf::from("b.txt"),
-            "rule".to_string(),
-            None,
-        );
-        logic.on_file_change(&[action]);
+        assert_eq!(logic.organize_now().unwrap().len(), 1);
 
-        assert!(logic.log_path.exists());
-        let content = std::fs::read_to_string(&logic.log_path).unwrap();
-        assert!(content.contains("Moved a.txt -> b.txt (Rule: rule)"));
+        let records = ActivityLog::new(logic.log_path()).read_all().unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(records[0].source.ends_with("a.pdf"));
+        assert_eq!(records[0].rule.as_deref(), Some("Docs"));
     }
 
     #[test]
@@ -359,6 +385,7 @@ mod tests {
         std::fs::create_dir(&target_dir).unwrap();
 
         config.rules.push(harbor_core::types::Rule {
+            id: String::new(),
             name: "test".to_string(),
             extensions: None,
             pattern: None,
@@ -367,6 +394,7 @@ mod tests {
             target_dir: target_dir.to_string_lossy().to_string(),
             create_symlink: None,
             enabled: Some(true),
+            notify: None,
         });
 
         // Create a file in target
@@ -396,9 +424,10 @@ mod tests {
         // Assert
         if count > 0 {
             assert!(!symlink.exists());
-            // Check log
-            let log_content = std::fs::read_to_string(&logic.log_path).unwrap();
+            // Noted in the tray's own log, not the activity log
+            let log_content = std::fs::read_to_string(logic.diagnostic_log_path()).unwrap();
             assert!(log_content.contains("Cleaned up"));
+            assert!(!logic.log_path().exists());
         }
     }
 
@@ -407,10 +436,10 @@ mod tests {
         let (config, tmp) = create_test_config();
         // Use a log path in a nested non-existent directory
         let log_path = tmp.path().join("nested").join("dir").join("log.txt");
-        let logic = TrayLogic::new(config).with_log_path(log_path.clone());
+        let logic = TrayLogic::new(with_pdf_rule(config, &tmp)).with_log_path(log_path.clone());
+        std::fs::write(tmp.path().join("Downloads").join("a.pdf"), "x").unwrap();
 
-        let action = (PathBuf::from("a"), PathBuf::from("b"), "rule".into(), None);
-        logic.on_file_change(&[action]);
+        logic.organize_now().unwrap();
 
         assert!(log_path.exists());
     }
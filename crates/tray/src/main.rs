@@ -2,8 +2,13 @@ use anyhow::Result;
 use native_windows_gui as nwg;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use windows::core::PCWSTR;
+use windows::Win32::UI::WindowsAndMessaging::{
+    ModifyMenuW, HMENU, MF_BYCOMMAND, MF_GRAYED, MF_STRING,
+};
 
 mod logic;
+use harbor_core::service::SnoozeFor;
 use logic::{load_initial_config, windows::utils::SingleInstance, TrayLogic};
 
 #[derive(Default)]
@@ -11,8 +16,13 @@ struct TrayState {
     window: nwg::Window,
     tray: nwg::TrayNotification,
     tray_menu: nwg::Menu,
+    item_status: nwg::MenuItem,
     item_start: nwg::MenuItem,
     item_stop: nwg::MenuItem,
+    pause_menu: nwg::Menu,
+    item_pause_15m: nwg::MenuItem,
+    item_pause_1h: nwg::MenuItem,
+    item_pause_tomorrow: nwg::MenuItem,
     item_organize: nwg::MenuItem,
     item_open_downloads: nwg::MenuItem,
     item_open_cfg: nwg::MenuItem,
@@ -20,11 +30,56 @@ struct TrayState {
     item_exit: nwg::MenuItem,
 }
 
-fn show_menu(ui: &TrayState) {
+fn show_menu(ui: &TrayState, logic: &TrayLogic) {
+    // Refresh the countdown of a timed pause before the menu opens
+    ui.tray.set_tip(&logic.status_tip());
+    set_menu_item_text(&ui.item_status, &logic.status_line());
     let (x, y) = nwg::GlobalCursor::position();
     ui.tray_menu.popup(x, y);
 }
 
+/// Relabels a menu item, keeping it disabled; nwg has no setter for the text.
+fn set_menu_item_text(item: &nwg::MenuItem, text: &str) {
+    let Some((menu, id)) = item.handle.hmenu_item() else {
+        return;
+    };
+    let wide_text: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    // SAFETY: `menu` and `id` identify a live item of our menu, and `wide_text`
+    // is a null-terminated wide string that outlives the call.
+    let _ = unsafe {
+        ModifyMenuW(
+            HMENU(menu.cast()),
+            id,
+            MF_BYCOMMAND | MF_STRING | MF_GRAYED,
+            id as usize,
+            PCWSTR(wide_text.as_ptr()),
+        )
+    };
+}
+
+/// Shows a failed menu action as a balloon, the tray's only way to report it.
+fn show_error(ui: &TrayState, title: &str, error: &anyhow::Error) {
+    ui.tray.show(
+        &format!("{:#}", error),
+        Some(title),
+        Some(nwg::TrayNotificationFlags::ERROR_ICON),
+        None,
+    );
+}
+
+/// How long the clicked Pause entry pauses for, if it is one.
+fn pause_choice(ui: &TrayState, handle: nwg::ControlHandle) -> Option<SnoozeFor> {
+    if handle == ui.item_pause_15m {
+        Some(SnoozeFor::Minutes(15))
+    } else if handle == ui.item_pause_1h {
+        Some(SnoozeFor::Minutes(60))
+    } else if handle == ui.item_pause_tomorrow {
+        Some(SnoozeFor::Tomorrow)
+    } else {
+        None
+    }
+}
+
 fn open_folder(path: &Path) {
     if cfg!(windows) {
         let _ = std::process::Command::new("explorer").arg(path).spawn();
@@ -51,7 +106,7 @@ fn main() -> Result<()> {
     // Load config using refactored function
     let cfg = load_initial_config(&cfg_path)?;
 
-    let app_logic = Arc::new(TrayLogic::new(cfg));
+    let app_logic = Arc::new(TrayLogic::new(cfg).with_config_path(cfg_path.clone()));
 
     let mut ui = TrayState::default();
 
@@ -88,6 +143,12 @@ fn main() -> Result<()> {
         .parent(&ui.window)
         .build(&mut ui.tray_menu)?;
 
+    nwg::MenuItem::builder()
+        .text(&app_logic.status_line())
+        .disabled(true)
+        .parent(&ui.tray_menu)
+        .build(&mut ui.item_status)?;
+
     nwg::MenuItem::builder()
         .text("Start Watching")
         .parent(&ui.tray_menu)
@@ -99,6 +160,26 @@ fn main() -> Result<()> {
         .parent(&ui.tray_menu)
         .build(&mut ui.item_stop)?;
 
+    nwg::Menu::builder()
+        .text("Pause")
+        .parent(&ui.tray_menu)
+        .build(&mut ui.pause_menu)?;
+
+    nwg::MenuItem::builder()
+        .text("For 15 Minutes")
+        .parent(&ui.pause_menu)
+        .build(&mut ui.item_pause_15m)?;
+
+    nwg::MenuItem::builder()
+        .text("For 1 Hour")
+        .parent(&ui.pause_menu)
+        .build(&mut ui.item_pause_1h)?;
+
+    nwg::MenuItem::builder()
+        .text("Until Tomorrow")
+        .parent(&ui.pause_menu)
+        .build(&mut ui.item_pause_tomorrow)?;
+
     nwg::MenuItem::builder()
         .text("Organize Now")
         .parent(&ui.tray_menu)
@@ -127,10 +208,6 @@ fn main() -> Result<()> {
     let ui_ref = std::rc::Rc::new(ui);
     let ui_weak = std::rc::Rc::downgrade(&ui_ref);
     let cfg_open_path = cfg_path.clone();
-    let downloads_dir = PathBuf::from(&cfg_open_path)
-        .parent()
-        .map(|_| PathBuf::from(&app_logic.config.download_dir))
-        .unwrap_or(PathBuf::from(&app_logic.config.download_dir));
 
     let logic_c = app_logic.clone();
 
@@ -139,14 +216,22 @@ fn main() -> Result<()> {
             match evt {
                 nwg::Event::OnContextMenu => {
                     if handle == ui.tray {
-                        show_menu(&ui);
+                        show_menu(&ui, &logic_c);
                     }
                 }
                 nwg::Event::OnMenuItemSelected => {
                     if handle == ui.item_start {
-                        logic_c.start_watching();
+                        if let Err(e) = logic_c.resume_watching() {
+                            show_error(&ui, "Harbor could not start watching", &e);
+                        }
                     } else if handle == ui.item_stop {
-                        logic_c.stop_watching();
+                        if let Err(e) = logic_c.stop_watching() {
+                            show_error(&ui, "Harbor could not stop watching", &e);
+                        }
+                    } else if let Some(duration) = pause_choice(&ui, handle) {
+                        if let Err(e) = logic_c.snooze(duration) {
+                            show_error(&ui, "Harbor could not pause", &e);
+                        }
                     } else if handle == ui.item_organize {
                         if let Ok(actions) = logic_c.organize_now() {
                             if !actions.is_empty() {
@@ -159,7 +244,9 @@ fn main() -> Result<()> {
                             }
                         }
                     } else if handle == ui.item_open_downloads {
-                        open_folder(&downloads_dir);
+                        // Read on demand so a reloaded download_dir is honoured
+                        let downloads_dir = logic_c.config_snapshot().download_dir;
+                        open_folder(Path::new(&downloads_dir));
                     } else if handle == ui.item_open_cfg {
                         open_config(&cfg_open_path);
                     } else if handle == ui.item_open_recent {
@@ -168,7 +255,7 @@ fn main() -> Result<()> {
                             if let Some(parent) = p.parent() {
                                 let _ = std::fs::create_dir_all(parent);
                             }
-                            let _ = std::fs::write(&p, "Recent Moves Log\n----------------\n");
+                            let _ = std::fs::write(&p, "");
                         }
                         open_config(&p);
                     } else if handle == ui.item_exit {
@@ -184,7 +271,9 @@ fn main() -> Result<()> {
     // Cleanup old symlinks on startup
     let _ = app_logic.cleanup_old_symlinks();
 
-    app_logic.start_watching();
+    if let Err(e) = app_logic.start_watching() {
+        show_error(&ui_ref, "Harbor could not start watching", &e);
+    }
 
     nwg::dispatch_thread_events();
     Ok(())
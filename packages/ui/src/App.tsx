@@ -9,6 +9,8 @@ import { SettingsPage } from './pages/SettingsPage';
 import { InfoPage } from './pages/InfoPage';
 import { useEffect } from 'react';
 import { listen } from '@tauri-apps/api/event';
+import { getCurrentWebview } from '@tauri-apps/api/webview';
+import { organizePaths } from './lib/tauri';
 import { useWindowSize } from './hooks/useWindowSize';
 
 function GlobalNavigationListener() {
@@ -27,6 +29,24 @@ function GlobalNavigationListener() {
   return null;
 }
 
+function GlobalDropListener() {
+  useEffect(() => {
+    const unlisten = getCurrentWebview().onDragDropEvent((event) => {
+      if (event.payload.type === 'drop' && event.payload.paths.length > 0) {
+        organizePaths(event.payload.paths).catch((err) =>
+          console.error('Failed to organize dropped files:', err)
+        );
+      }
+    });
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  return null;
+}
+
 function GlobalContextMenuListener() {
   useEffect(() => {
     const handleContextMenu = (e: MouseEvent) => {
@@ -54,6 +74,7 @@ function App() {
         <BrowserRouter>
           <GlobalNavigationListener />
           <GlobalContextMenuListener />
+          <GlobalDropListener />
           <UpdateProvider>
             <Routes>
               <Route path="/" element={<Layout />}>
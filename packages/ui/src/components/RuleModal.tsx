@@ -5,7 +5,7 @@ import { open } from '@tauri-apps/plugin-dialog';
 interface RuleModalProps {
     isOpen: boolean;
     onClose: () => void;
-    onSave: (rule: Omit<Rule, 'id' | 'icon' | 'icon_color'>) => Promise<void>;
+    onSave: (rule: Omit<Rule, 'id' | 'icon' | 'icon_color' | 'notify' | 'stats'>) => Promise<void>;
     initialData?: Rule | null;
 }
 
@@ -10,19 +10,28 @@ vi.mock('../lib/tauri', () => ({
     getDownloadDir: vi.fn(),
     startService: vi.fn(),
     stopService: vi.fn(),
+    snoozeService: vi.fn(),
+    resumeService: vi.fn(),
     setStartupEnabled: vi.fn(),
     triggerOrganizeNow: vi.fn(),
     reloadConfig: vi.fn(),
     resetToDefaults: vi.fn(),
+    onServiceStateChanged: vi.fn(),
+    onWatcherHealth: vi.fn(),
+    onConfigReloaded: vi.fn(),
+    onRecordError: vi.fn(),
 }));
 
 // Test component to consume context
 function TestComponent() {
-    const { serviceStatus, toggleService, startupEnabled, toggleStartup } = useSettingsContext();
+    const { serviceStatus, toggleService, snooze, startupEnabled, toggleStartup, error } = useSettingsContext();
     return (
         <div>
+            <div data-testid="error">{error}</div>
             <div data-testid="service-status">{serviceStatus.running ? 'Running' : 'Stopped'}</div>
+            <div data-testid="service-summary">{serviceStatus.summary}</div>
             <button data-testid="toggle-service" onClick={toggleService}>Toggle Service</button>
+            <button data-testid="snooze" onClick={() => snooze({ minutes: 15 })}>Pause</button>
             <div data-testid="startup-status">{startupEnabled ? 'Enabled' : 'Disabled'}</div>
             <button data-testid="toggle-startup" onClick={toggleStartup}>Toggle Startup</button>
         </div>
@@ -33,13 +42,68 @@ describe('SettingsContext', () => {
     beforeEach(() => {
         vi.resetAllMocks();
         // Default mocks
-        vi.mocked(tauri.getServiceStatus).mockResolvedValue({ running: false });
+        vi.mocked(tauri.getServiceStatus).mockResolvedValue({ running: false, paused: false, summary: 'Stopped' });
         vi.mocked(tauri.getStartupEnabled).mockResolvedValue(false);
         vi.mocked(tauri.getDownloadDir).mockResolvedValue('/downloads');
+        vi.mocked(tauri.onServiceStateChanged).mockResolvedValue(() => {});
+        vi.mocked(tauri.onWatcherHealth).mockResolvedValue(() => {});
+        vi.mocked(tauri.onConfigReloaded).mockResolvedValue(() => {});
+        vi.mocked(tauri.onRecordError).mockResolvedValue(() => {});
+    });
+
+    it('follows state changes pushed by the backend', async () => {
+        render(
+            <SettingsProvider>
+                <TestComponent />
+            </SettingsProvider>
+        );
+
+        await waitFor(() => expect(tauri.onServiceStateChanged).toHaveBeenCalled());
+        // Let the initial fetch land first so it cannot overwrite the event
+        await act(async () => {});
+        const handler = vi.mocked(tauri.onServiceStateChanged).mock.calls[0][0];
+        act(() => handler({ running: true, paused: false, summary: 'Watching' }));
+
+        expect(screen.getByTestId('service-status')).toHaveTextContent('Running');
+    });
+
+    it('shows what the watcher could not record', async () => {
+        render(
+            <SettingsProvider>
+                <TestComponent />
+            </SettingsProvider>
+        );
+
+        await waitFor(() => expect(tauri.onRecordError).toHaveBeenCalled());
+        await act(async () => {});
+        const handler = vi.mocked(tauri.onRecordError).mock.calls[0][0];
+        act(() => handler('writing the activity log: disk full'));
+
+        expect(screen.getByTestId('error')).toHaveTextContent('disk full');
+    });
+
+    it('pauses the service for a while', async () => {
+        vi.mocked(tauri.getServiceStatus)
+            .mockResolvedValueOnce({ running: true, paused: false, summary: 'Watching' })
+            .mockResolvedValueOnce({ running: true, paused: true, resumes_in_seconds: 900, summary: 'Paused, resumes in 15 min' });
+
+        render(
+            <SettingsProvider>
+                <TestComponent />
+            </SettingsProvider>
+        );
+
+        await waitFor(() => expect(screen.getByTestId('service-summary')).toHaveTextContent('Watching'));
+        await act(async () => {
+            screen.getByTestId('snooze').click();
+        });
+
+        expect(tauri.snoozeService).toHaveBeenCalledWith({ minutes: 15 });
+        await waitFor(() => expect(screen.getByTestId('service-summary')).toHaveTextContent('Paused, resumes in 15 min'));
     });
 
     it('loads initial state correctly', async () => {
-        vi.mocked(tauri.getServiceStatus).mockResolvedValue({ running: true });
+        vi.mocked(tauri.getServiceStatus).mockResolvedValue({ running: true, paused: false, summary: 'Watching' });
 
         render(
             <SettingsProvider>
@@ -55,8 +119,8 @@ describe('SettingsContext', () => {
     it('optimistically updates service status on toggle', async () => {
         // Initial state: Stopped
         vi.mocked(tauri.getServiceStatus)
-            .mockResolvedValueOnce({ running: false }) // Init
-            .mockResolvedValueOnce({ running: true }); // After toggle validation
+            .mockResolvedValueOnce({ running: false, paused: false, summary: 'Stopped' }) // Init
+            .mockResolvedValueOnce({ running: true, paused: false, summary: 'Watching' }); // After toggle validation
 
         render(
             <SettingsProvider>
@@ -81,7 +145,7 @@ describe('SettingsContext', () => {
 
     it('reverts optimistic update if service call fails', async () => {
         vi.mocked(tauri.startService).mockRejectedValue(new Error('Failed to start'));
-        vi.mocked(tauri.getServiceStatus).mockResolvedValue({ running: false });
+        vi.mocked(tauri.getServiceStatus).mockResolvedValue({ running: false, paused: false, summary: 'Stopped' });
 
         render(
             <SettingsProvider>
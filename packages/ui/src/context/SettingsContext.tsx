@@ -1,15 +1,21 @@
 import { createContext, useContext, useState, useEffect, useCallback, type ReactNode } from 'react';
-import type { ServiceStatus } from '../lib/tauri';
+import type { ServiceStatus, SnoozeFor } from '../lib/tauri';
 import {
     getServiceStatus,
     startService,
     stopService,
+    snoozeService,
+    resumeService,
     triggerOrganizeNow,
     getStartupEnabled,
     setStartupEnabled as setStartupEnabledApi,
     getDownloadDir,
     reloadConfig,
-    resetToDefaults
+    resetToDefaults,
+    onServiceStateChanged,
+    onWatcherHealth,
+    onConfigReloaded,
+    onRecordError
 } from '../lib/tauri';
 
 interface SettingsContextType {
@@ -20,6 +26,8 @@ interface SettingsContextType {
     organizing: boolean;
     error: string | null;
     toggleService: () => Promise<void>;
+    snooze: (duration: SnoozeFor) => Promise<void>;
+    resume: () => Promise<void>;
     toggleStartup: () => Promise<void>;
     organizeNow: () => Promise<number>;
     reload: () => Promise<void>;
@@ -30,7 +38,7 @@ interface SettingsContextType {
 const SettingsContext = createContext<SettingsContextType | undefined>(undefined);
 
 export function SettingsProvider({ children }: { children: ReactNode }) {
-    const [serviceStatus, setServiceStatus] = useState<ServiceStatus>({ running: false });
+    const [serviceStatus, setServiceStatus] = useState<ServiceStatus>({ running: false, paused: false, summary: 'Stopped' });
     const [startupEnabled, setStartupEnabled] = useState(false);
     const [downloadDir, setDownloadDir] = useState('');
     const [loading, setLoading] = useState(true);
@@ -56,15 +64,36 @@ export function SettingsProvider({ children }: { children: ReactNode }) {
         }
     }, []);
 
-    // Poll service status every 5 seconds to stay in sync with backend
+    // The backend pushes state changes, including ones made from the tray menu
     useEffect(() => {
         fetchStatus();
-        const interval = setInterval(() => {
-            getServiceStatus().then(setServiceStatus).catch(console.error);
-        }, 5000);
-        return () => clearInterval(interval);
+        const unlisteners = [
+            onServiceStateChanged(setServiceStatus),
+            onWatcherHealth((health) => setServiceStatus(prev => prev.running ? { ...prev, health } : prev)),
+            onConfigReloaded((configError) => {
+                setServiceStatus(prev => ({ ...prev, config_error: configError }));
+                // The edit may have paused or resumed the watcher, e.g. `harbor downloads-pause`
+                if (!configError) {
+                    getServiceStatus().then(setServiceStatus).catch(() => {});
+                }
+            }),
+            onRecordError(setError),
+        ];
+        return () => {
+            unlisteners.forEach((unlisten) => unlisten.then((f) => f()));
+        };
     }, [fetchStatus]);
 
+    // Keep the countdown of a timed pause current
+    const snoozed = serviceStatus.resumes_in_seconds != null;
+    useEffect(() => {
+        if (!snoozed) return;
+        const timer = setInterval(() => {
+            getServiceStatus().then(setServiceStatus).catch(() => {});
+        }, 30_000);
+        return () => clearInterval(timer);
+    }, [snoozed]);
+
     const toggleService = async () => {
         try {
             // Optimistic update
@@ -86,6 +115,24 @@ export function SettingsProvider({ children }: { children: ReactNode }) {
         }
     };
 
+    const snooze = async (duration: SnoozeFor) => {
+        try {
+            await snoozeService(duration);
+            setServiceStatus(await getServiceStatus());
+        } catch (err) {
+            setError(err instanceof Error ? err.message : String(err));
+        }
+    };
+
+    const resume = async () => {
+        try {
+            await resumeService();
+            setServiceStatus(await getServiceStatus());
+        } catch (err) {
+            setError(err instanceof Error ? err.message : String(err));
+        }
+    };
+
     const toggleStartup = async () => {
         try {
             // Optimistic update
@@ -145,6 +192,8 @@ export function SettingsProvider({ children }: { children: ReactNode }) {
             organizing,
             error,
             toggleService,
+            snooze,
+            resume,
             toggleStartup,
             organizeNow,
             reload,
@@ -1,6 +1,6 @@
 import { useState, useEffect, useCallback } from 'react';
 import type { ActivityLog, ActivityStats } from '../lib/tauri';
-import { getActivityLogs, getActivityStats, clearActivityLogs } from '../lib/tauri';
+import { getActivityLogs, getActivityStats, clearActivityLogs, onMoveCompleted, onMoveFailed } from '../lib/tauri';
 
 export function useActivity(pageSize = 20) {
     const [logs, setLogs] = useState<ActivityLog[]>([]);
@@ -65,6 +65,14 @@ export function useActivity(pageSize = 20) {
         refresh();
     }, []); // Initial load
 
+    // New moves and failures show up as soon as the watcher records them
+    useEffect(() => {
+        const unlisteners = [onMoveCompleted(refresh), onMoveFailed(refresh)];
+        return () => {
+            unlisteners.forEach((unlisten) => unlisten.then((f) => f()));
+        };
+    }, [refresh]);
+
     return {
         logs,
         stats,
@@ -0,0 +1,27 @@
+import { useState, useEffect, useCallback } from 'react';
+import type { NotificationSettings } from '../lib/tauri';
+import { getNotificationSettings, setNotificationSettings } from '../lib/tauri';
+
+export function useNotificationSettings() {
+    const [settings, setSettings] = useState<NotificationSettings>({});
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        getNotificationSettings()
+            .then(setSettings)
+            .catch((err) => console.error('Failed to load notification settings:', err));
+    }, []);
+
+    const update = useCallback(async (changes: Partial<NotificationSettings>) => {
+        const next = { ...settings, ...changes };
+        try {
+            await setNotificationSettings(next);
+            setSettings(next);
+            setError(null);
+        } catch (err) {
+            setError(err instanceof Error ? err.message : String(err));
+        }
+    }, [settings]);
+
+    return { settings, error, update };
+}
@@ -1,11 +1,12 @@
 import { useState, useEffect, useCallback } from 'react';
-import type { Rule } from '../lib/tauri';
-import { getRules, createRule, updateRule, deleteRule, toggleRule, reorderRules } from '../lib/tauri';
+import type { Rule, RuleWarning } from '../lib/tauri';
+import { getRules, createRule, updateRule, deleteRule, toggleRule, setRuleNotify, reorderRules } from '../lib/tauri';
 
 export function useRules() {
     const [rules, setRules] = useState<Rule[]>([]);
     const [loading, setLoading] = useState(true);
     const [error, setError] = useState<string | null>(null);
+    const [warnings, setWarnings] = useState<RuleWarning[]>([]);
 
     const fetchRules = useCallback(async () => {
         try {
@@ -25,7 +26,7 @@ export function useRules() {
         fetchRules();
     }, [fetchRules]);
 
-    const addRule = async (rule: Omit<Rule, 'id' | 'icon' | 'icon_color'>) => {
+    const addRule = async (rule: Omit<Rule, 'id' | 'icon' | 'icon_color' | 'notify' | 'stats'>) => {
         try {
             const newRule = await createRule(rule);
             setRules((prev) => [...prev, newRule]);
@@ -65,6 +66,13 @@ export function useRules() {
         }
     };
 
+    const setNotify = async (id: string, notify: boolean) => {
+        await setRuleNotify(id, notify);
+        setRules((prev) =>
+            prev.map((r) => (r.id === id ? { ...r, notify } : r))
+        );
+    };
+
     const reorder = async (newOrderIds: string[]) => {
         // Optimistic update
         const oldRules = [...rules];
@@ -82,7 +90,7 @@ export function useRules() {
         setRules(newRules);
 
         try {
-            await reorderRules(newOrderIds);
+            setWarnings(await reorderRules(newOrderIds));
         } catch (err) {
             // Revert on error
             console.error("Failed to reorder rules:", err);
@@ -95,11 +103,13 @@ export function useRules() {
         rules,
         loading,
         error,
+        warnings,
         fetchRules,
         addRule,
         editRule,
         removeRule,
         toggleRule: toggle,
+        setRuleNotify: setNotify,
         reorderRules: reorder,
     };
 }
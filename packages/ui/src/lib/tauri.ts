@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 // --- Types ---
 
@@ -12,8 +13,54 @@ export interface Rule {
     destination: string;
     create_symlink: boolean;
     enabled: boolean;
+    notify: boolean;
     icon: string;
     icon_color: string;
+    stats: RuleStats;
+}
+
+export interface RuleStats {
+    matches: number;
+    bytes_moved: number;
+    last_matched?: string;
+    last_error?: string;
+    last_error_at?: string;
+}
+
+export interface RuleTrace {
+    rule_name: string;
+    matched: boolean;
+    /** Human-readable reason, e.g. "'.pdf' is not one of png, jpg" */
+    reason: string;
+    verdict: { kind: string } & Record<string, unknown>;
+}
+
+export interface Explanation {
+    path: string;
+    /** Why the watcher skips the file before trying rules */
+    skip?: string;
+    winner?: string;
+    would_move: boolean;
+    rules: RuleTrace[];
+}
+
+export interface RuleWarning {
+    message: string;
+    warning:
+        | { kind: 'shadowed'; rule: string; by: string }
+        | { kind: 'overlapping_extensions'; first: string; second: string; extensions: string[] }
+        | { kind: 'duplicate_destination'; first: string; second: string; target_dir: string };
+}
+
+export type RuleCollision = 'skip' | 'rename' | 'replace';
+
+export interface ImportRulesResult {
+    added: string[];
+    replaced: string[];
+    /** [name in the pack, name it was added as] */
+    renamed: [string, string][];
+    skipped: string[];
+    warnings: RuleWarning[];
 }
 
 export interface ActivityLog {
@@ -25,15 +72,37 @@ export interface ActivityLog {
     source_path: string;
     dest_path: string;
     rule_name: string;
+    /** Empty for entries recorded before rules had ids */
+    rule_id: string;
     status: string;
     symlink_info?: string;
 }
 
+export interface ActivityBreakdown {
+    key: string;
+    count: number;
+    bytes: number;
+}
+
+export interface DailyCount {
+    /** YYYY-MM-DD, local time */
+    date: string;
+    count: number;
+    bytes: number;
+}
+
 export interface ActivityStats {
     total_files_moved: number;
     files_moved_today: number;
     files_moved_this_week: number;
+    total_bytes_moved: number;
+    total_failed: number;
     most_active_rule?: string;
+    /** Local hour (0-23) with the most moves */
+    busiest_hour?: number;
+    by_rule: ActivityBreakdown[];
+    by_extension: ActivityBreakdown[];
+    daily: DailyCount[];
 }
 
 export interface ActivityLogsResponse {
@@ -42,11 +111,70 @@ export interface ActivityLogsResponse {
     has_more: boolean;
 }
 
+export interface ActivityFilters {
+    rule?: string;
+    /** Matches a rule's history across renames */
+    ruleId?: string;
+    extension?: string;
+    /** YYYY-MM-DD or an ISO timestamp */
+    since?: string;
+    /** YYYY-MM-DD (inclusive) or an ISO timestamp */
+    until?: string;
+    outcome?: 'success' | 'failed';
+    /** Substring of the file name or either path */
+    search?: string;
+}
+
+export interface WatcherHealth {
+    pid: number;
+    interval_secs: number;
+    last_scan?: string;
+    last_scan_ms: number;
+    files_examined: number;
+    last_error?: string;
+    last_error_at?: string;
+    consecutive_failures: number;
+    total_failures: number;
+    /** Files whose move failed and that will be tried again */
+    pending_retries: number;
+    /** Why the watcher cannot work at all, e.g. the download folder is missing */
+    degraded?: string;
+}
+
+export interface MovedFile {
+    source: string;
+    dest: string;
+    rule: string;
+}
+
+export interface FailedFile {
+    path: string;
+    rule: string;
+    message: string;
+    /** Seconds until the next try; absent once Harbor gave up */
+    retry_in_secs?: number;
+}
+
 export interface ServiceStatus {
     running: boolean;
+    /** Running, but skipping passes until resumed */
+    paused: boolean;
     uptime_seconds?: number;
+    /** Set when the config file was edited into an invalid state */
+    config_error?: string;
+    /** The watcher's latest pass, while it is running */
+    health?: WatcherHealth;
+    /** When a timed pause ends */
+    paused_until?: string;
+    /** Seconds until then */
+    resumes_in_seconds?: number;
+    /** One line, e.g. "Paused, resumes in 14 min" */
+    summary: string;
 }
 
+/** How long to pause the watcher: a number of minutes, or until 8:00 tomorrow */
+export type SnoozeFor = { minutes: number } | 'tomorrow';
+
 // --- API Functions ---
 
 // Rules
@@ -54,7 +182,7 @@ export const getRules = async (): Promise<Rule[]> => {
     return await invoke('get_rules');
 };
 
-export const createRule = async (rule: Omit<Rule, 'id' | 'icon' | 'icon_color'>): Promise<Rule> => {
+export const createRule = async (rule: Omit<Rule, 'id' | 'icon' | 'icon_color' | 'notify' | 'stats'>): Promise<Rule> => {
     return await invoke('create_rule', { ...rule });
 };
 
@@ -62,33 +190,81 @@ export const updateRule = async (rule: Partial<Rule> & { id: string }): Promise<
     return await invoke('update_rule', { ...rule });
 };
 
-export const deleteRule = async (ruleName: string): Promise<void> => {
-    return await invoke('delete_rule', { ruleName });
+export const deleteRule = async (id: string): Promise<void> => {
+    return await invoke('delete_rule', { id });
+};
+
+export const toggleRule = async (id: string, enabled: boolean): Promise<void> => {
+    return await invoke('toggle_rule', { id, enabled });
+};
+
+export const setRuleNotify = async (id: string, notify: boolean): Promise<void> => {
+    return await invoke('set_rule_notify', { id, notify });
+};
+
+export const reorderRules = async (ids: string[]): Promise<RuleWarning[]> => {
+    return await invoke('reorder_rules', { ids });
 };
 
-export const toggleRule = async (ruleName: string, enabled: boolean): Promise<void> => {
-    return await invoke('toggle_rule', { ruleName, enabled });
+export const exportRules = async (names: string[], path: string): Promise<void> => {
+    return await invoke('export_rules', { names, path });
 };
 
-export const reorderRules = async (ruleNames: string[]): Promise<void> => {
-    return await invoke('reorder_rules', { ruleNames });
+export const importRules = async (
+    path: string,
+    onCollision: RuleCollision = 'skip',
+    rebase = false
+): Promise<ImportRulesResult> => {
+    return await invoke('import_rules', { path, onCollision, rebase });
+};
+
+export interface Profiles {
+    active: string;
+    /** Every profile name, sorted, including the active one */
+    profiles: string[];
+}
+
+export const getProfiles = async (): Promise<Profiles> => {
+    return await invoke('get_profiles');
+};
+
+/** Swaps in the rules of another profile; the running watcher picks them up */
+export const setActiveProfile = async (name: string): Promise<Profiles> => {
+    return await invoke('set_active_profile', { name });
 };
 
 export const getDownloadDir = async (): Promise<string> => {
     return await invoke('get_download_dir');
 };
 
+export const explainFile = async (path: string): Promise<Explanation> => {
+    return await invoke('explain_file', { path });
+};
+
 export async function resetToDefaults(): Promise<void> {
     return invoke('reset_to_defaults');
 }
 
 // Activity
-export const getActivityLogs = async (limit?: number, offset?: number): Promise<ActivityLogsResponse> => {
-    return await invoke('get_activity_logs', { limit, offset });
+export const getActivityLogs = async (
+    limit?: number,
+    offset?: number,
+    filters: ActivityFilters = {}
+): Promise<ActivityLogsResponse> => {
+    return await invoke('get_activity_logs', { limit, offset, ...filters });
 };
 
-export const getActivityStats = async (): Promise<ActivityStats> => {
-    return await invoke('get_activity_stats');
+export const getActivityStats = async (days: 30 | 90 = 30): Promise<ActivityStats> => {
+    return await invoke('get_activity_stats', { days });
+};
+
+/** Writes the matching history to `path`; returns the number of records */
+export const exportActivity = async (
+    path: string,
+    format: 'csv' | 'json',
+    filters: Pick<ActivityFilters, 'rule' | 'since' | 'until'> = {}
+): Promise<number> => {
+    return await invoke('export_activity', { path, format, ...filters });
 };
 
 export const clearActivityLogs = async (): Promise<void> => {
@@ -108,10 +284,24 @@ export const stopService = async (): Promise<void> => {
     return await invoke('stop_service');
 };
 
+/** Pauses the watcher; it resumes on its own, even after a restart */
+export const snoozeService = async (duration: SnoozeFor): Promise<void> => {
+    return await invoke('snooze_service', { duration });
+};
+
+export const resumeService = async (): Promise<void> => {
+    return await invoke('resume_service');
+};
+
 export const triggerOrganizeNow = async (): Promise<number> => {
     return await invoke('trigger_organize_now');
 };
 
+/** Organizes dropped files and folders with the current rules; returns the number moved */
+export const organizePaths = async (paths: string[]): Promise<number> => {
+    return await invoke('organize_paths', { paths });
+};
+
 export const getStartupEnabled = async (): Promise<boolean> => {
     return await invoke('get_startup_enabled');
 };
@@ -152,6 +342,28 @@ export const setCheckUpdates = async (enabled: boolean): Promise<void> => {
     return await invoke('set_check_updates', { enabled });
 };
 
+export interface QuietHours {
+    /** HH:MM */
+    start: string;
+    /** HH:MM, may be before start to span midnight */
+    end: string;
+}
+
+export interface NotificationSettings {
+    enabled?: boolean;
+    failures?: boolean;
+    unmatched_after_hours?: number;
+    quiet_hours?: QuietHours;
+}
+
+export const getNotificationSettings = async (): Promise<NotificationSettings> => {
+    return await invoke('get_notification_settings');
+};
+
+export const setNotificationSettings = async (settings: NotificationSettings): Promise<void> => {
+    return await invoke('set_notification_settings', { settings });
+};
+
 export const getLastNotifiedVersion = async (): Promise<string | null> => {
     return await invoke('get_last_notified_version');
 };
@@ -159,3 +371,28 @@ export const getLastNotifiedVersion = async (): Promise<string | null> => {
 export const setLastNotifiedVersion = async (version: string): Promise<void> => {
     return await invoke('set_last_notified_version', { version });
 };
+
+// Events pushed by the watcher; each returns a function that stops listening
+
+export const onMoveCompleted = (handler: (moves: MovedFile[]) => void): Promise<UnlistenFn> =>
+    listen<{ moves: MovedFile[] }>('move-completed', (event) => handler(event.payload.moves));
+
+export const onMoveFailed = (handler: (failures: FailedFile[]) => void): Promise<UnlistenFn> =>
+    listen<{ failures: FailedFile[] }>('move-failed', (event) => handler(event.payload.failures));
+
+export const onServiceStateChanged = (handler: (status: ServiceStatus) => void): Promise<UnlistenFn> =>
+    listen<ServiceStatus>('service-state-changed', (event) => handler(event.payload));
+
+export const onWatcherHealth = (handler: (health: WatcherHealth) => void): Promise<UnlistenFn> =>
+    listen<WatcherHealth>('watcher-health', (event) => handler(event.payload));
+
+/** Fires when the watcher could not save what it did, e.g. to the activity log */
+export const onRecordError = (handler: (message: string) => void): Promise<UnlistenFn> =>
+    listen<string>('record-error', (event) => handler(event.payload));
+
+/** Fires when the config file was reloaded, or with the error when the new version is invalid */
+export const onConfigReloaded = (handler: (error?: string) => void): Promise<UnlistenFn> =>
+    Promise.all([
+        listen('config-reloaded', () => handler()),
+        listen<string>('config-error', (event) => handler(event.payload)),
+    ]).then((unlisteners) => () => unlisteners.forEach((unlisten) => unlisten()));
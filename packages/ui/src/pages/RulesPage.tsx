@@ -23,7 +23,7 @@ const iconColorClassesLight: Record<string, string> = {
 import { getTutorialCompleted, setTutorialCompleted } from '../lib/tauri';
 
 export function RulesPage() {
-  const { rules, loading, error, addRule, editRule, removeRule, toggleRule } = useRules();
+  const { rules, loading, error, addRule, editRule, removeRule, toggleRule, setRuleNotify } = useRules();
   const [isModalOpen, setIsModalOpen] = useState(false);
   const [editingRule, setEditingRule] = useState<Rule | null>(null);
   const [searchTerm, setSearchTerm] = useState('');
@@ -46,11 +46,11 @@ export function RulesPage() {
     checkTutorial();
   }, []);
 
-  const handleCreate = async (ruleData: Omit<Rule, 'id' | 'icon' | 'icon_color'>) => {
+  const handleCreate = async (ruleData: Omit<Rule, 'id' | 'icon' | 'icon_color' | 'notify' | 'stats'>) => {
     await addRule(ruleData);
   };
 
-  const handleUpdate = async (ruleData: Omit<Rule, 'id' | 'icon' | 'icon_color'>) => {
+  const handleUpdate = async (ruleData: Omit<Rule, 'id' | 'icon' | 'icon_color' | 'notify' | 'stats'>) => {
     if (!editingRule) return;
     await editRule({ ...ruleData, id: editingRule.id });
   };
@@ -219,6 +219,15 @@ export function RulesPage() {
                       </td>
                       <td className="px-6 py-5 text-center">
                         <div className="flex justify-center gap-2">
+                          <button
+                            onClick={() => setRuleNotify(rule.id, !rule.notify)}
+                            title={rule.notify ? 'Stop notifying about moves' : 'Notify me about moves'}
+                            className={`p-1.5 rounded-md transition-colors cursor-pointer ${rule.notify ? 'text-primary hover:bg-primary/10' : 'text-slate-400 dark:text-slate-500 hover:text-primary hover:bg-primary/10'}`}
+                          >
+                            <span className="material-icons-round text-xl">
+                              {rule.notify ? 'notifications_active' : 'notifications_none'}
+                            </span>
+                          </button>
                           <button
                             onClick={() => openEditModal(rule)}
                             className="p-1.5 text-slate-400 dark:text-slate-500 hover:text-primary hover:bg-primary/10 rounded-md transition-colors cursor-pointer"
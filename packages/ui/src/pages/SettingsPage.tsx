@@ -2,6 +2,7 @@ import { useTheme } from '../context/ThemeContext';
 import { Header } from '../components/Header';
 import { useSettings } from '../hooks/useSettings';
 import { useUpdateCheck } from '../hooks/useUpdateCheck';
+import { useNotificationSettings } from '../hooks/useNotificationSettings';
 import { useState } from 'react';
 import { ConfirmationModal } from '../components/ConfirmationModal';
 import { useWindowSize } from '../hooks/useWindowSize';
@@ -15,6 +16,8 @@ export function SettingsPage() {
     loading,
     error,
     toggleService,
+    snooze,
+    resume,
     toggleStartup,
     reload,
     reset,
@@ -35,6 +38,12 @@ export function SettingsPage() {
     url: updateUrl
   } = updateState;
 
+  const {
+    settings: notifications,
+    error: notificationsError,
+    update: updateNotifications,
+  } = useNotificationSettings();
+
   const [showResetModal, setShowResetModal] = useState(false);
   const [feedbackMessage, setFeedbackMessage] = useState<string | null>(null);
 
@@ -71,9 +80,9 @@ export function SettingsPage() {
               <div className="p-6 md:p-8 flex flex-col md:flex-row md:items-center justify-between gap-6">
                 <div className="flex-1">
                   <div className="flex items-center space-x-2 mb-2">
-                    <span className={`inline-block w-2.5 h-2.5 rounded-full ${serviceEnabled ? 'bg-emerald-500' : 'bg-slate-400'}`}></span>
-                    <span className={`text-xs font-bold uppercase tracking-widest ${serviceEnabled ? 'text-emerald-600' : 'text-slate-500'}`}>
-                      {loading ? 'Checking...' : (serviceEnabled ? 'Service is Running' : 'Service is Stopped')}
+                    <span className={`inline-block w-2.5 h-2.5 rounded-full ${serviceStatus.paused ? 'bg-amber-500' : serviceEnabled ? 'bg-emerald-500' : 'bg-slate-400'}`}></span>
+                    <span className={`text-xs font-bold uppercase tracking-widest ${serviceStatus.paused ? 'text-amber-600' : serviceEnabled ? 'text-emerald-600' : 'text-slate-500'}`}>
+                      {loading ? 'Checking...' : (serviceStatus.paused ? serviceStatus.summary : serviceEnabled ? 'Service is Running' : 'Service is Stopped')}
                     </span>
                   </div>
                   <h3 className="text-xl font-bold text-slate-800 dark:text-white">Service Status</h3>
@@ -98,6 +107,28 @@ export function SettingsPage() {
                 <span className="text-xs text-slate-500 uppercase tracking-tight font-semibold">
                   Process ID: <span className="text-slate-800 dark:text-white font-mono ml-1">Native</span>
                 </span>
+                {serviceEnabled && (
+                  <div className="flex items-center gap-2 text-xs font-semibold">
+                    {serviceStatus.paused ? (
+                      <button onClick={resume} className="px-2 py-1 rounded-md text-primary hover:bg-primary/10 cursor-pointer">
+                        Resume
+                      </button>
+                    ) : (
+                      <>
+                        <span className="text-slate-500 uppercase tracking-tight">Pause:</span>
+                        <button onClick={() => snooze({ minutes: 15 })} className="px-2 py-1 rounded-md text-primary hover:bg-primary/10 cursor-pointer">
+                          15 min
+                        </button>
+                        <button onClick={() => snooze({ minutes: 60 })} className="px-2 py-1 rounded-md text-primary hover:bg-primary/10 cursor-pointer">
+                          1 hour
+                        </button>
+                        <button onClick={() => snooze('tomorrow')} className="px-2 py-1 rounded-md text-primary hover:bg-primary/10 cursor-pointer">
+                          Until tomorrow
+                        </button>
+                      </>
+                    )}
+                  </div>
+                )}
                 <span className="text-xs text-slate-500 uppercase tracking-tight font-semibold">
                   Uptime: <span className="text-slate-800 dark:text-white font-mono ml-1">{serviceStatus.uptime_seconds ? `${Math.floor(serviceStatus.uptime_seconds / 60)}m` : 'N/A'}</span>
                 </span>
@@ -232,6 +263,115 @@ export function SettingsPage() {
               </div>
             </section>
 
+            {/* Notifications */}
+            <section>
+              <h3 className="text-lg font-bold text-slate-800 dark:text-white mb-4 flex items-center">
+                <span className="material-icons-round mr-2 text-primary">notifications</span>
+                Notifications
+              </h3>
+              {notificationsError && (
+                <p className="mb-4 text-sm text-red-500">{notificationsError}</p>
+              )}
+              <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+                <div className="flex items-center justify-between p-4 bg-white dark:bg-slate-900 rounded-lg border border-slate-200 dark:border-slate-800">
+                  <div>
+                    <p className="text-sm font-semibold text-slate-800 dark:text-white">Desktop Notifications</p>
+                    <p className="text-xs text-slate-500">Moves are shown for rules with the bell turned on.</p>
+                  </div>
+                  <label className="relative inline-flex items-center cursor-pointer">
+                    <input
+                      type="checkbox"
+                      className="sr-only peer"
+                      checked={notifications.enabled ?? true}
+                      onChange={(e) => updateNotifications({ enabled: e.target.checked })}
+                    />
+                    <div className="w-9 h-5 bg-slate-200 dark:bg-slate-700 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-slate-300 after:border after:rounded-full after:h-4 after:w-4 after:transition-all peer-checked:bg-primary"></div>
+                  </label>
+                </div>
+
+                <div className="flex items-center justify-between p-4 bg-white dark:bg-slate-900 rounded-lg border border-slate-200 dark:border-slate-800">
+                  <div>
+                    <p className="text-sm font-semibold text-slate-800 dark:text-white">Failed Moves</p>
+                    <p className="text-xs text-slate-500">Tell me when a file could not be moved.</p>
+                  </div>
+                  <label className="relative inline-flex items-center cursor-pointer">
+                    <input
+                      type="checkbox"
+                      className="sr-only peer"
+                      checked={notifications.failures ?? true}
+                      onChange={(e) => updateNotifications({ failures: e.target.checked })}
+                    />
+                    <div className="w-9 h-5 bg-slate-200 dark:bg-slate-700 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-slate-300 after:border after:rounded-full after:h-4 after:w-4 after:transition-all peer-checked:bg-primary"></div>
+                  </label>
+                </div>
+
+                <div className="flex items-center justify-between p-4 bg-white dark:bg-slate-900 rounded-lg border border-slate-200 dark:border-slate-800">
+                  <div>
+                    <p className="text-sm font-semibold text-slate-800 dark:text-white">Unmatched Files</p>
+                    <p className="text-xs text-slate-500">Hours before a file no rule matches is reported. Empty turns this off.</p>
+                  </div>
+                  <input
+                    type="number"
+                    min={1}
+                    className="w-20 px-2 py-1 text-sm rounded-md border border-slate-200 dark:border-slate-700 bg-white dark:bg-slate-800 text-slate-800 dark:text-white"
+                    value={notifications.unmatched_after_hours ?? ''}
+                    onChange={(e) =>
+                      updateNotifications({
+                        unmatched_after_hours: e.target.value ? Number(e.target.value) : undefined,
+                      })
+                    }
+                  />
+                </div>
+
+                <div className="flex items-center justify-between p-4 bg-white dark:bg-slate-900 rounded-lg border border-slate-200 dark:border-slate-800">
+                  <div>
+                    <p className="text-sm font-semibold text-slate-800 dark:text-white">Quiet Hours</p>
+                    <p className="text-xs text-slate-500">No notifications during this time.</p>
+                  </div>
+                  <div className="flex items-center gap-2">
+                    {notifications.quiet_hours && (
+                      <>
+                        <input
+                          type="time"
+                          className="px-2 py-1 text-sm rounded-md border border-slate-200 dark:border-slate-700 bg-white dark:bg-slate-800 text-slate-800 dark:text-white"
+                          value={notifications.quiet_hours.start}
+                          onChange={(e) =>
+                            updateNotifications({
+                              quiet_hours: { ...notifications.quiet_hours!, start: e.target.value },
+                            })
+                          }
+                        />
+                        <span className="text-slate-500">–</span>
+                        <input
+                          type="time"
+                          className="px-2 py-1 text-sm rounded-md border border-slate-200 dark:border-slate-700 bg-white dark:bg-slate-800 text-slate-800 dark:text-white"
+                          value={notifications.quiet_hours.end}
+                          onChange={(e) =>
+                            updateNotifications({
+                              quiet_hours: { ...notifications.quiet_hours!, end: e.target.value },
+                            })
+                          }
+                        />
+                      </>
+                    )}
+                    <label className="relative inline-flex items-center cursor-pointer">
+                      <input
+                        type="checkbox"
+                        className="sr-only peer"
+                        checked={!!notifications.quiet_hours}
+                        onChange={(e) =>
+                          updateNotifications({
+                            quiet_hours: e.target.checked ? { start: '22:00', end: '07:00' } : undefined,
+                          })
+                        }
+                      />
+                      <div className="w-9 h-5 bg-slate-200 dark:bg-slate-700 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-slate-300 after:border after:rounded-full after:h-4 after:w-4 after:transition-all peer-checked:bg-primary"></div>
+                    </label>
+                  </div>
+                </div>
+              </div>
+            </section>
+
             {/* Updates & Maintenance */}
             <section>
               <h3 className="text-lg font-bold text-slate-800 dark:text-white mb-4 flex items-center">